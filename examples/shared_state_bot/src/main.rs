@@ -2,13 +2,8 @@
 
 use std::sync::atomic::{AtomicU64, Ordering};
 
-use lazy_static::lazy_static;
 use teloxide::prelude::*;
 
-lazy_static! {
-    static ref MESSAGES_TOTAL: AtomicU64 = AtomicU64::new(0);
-}
-
 #[tokio::main]
 async fn main() {
     run().await;
@@ -21,18 +16,23 @@ async fn run() {
     let bot = Bot::from_env();
 
     Dispatcher::new(bot)
+        .dependency(AtomicU64::new(0))
         .messages_handler(|rx: DispatcherHandlerRx<Message>| {
-            rx.for_each_concurrent(None, |message| async move {
-                let previous = MESSAGES_TOTAL.fetch_add(1, Ordering::Relaxed);
-
-                message
-                    .answer_str(format!(
-                        "I received {} messages in total.",
-                        previous
-                    ))
-                    .await
-                    .log_on_error()
-                    .await;
+            let messages_total = rx.dependencies().require::<AtomicU64>();
+
+            rx.for_each_concurrent(None, move |message| {
+                let previous = messages_total.fetch_add(1, Ordering::Relaxed);
+
+                async move {
+                    message
+                        .answer_str(format!(
+                            "I received {} messages in total.",
+                            previous
+                        ))
+                        .await
+                        .log_on_error()
+                        .await;
+                }
             })
         })
         .dispatch()
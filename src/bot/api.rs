@@ -7,7 +7,7 @@ use crate::{
         EditMessageLiveLocation, EditMessageMedia, EditMessageReplyMarkup,
         EditMessageText, ExportChatInviteLink, ForwardMessage, GetChat,
         GetChatAdministrators, GetChatMember, GetChatMembersCount, GetFile,
-        GetGameHighScores, GetMe, GetStickerSet, GetUpdates,
+        GetGameHighScores, GetMe, GetMyCommands, GetStickerSet, GetUpdates,
         GetUserProfilePhotos, GetWebhookInfo, KickChatMember, LeaveChat,
         PinChatMessage, PromoteChatMember, RestrictChatMember, SendAnimation,
         SendAudio, SendChatAction, SendChatActionKind, SendContact,
@@ -15,15 +15,16 @@ use crate::{
         SendMessage, SendPhoto, SendPoll, SendSticker, SendVenue, SendVideo,
         SendVideoNote, SendVoice, SetChatAdministratorCustomTitle,
         SetChatDescription, SetChatPermissions, SetChatPhoto,
-        SetChatStickerSet, SetChatTitle, SetGameScore, SetStickerPositionInSet,
-        SetWebhook, StopMessageLiveLocation, StopPoll, UnbanChatMember,
+        SetChatStickerSet, SetChatTitle, SetGameScore, SetMyCommands,
+        SetStickerPositionInSet, SetWebhook, StopMessageLiveLocation,
+        StopPoll, UnbanChatMember,
         UnpinChatMessage, UploadStickerFile,SetGuildCredit, DeleteGuildCredit, GetGuildCredit,
         GetPrivateChat, GetGuildRoles, GetGuildMembers, SearchGuildMember, SetMemberRoles,
         GetRoleMembers, SearchGuildMemberByUsername, GetMessage,
     },
     types::{
-        ChatId, ChatOrInlineMessage, ChatPermissions, InlineQueryResult,
-        InputFile, InputMedia, LabeledPrice,GuildCredit
+        BotCommand, ChatId, ChatOrInlineMessage, ChatPermissions,
+        InlineQueryResult, InputFile, InputMedia, LabeledPrice,GuildCredit
     },
     Bot,
 };
@@ -102,6 +103,31 @@ impl Bot {
         GetMe::new(Arc::clone(self))
     }
 
+    /// Use this method to change the list of the bot's commands, shown in the
+    /// command menu of clients.
+    ///
+    /// [The official docs](https://core.telegram.org/bots/api#setmycommands).
+    ///
+    /// # Params
+    ///   - `commands`: A list of bot commands to be set, at most 100 commands
+    ///     can be specified. See [`BotCommand::bot_commands`] to keep it in
+    ///     sync with your commands `enum`.
+    ///
+    /// [`BotCommand::bot_commands`]: crate::utils::command::BotCommand::bot_commands
+    pub fn set_my_commands<C>(self: &Arc<Bot>, commands: C) -> SetMyCommands
+    where
+        C: Into<Vec<BotCommand>>,
+    {
+        SetMyCommands::new(Arc::clone(self), commands)
+    }
+
+    /// Use this method to get the current list of the bot's commands.
+    ///
+    /// [The official docs](https://core.telegram.org/bots/api#getmycommands).
+    pub fn get_my_commands(self: &Arc<Bot>) -> GetMyCommands {
+        GetMyCommands::new(Arc::clone(self))
+    }
+
     /// Use this method to send text messages.
     ///
     /// [The official docs](https://core.telegram.org/bots/api#sendmessage).
@@ -1,11 +1,15 @@
-use tokio::io::AsyncWrite;
+use std::{path::Path, sync::Arc};
 
-#[cfg(feature = "unstable-stream")]
-use ::{bytes::Bytes, tokio::stream::Stream};
+use bytes::Bytes;
+use tokio::{io::AsyncWrite, stream::Stream};
 
-#[cfg(feature = "unstable-stream")]
-use crate::net::download_file_stream;
-use crate::{bot::Bot, net::download_file, DownloadError};
+use crate::{
+    bot::Bot,
+    net::{download_file, download_file_stream, FileDownload},
+    requests::Request,
+    types::File,
+    DownloadError,
+};
 
 impl Bot {
     /// Download a file from Telegram into `destination`.
@@ -13,6 +17,7 @@ impl Bot {
     /// `path` can be obtained from [`Bot::get_file`].
     ///
     /// To download as a stream of chunks, see [`Bot::download_file_stream`].
+    /// To resume a download or verify the file, see [`Bot::download`].
     ///
     /// ## Examples
     ///
@@ -33,6 +38,7 @@ impl Bot {
     ///
     /// [`Bot::get_file`]: crate::Bot::get_file
     /// [`Bot::download_file_stream`]: crate::Bot::download_file_stream
+    /// [`Bot::download`]: crate::Bot::download
     pub async fn download_file<D>(
         &self,
         path: &str,
@@ -41,7 +47,14 @@ impl Bot {
     where
         D: AsyncWrite + Unpin,
     {
-        download_file(&self.client, &self.token, path, destination).await
+        download_file(
+            &self.client,
+            &self.token,
+            &self.api_url,
+            path,
+            destination,
+        )
+        .await
     }
 
     /// Download a file from Telegram.
@@ -55,12 +68,53 @@ impl Bot {
     /// [`AsyncWrite`]: tokio::io::AsyncWrite
     /// [`tokio::fs::File`]: tokio::fs::File
     /// [`Bot::download_file`]: crate::Bot::download_file
-    #[cfg(feature = "unstable-stream")]
     pub async fn download_file_stream(
         &self,
         path: &str,
     ) -> Result<impl Stream<Item = Result<Bytes, reqwest::Error>>, reqwest::Error>
     {
-        download_file_stream(&self.client, &self.token, path).await
+        download_file_stream(&self.client, &self.token, &self.api_url, path)
+            .await
+    }
+
+    /// Prepares a download of a file from Telegram, which can be resumed,
+    /// limited in size and verified.
+    ///
+    /// `path` can be obtained from the [`Bot::get_file`].
+    ///
+    /// [`Bot::get_file`]: crate::bot::Bot::get_file
+    pub fn download(&self, path: &str) -> FileDownload {
+        FileDownload::new(&self.client, &self.token, &self.api_url, path)
+    }
+
+    /// Downloads the file with the identifier `file_id` into the file at
+    /// `destination`.
+    ///
+    /// The file is resolved by [`Bot::get_file`] first, and its size is
+    /// verified if known. Returns the resolved file.
+    ///
+    /// [`Bot::get_file`]: crate::bot::Bot::get_file
+    pub async fn download_to_path<F, P>(
+        self: &Arc<Bot>,
+        file_id: F,
+        destination: P,
+    ) -> Result<File, DownloadError>
+    where
+        F: Into<String>,
+        P: AsRef<Path>,
+    {
+        let file = self
+            .get_file(file_id)
+            .send()
+            .await
+            .map_err(DownloadError::Request)?;
+
+        let mut download = self.download(&file.file_path);
+        if file.file_size > 0 {
+            download = download.expected_size(file.file_size.into());
+        }
+        download.to_path(destination).await?;
+
+        Ok(file)
     }
 }
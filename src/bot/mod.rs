@@ -1,20 +1,51 @@
 use reqwest::Client;
 use std::sync::Arc;
 
+use crate::{
+    net::{
+        self, Layered, ReqwestTransport, SharedTransport, Transport,
+        TransportMiddleware,
+    },
+    requests::{ProgressCallback, UploadCache, UploadProgress},
+};
+
 mod api;
 mod download;
 
 /// A Telegram bot used to send requests.
-#[derive(Default, Debug, Clone)]
+#[derive(Debug, Clone)]
 pub struct Bot {
     token: String,
     client: Client,
+    transport: SharedTransport,
+    api_url: String,
+    upload_progress: Option<ProgressCallback>,
+    upload_cache: Option<UploadCache>,
+}
+
+impl Default for Bot {
+    fn default() -> Self {
+        let client = Client::new();
+        Self {
+            token: String::new(),
+            transport: SharedTransport(Arc::new(ReqwestTransport::new(
+                client.clone(),
+            ))),
+            client,
+            api_url: net::TELEGRAM_API_URL.to_owned(),
+            upload_progress: None,
+            upload_cache: None,
+        }
+    }
 }
 
 impl Bot {
     /// Creates a new `Bot` with the `TELOXIDE_TOKEN` environmental variable (a
     /// bot's token) and the default [`reqwest::Client`].
     ///
+    /// If the `TELEGRAM_API_URL` environmental variable is set, the bot sends
+    /// requests to it instead of the default Bot API server.
+    ///
     /// # Panics
     /// If cannot get the `TELOXIDE_TOKEN` environmental variable.
     ///
@@ -26,16 +57,24 @@ impl Bot {
     /// Creates a new `Bot` with the `TELOXIDE_TOKEN` environmental variable (a
     /// bot's token) and your [`reqwest::Client`].
     ///
+    /// If the `TELEGRAM_API_URL` environmental variable is set, the bot sends
+    /// requests to it instead of the default Bot API server.
+    ///
     /// # Panics
     /// If cannot get the `TELOXIDE_TOKEN` environmental variable.
     ///
     /// [`reqwest::Client`]: https://docs.rs/reqwest/0.10.1/reqwest/struct.Client.html
     pub fn from_env_with_client(client: Client) -> Arc<Self> {
-        Self::with_client(
+        let bot = Self::with_client(
             &std::env::var("TELOXIDE_TOKEN")
                 .expect("Cannot get the TELOXIDE_TOKEN env variable"),
             client,
-        )
+        );
+
+        match std::env::var("TELEGRAM_API_URL") {
+            Ok(api_url) => bot.with_api_url(api_url),
+            Err(_) => bot,
+        }
     }
 
     /// Creates a new `Bot` with the specified token and the default
@@ -57,7 +96,132 @@ impl Bot {
     where
         S: Into<String>,
     {
-        Arc::new(Self { token: token.into(), client })
+        Arc::new(Self {
+            token: token.into(),
+            transport: SharedTransport(Arc::new(ReqwestTransport::new(
+                client.clone(),
+            ))),
+            client,
+            api_url: net::TELEGRAM_API_URL.to_owned(),
+            upload_progress: None,
+            upload_cache: None,
+        })
+    }
+
+    /// Returns a `Bot` which sends requests and downloads files from the Bot
+    /// API server at `api_url` (e.g. `https://api.telegram.org`) instead of
+    /// the default one.
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// use teloxide::Bot;
+    ///
+    /// let bot = Bot::new("TOKEN").with_api_url("http://localhost:8081");
+    /// assert_eq!(bot.api_url(), "http://localhost:8081");
+    /// ```
+    pub fn with_api_url<U>(self: Arc<Self>, api_url: U) -> Arc<Self>
+    where
+        U: Into<String>,
+    {
+        let mut bot = Arc::try_unwrap(self).unwrap_or_else(|bot| (*bot).clone());
+        bot.api_url = api_url.into();
+        Arc::new(bot)
+    }
+
+    /// Returns a `Bot` which reports the progress of uploading files to
+    /// `callback`.
+    ///
+    /// The callback is called each time a chunk of a file is passed to the
+    /// connection.
+    ///
+    /// ## Examples
+    ///
+    /// ```no_run
+    /// use teloxide::{prelude::*, types::InputFile};
+    ///
+    /// # async fn upload(bot: std::sync::Arc<Bot>) -> ResponseResult<()> {
+    /// bot.with_upload_progress(|progress| {
+    ///     log::info!("{}: {} bytes sent", progress.file_name, progress.sent);
+    /// })
+    /// .send_document(42, InputFile::file("report.pdf"))
+    /// .send()
+    /// .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn with_upload_progress<F>(self: Arc<Self>, callback: F) -> Arc<Self>
+    where
+        F: Fn(&UploadProgress) + Send + Sync + 'static,
+    {
+        let mut bot = Arc::try_unwrap(self).unwrap_or_else(|bot| (*bot).clone());
+        bot.upload_progress = Some(ProgressCallback(Arc::new(callback)));
+        Arc::new(bot)
+    }
+
+    /// Returns a `Bot` which stores `file_id`s of uploaded files in `cache`
+    /// and sends them instead of uploading the same files again.
+    ///
+    /// See [`UploadCache`] for the requests which use the cache.
+    ///
+    /// [`UploadCache`]: crate::requests::UploadCache
+    pub fn with_upload_cache(self: Arc<Self>, cache: UploadCache) -> Arc<Self> {
+        let mut bot = Arc::try_unwrap(self).unwrap_or_else(|bot| (*bot).clone());
+        bot.upload_cache = Some(cache);
+        Arc::new(bot)
+    }
+
+    /// Returns a `Bot` which sends requests by `transport` instead of its
+    /// [`reqwest::Client`].
+    ///
+    /// Files are still downloaded by the [`reqwest::Client`].
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// use teloxide::{prelude::*, testing::{InMemoryTransport, MockResponse}};
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// let transport = InMemoryTransport::new();
+    /// transport.respond("leaveChat", MockResponse::ok(true));
+    ///
+    /// let bot = Bot::new("TOKEN").with_transport(transport.clone());
+    /// bot.leave_chat(123).send().await.unwrap();
+    ///
+    /// assert_eq!(transport.calls_to("leaveChat").len(), 1);
+    /// # }
+    /// ```
+    ///
+    /// [`reqwest::Client`]: https://docs.rs/reqwest/0.10.1/reqwest/struct.Client.html
+    pub fn with_transport<T>(self: Arc<Self>, transport: T) -> Arc<Self>
+    where
+        T: Transport + 'static,
+    {
+        let mut bot = Arc::try_unwrap(self).unwrap_or_else(|bot| (*bot).clone());
+        bot.transport = SharedTransport(Arc::new(transport));
+        Arc::new(bot)
+    }
+
+    /// Returns a `Bot` which passes requests through `middleware` before
+    /// sending them by the current transport.
+    ///
+    /// Middlewares added later handle requests earlier.
+    ///
+    /// See [`TransportMiddleware`] for an example.
+    ///
+    /// [`TransportMiddleware`]: crate::net::TransportMiddleware
+    pub fn with_transport_middleware<M>(
+        self: Arc<Self>,
+        middleware: M,
+    ) -> Arc<Self>
+    where
+        M: TransportMiddleware + 'static,
+    {
+        let mut bot = Arc::try_unwrap(self).unwrap_or_else(|bot| (*bot).clone());
+        let next = Arc::clone(&bot.transport.0);
+        bot.transport = SharedTransport(Arc::new(Layered { middleware, next }));
+        Arc::new(bot)
     }
 }
 
@@ -73,4 +237,21 @@ impl Bot {
     pub fn client(&self) -> &Client {
         &self.client
     }
+
+    /// The URL of the Bot API server this bot sends requests to.
+    pub fn api_url(&self) -> &str {
+        &self.api_url
+    }
+
+    pub(crate) fn upload_progress(&self) -> Option<ProgressCallback> {
+        self.upload_progress.clone()
+    }
+
+    pub(crate) fn upload_cache(&self) -> Option<&UploadCache> {
+        self.upload_cache.as_ref()
+    }
+
+    pub(crate) fn transport(&self) -> &dyn Transport {
+        &*self.transport.0
+    }
 }
@@ -0,0 +1,216 @@
+use std::{
+    any::{type_name, Any, TypeId},
+    collections::HashMap,
+    fmt::{self, Debug},
+    sync::Arc,
+};
+
+/// A container of values shared by handlers, such as database pools,
+/// configuration or caches, looked up by their types.
+///
+/// Values are added to a [`Dispatcher`] by [`Dispatcher::dependency`] and
+/// obtained by its handlers from [`DispatcherHandlerRx::dependencies`]:
+///
+/// ```no_run
+/// use std::sync::atomic::{AtomicU64, Ordering};
+/// use teloxide::prelude::*;
+///
+/// # #[tokio::main]
+/// # async fn main_() {
+/// Dispatcher::new(Bot::from_env())
+///     .dependency(AtomicU64::new(0))
+///     .messages_handler(|rx: DispatcherHandlerRx<Message>| {
+///         let total = rx.dependencies().require::<AtomicU64>();
+///
+///         rx.for_each(move |message| {
+///             let previous = total.fetch_add(1, Ordering::Relaxed);
+///             async move {
+///                 message
+///                     .answer_str(format!("{} messages so far", previous))
+///                     .await
+///                     .log_on_error()
+///                     .await;
+///             }
+///         })
+///     })
+///     .dispatch()
+///     .await;
+/// # }
+/// ```
+///
+/// In tests, a handler can be given fakes instead, by
+/// [`DispatcherHandlerRx::with_dependencies`]. To make a dependency
+/// replaceable by a fake, add it as a trait object with
+/// [`Dependencies::insert_arc`] and request it as such:
+///
+/// ```
+/// use std::sync::Arc;
+/// use teloxide::dispatching::Dependencies;
+///
+/// trait Storage: Send + Sync {
+///     fn get(&self, key: &str) -> Option<String>;
+/// }
+///
+/// struct FakeStorage;
+///
+/// impl Storage for FakeStorage {
+///     fn get(&self, _: &str) -> Option<String> {
+///         Some("fake".to_owned())
+///     }
+/// }
+///
+/// let mut dependencies = Dependencies::new();
+/// dependencies.insert_arc::<dyn Storage>(Arc::new(FakeStorage));
+///
+/// let storage = dependencies.require::<dyn Storage>();
+/// assert_eq!(storage.get("key").as_deref(), Some("fake"));
+/// ```
+///
+/// [`Dispatcher`]: crate::dispatching::Dispatcher
+/// [`Dispatcher::dependency`]: crate::dispatching::Dispatcher::dependency
+/// [`DispatcherHandlerRx::dependencies`]: crate::dispatching::DispatcherHandlerRx::dependencies
+/// [`DispatcherHandlerRx::with_dependencies`]: crate::dispatching::DispatcherHandlerRx::with_dependencies
+/// [`Dependencies::insert_arc`]: crate::dispatching::Dependencies::insert_arc
+#[derive(Clone, Default)]
+pub struct Dependencies {
+    // Values of the type `T` are stored as `Arc<T>`, so that `T` can be
+    // unsized.
+    values: HashMap<TypeId, Arc<dyn Any + Send + Sync>>,
+    names: Vec<&'static str>,
+}
+
+impl Dependencies {
+    /// Creates an empty container.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds `value`, replacing a value of the same type, if any.
+    pub fn insert<T>(&mut self, value: T)
+    where
+        T: Send + Sync + 'static,
+    {
+        self.insert_arc(Arc::new(value));
+    }
+
+    /// Adds a shared `value`, replacing a value of the same type, if any.
+    ///
+    /// Unlike [`Dependencies::insert`], `T` can be a trait object.
+    ///
+    /// [`Dependencies::insert`]: crate::dispatching::Dependencies::insert
+    pub fn insert_arc<T>(&mut self, value: Arc<T>)
+    where
+        T: ?Sized + Send + Sync + 'static,
+    {
+        if self.values.insert(TypeId::of::<T>(), Arc::new(value)).is_none() {
+            self.names.push(type_name::<T>());
+        }
+    }
+
+    /// Adds `value` like [`Dependencies::insert`] and returns the container.
+    ///
+    /// [`Dependencies::insert`]: crate::dispatching::Dependencies::insert
+    #[must_use]
+    pub fn with<T>(mut self, value: T) -> Self
+    where
+        T: Send + Sync + 'static,
+    {
+        self.insert(value);
+        self
+    }
+
+    /// Adds `value` like [`Dependencies::insert_arc`] and returns the
+    /// container.
+    ///
+    /// [`Dependencies::insert_arc`]: crate::dispatching::Dependencies::insert_arc
+    #[must_use]
+    pub fn with_arc<T>(mut self, value: Arc<T>) -> Self
+    where
+        T: ?Sized + Send + Sync + 'static,
+    {
+        self.insert_arc(value);
+        self
+    }
+
+    /// Returns the value of the type `T`, if any.
+    #[must_use]
+    pub fn get<T>(&self) -> Option<Arc<T>>
+    where
+        T: ?Sized + Send + Sync + 'static,
+    {
+        self.values
+            .get(&TypeId::of::<T>())
+            .and_then(|value| value.downcast_ref::<Arc<T>>())
+            .cloned()
+    }
+
+    /// Returns the value of the type `T`.
+    ///
+    /// # Panics
+    /// If there's no value of the type `T`.
+    #[must_use]
+    pub fn require<T>(&self) -> Arc<T>
+    where
+        T: ?Sized + Send + Sync + 'static,
+    {
+        self.get().unwrap_or_else(|| {
+            panic!(
+                "No dependency of the type `{}` (the dependencies are: {:?})",
+                type_name::<T>(),
+                self.names
+            )
+        })
+    }
+}
+
+impl Debug for Dependencies {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_set().entries(&self.names).finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    trait Greeter: Send + Sync {
+        fn greet(&self) -> String;
+    }
+
+    struct English;
+
+    impl Greeter for English {
+        fn greet(&self) -> String {
+            "Hello".to_owned()
+        }
+    }
+
+    #[test]
+    fn get_by_type() {
+        let dependencies = Dependencies::new()
+            .with(42_u32)
+            .with("config".to_owned())
+            .with_arc::<dyn Greeter>(Arc::new(English));
+
+        assert_eq!(*dependencies.require::<u32>(), 42);
+        assert_eq!(*dependencies.require::<String>(), "config");
+        assert_eq!(dependencies.require::<dyn Greeter>().greet(), "Hello");
+        assert!(dependencies.get::<u64>().is_none());
+    }
+
+    #[test]
+    fn replace() {
+        let mut dependencies = Dependencies::new().with(1_u32);
+        dependencies.insert(2_u32);
+
+        assert_eq!(*dependencies.require::<u32>(), 2);
+        assert_eq!(format!("{:?}", dependencies), r#"{"u32"}"#);
+    }
+
+    #[test]
+    #[should_panic(expected = "No dependency of the type `u64`")]
+    fn require_missing() {
+        let _ = Dependencies::new().with(1_u32).require::<u64>();
+    }
+}
@@ -3,11 +3,11 @@ use crate::dispatching::{
         DialogueDispatcherHandler, DialogueStage, DialogueWithCx, GetChatId,
         InMemStorage, Storage,
     },
-    DispatcherHandler, UpdateWithCx,
+    DispatcherHandler, DispatcherHandlerRx, UpdateWithCx,
 };
-use std::{convert::Infallible, marker::PhantomData};
+use std::{convert::Infallible, marker::PhantomData, panic::AssertUnwindSafe};
 
-use futures::{future::BoxFuture, StreamExt};
+use futures::{future::BoxFuture, FutureExt, StreamExt};
 use tokio::sync::mpsc;
 
 use lockfree::map::Map;
@@ -21,8 +21,14 @@ use std::sync::{Arc, Mutex};
 /// See [the module-level documentation for the design
 /// overview](crate::dispatching::dialogue).
 ///
+/// ## Panics in handlers
+/// A panic in the handler is caught, logged along with the identifier of the
+/// update, and counted by [`Dispatcher::panic_counter`]. The dialogue of the
+/// chat starts over, while other chats aren't affected.
+///
 /// [`Dispatcher`]: crate::dispatching::Dispatcher
 /// [`DispatcherHandler`]: crate::dispatching::DispatcherHandler
+/// [`Dispatcher::panic_counter`]: crate::dispatching::Dispatcher::panic_counter
 pub struct DialogueDispatcher<D, S, H, Upd> {
     storage: Arc<S>,
     handler: Arc<H>,
@@ -31,12 +37,15 @@ pub struct DialogueDispatcher<D, S, H, Upd> {
     /// A lock-free map to handle updates from the same chat sequentially, but
     /// concurrently from different chats.
     ///
-    /// A value is the TX part of an unbounded asynchronous MPSC channel. A
-    /// handler that executes updates from the same chat ID sequentially
-    /// handles the RX part.
-    senders: Arc<Map<i64, mpsc::UnboundedSender<UpdateWithCx<Upd>>>>,
+    /// A value is the TX part of an unbounded asynchronous MPSC channel of
+    /// updates along with their identifiers. A handler that executes updates
+    /// from the same chat ID sequentially handles the RX part.
+    senders: Arc<Map<i64, UpdateSender<Upd>>>,
 }
 
+type UpdateSender<Upd> =
+    mpsc::UnboundedSender<(Option<i64>, UpdateWithCx<Upd>)>;
+
 impl<D, H, Upd> DialogueDispatcher<D, InMemStorage<D>, H, Upd>
 where
     H: DialogueDispatcherHandler<Upd, D, Infallible> + Send + Sync + 'static,
@@ -77,20 +86,23 @@ where
         }
     }
 
+    /// Spawns a handler of updates from one chat, which reports its panics to
+    /// `queue`.
     #[must_use]
-    fn new_tx(&self) -> mpsc::UnboundedSender<UpdateWithCx<Upd>> {
-        let (tx, rx) = mpsc::unbounded_channel();
+    fn new_tx(&self, queue: DispatcherHandlerRx<Upd>) -> UpdateSender<Upd> {
+        let (tx, rx): (UpdateSender<Upd>, _) = mpsc::unbounded_channel();
 
         let storage = Arc::clone(&self.storage);
         let handler = Arc::clone(&self.handler);
         let senders = Arc::clone(&self.senders);
 
-        tokio::spawn(rx.for_each(move |cx: UpdateWithCx<Upd>| {
+        tokio::spawn(rx.for_each(move |(update_id, cx)| {
             let storage = Arc::clone(&storage);
             let handler = Arc::clone(&handler);
             let senders = Arc::clone(&senders);
+            let queue = queue.reopen();
 
-            async move {
+            let handle = async move {
                 let chat_id = cx.update.chat_id();
 
                 let dialogue = Arc::clone(&storage)
@@ -120,7 +132,15 @@ where
                         // the beginning of this async block).
                     }
                 }
-            }
+            };
+
+            // The dialogue has been removed from `storage` already, so after a
+            // panic the chat starts over with a default dialogue.
+            AssertUnwindSafe(handle).catch_unwind().map(move |result| {
+                if let Err(payload) = result {
+                    queue.report_panic_of(update_id, &*payload);
+                }
+            })
         }));
 
         tx
@@ -135,23 +155,22 @@ where
     S: Storage<D> + Send + Sync + 'static,
     S::Error: Send + 'static,
 {
-    fn handle(
-        self,
-        updates: mpsc::UnboundedReceiver<UpdateWithCx<Upd>>,
-    ) -> BoxFuture<'static, ()>
+    fn handle(self, updates: DispatcherHandlerRx<Upd>) -> BoxFuture<'static, ()>
     where
         UpdateWithCx<Upd>: 'static,
     {
         let this = Arc::new(self);
+        let queue = updates.reopen();
 
         Box::pin(updates.for_each(move |cx| {
             let this = Arc::clone(&this);
+            let update_id = queue.in_flight();
             let chat_id = cx.update.chat_id();
 
             match this.senders.get(&chat_id) {
                 // An old dialogue
                 Some(tx) => {
-                    if tx.1.send(cx).is_err() {
+                    if tx.1.send((update_id, cx)).is_err() {
                         panic!(
                             "We are not dropping a receiver or call .close() \
                              on it",
@@ -159,8 +178,8 @@ where
                     }
                 }
                 None => {
-                    let tx = this.new_tx();
-                    if tx.send(cx).is_err() {
+                    let tx = this.new_tx(queue.reopen());
+                    if tx.send((update_id, cx)).is_err() {
                         panic!(
                             "We are not dropping a receiver or call .close() \
                              on it",
@@ -180,10 +199,9 @@ mod tests {
     use super::*;
 
     use crate::Bot;
-    use futures::{stream, StreamExt};
     use lazy_static::lazy_static;
     use tokio::{
-        sync::{mpsc, Mutex},
+        sync::Mutex,
         time::{delay_for, Duration},
     };
 
@@ -234,53 +252,37 @@ mod tests {
             },
         );
 
-        let updates = stream::iter(
-            vec![
-                MyUpdate::new(1, 174),
-                MyUpdate::new(1, 125),
-                MyUpdate::new(2, 411),
-                MyUpdate::new(1, 2),
-                MyUpdate::new(2, 515),
-                MyUpdate::new(2, 623),
-                MyUpdate::new(1, 193),
-                MyUpdate::new(1, 104),
-                MyUpdate::new(2, 2222),
-                MyUpdate::new(2, 737),
-                MyUpdate::new(3, 72782),
-                MyUpdate::new(3, 2737),
-                MyUpdate::new(1, 7),
-                MyUpdate::new(1, 7778),
-                MyUpdate::new(3, 5475),
-                MyUpdate::new(3, 1096),
-                MyUpdate::new(3, 872),
-                MyUpdate::new(2, 10),
-                MyUpdate::new(2, 55456),
-                MyUpdate::new(3, 5665),
-                MyUpdate::new(3, 1611),
-            ]
-            .into_iter()
-            .map(|update| UpdateWithCx {
-                update,
-                bot: Bot::new("Doesn't matter here"),
-            })
-            .collect::<Vec<UpdateWithCx<MyUpdate>>>(),
-        );
-
-        let (tx, rx) = mpsc::unbounded_channel();
-
-        updates
-            .for_each(move |update| {
-                let tx = tx.clone();
-
-                async move {
-                    if tx.send(update).is_err() {
-                        panic!("tx.send(update) failed");
-                    }
-                }
-            })
-            .await;
-
-        dispatcher.handle(rx).await;
+        let updates = vec![
+            MyUpdate::new(1, 174),
+            MyUpdate::new(1, 125),
+            MyUpdate::new(2, 411),
+            MyUpdate::new(1, 2),
+            MyUpdate::new(2, 515),
+            MyUpdate::new(2, 623),
+            MyUpdate::new(1, 193),
+            MyUpdate::new(1, 104),
+            MyUpdate::new(2, 2222),
+            MyUpdate::new(2, 737),
+            MyUpdate::new(3, 72782),
+            MyUpdate::new(3, 2737),
+            MyUpdate::new(1, 7),
+            MyUpdate::new(1, 7778),
+            MyUpdate::new(3, 5475),
+            MyUpdate::new(3, 1096),
+            MyUpdate::new(3, 872),
+            MyUpdate::new(2, 10),
+            MyUpdate::new(2, 55456),
+            MyUpdate::new(3, 5665),
+            MyUpdate::new(3, 1611),
+        ]
+        .into_iter()
+        .map(|update| UpdateWithCx {
+            update,
+            bot: Bot::new("Doesn't matter here"),
+        })
+        .collect::<DispatcherHandlerRx<MyUpdate>>();
+
+        dispatcher.handle(updates).await;
 
         // Wait until our futures to be finished.
         delay_for(Duration::from_millis(3000)).await;
@@ -1,59 +1,151 @@
 use crate::{
     dispatching::{
-        update_listeners, update_listeners::UpdateListener, DispatcherHandler,
-        UpdateWithCx,
+        dispatcher_handler_rx::{queue, QueueControl, QueueTx},
+        middleware::Middleware,
+        update_listeners,
+        update_listeners::UpdateListener,
+        Dependencies, DispatcherHandler, PanicCounter, QueueMetrics,
+        QueuePolicy, UpdateWithCx,
     },
     error_handlers::{ErrorHandler, LoggingErrorHandler},
     types::{
-        CallbackQuery, ChosenInlineResult, InlineQuery, Message, Poll,
-        PollAnswer, PreCheckoutQuery, ShippingQuery, UpdateKind,
+        AllowedUpdate, CallbackQuery, ChosenInlineResult, InlineQuery, Message,
+        MiniProgramData, Poll, PollAnswer, PreCheckoutQuery, ShippingQuery,
+        Update, UpdateKind,
     },
     Bot,
 };
-use futures::StreamExt;
-use std::{fmt::Debug, sync::Arc};
-use tokio::sync::mpsc;
+use futures::{FutureExt, StreamExt};
+use std::{
+    collections::HashMap,
+    fmt::Debug,
+    panic::AssertUnwindSafe,
+    sync::{Arc, Mutex},
+};
+
+type Tx<Upd> = Option<QueueTx<Upd>>;
+
+type BoxedMiddleware = Box<dyn Middleware<Update, Output = Update>>;
 
-type Tx<Upd> = Option<mpsc::UnboundedSender<UpdateWithCx<Upd>>>;
+/// A handler to be spawned with the dependencies, when the dispatching starts.
+type PendingHandler = Box<dyn FnOnce(Dependencies) + Send>;
 
 #[macro_use]
 mod macros {
     /// Pushes an update to a queue.
     macro_rules! send {
-        ($bot:expr, $tx:expr, $update:expr, $variant:expr) => {
-            send($bot, $tx, $update, stringify!($variant));
+        (
+            $bot:expr,
+            $tx:expr,
+            $update_id:expr,
+            $update:expr,
+            $variant:expr
+        ) => {
+            send($bot, $tx, $update_id, $update, stringify!($variant)).await;
         };
     }
 }
 
-fn send<'a, Upd>(
+async fn send<'a, Upd>(
     bot: &'a Arc<Bot>,
     tx: &'a Tx<Upd>,
+    update_id: i64,
     update: Upd,
     variant: &'static str,
-) where
-    Upd: Debug,
-{
+) {
     if let Some(tx) = tx {
-        if let Err(error) =
-            tx.send(UpdateWithCx { bot: Arc::clone(&bot), update })
-        {
-            log::error!(
-                "The RX part of the {} channel is closed, but an update is \
-                 received.\nError:{}\n",
-                variant,
-                error
+        let cx = UpdateWithCx { bot: Arc::clone(bot), update };
+        if tx.send(Some(update_id), cx).await.is_err() {
+            log::debug!(
+                "The update #{} is dropped, since the handler of {} has \
+                 stopped",
+                update_id,
+                variant
             );
         }
     }
 }
 
+const KINDS: [AllowedUpdate; 12] = [
+    AllowedUpdate::Message,
+    AllowedUpdate::EditedMessage,
+    AllowedUpdate::ChannelPost,
+    AllowedUpdate::EditedChannelPost,
+    AllowedUpdate::InlineQuery,
+    AllowedUpdate::ChosenInlineResult,
+    AllowedUpdate::CallbackQuery,
+    AllowedUpdate::ShippingQuery,
+    AllowedUpdate::PreCheckoutQuery,
+    AllowedUpdate::Poll,
+    AllowedUpdate::PollAnswer,
+    AllowedUpdate::MiniProgramData,
+];
+
+fn kind_name(kind: AllowedUpdate) -> &'static str {
+    match kind {
+        AllowedUpdate::Message => "messages",
+        AllowedUpdate::EditedMessage => "edited messages",
+        AllowedUpdate::ChannelPost => "channel posts",
+        AllowedUpdate::EditedChannelPost => "edited channel posts",
+        AllowedUpdate::InlineQuery => "inline queries",
+        AllowedUpdate::ChosenInlineResult => "chosen inline results",
+        AllowedUpdate::CallbackQuery => "callback queries",
+        AllowedUpdate::ShippingQuery => "shipping queries",
+        AllowedUpdate::PreCheckoutQuery => "pre-checkout queries",
+        AllowedUpdate::Poll => "polls",
+        AllowedUpdate::PollAnswer => "poll answers",
+        AllowedUpdate::MiniProgramData => "mini-program data",
+    }
+}
+
 /// One dispatcher to rule them all.
 ///
 /// See [the module-level documentation for the design
 /// overview](crate::dispatching).
+///
+/// ## Panics in handlers
+/// A panic in a handler is caught and logged along with the identifier of
+/// the update the handler has received last, and counted by
+/// [`Dispatcher::panic_counter`]. After that, updates of its kind are
+/// dropped and counted by [`QueueMetrics::dropped`], unless the handler is
+/// wrapped into [`restart_on_panic`].
+///
+/// ## Queues
+/// Updates of each kind wait for their handler in a queue, which is
+/// unbounded by default. See [`Dispatcher::queue_limit`] to avoid unlimited
+/// memory growth when a handler is slower than the updates come, and
+/// [`Dispatcher::queue_metrics`] to watch the queues.
+///
+/// ## Concurrency
+/// A handler decides how to process the updates of its kind. To process
+/// updates from different chats concurrently, while keeping their order
+/// within each chat, use [`DispatcherHandlerRxExt::for_each_per_chat`].
+///
+/// ## Dependencies
+/// Values shared by the handlers, such as database pools, are added by
+/// [`Dispatcher::dependency`] and obtained by the handlers from
+/// [`DispatcherHandlerRx::dependencies`].
+///
+/// ## Middlewares
+/// Before getting into a queue, updates of all kinds pass through the
+/// middlewares added by [`Dispatcher::middleware`].
+///
+/// [`Dispatcher::panic_counter`]: crate::dispatching::Dispatcher::panic_counter
+/// [`restart_on_panic`]: crate::dispatching::restart_on_panic
+/// [`QueueMetrics::dropped`]: crate::dispatching::QueueMetrics::dropped
+/// [`Dispatcher::queue_limit`]: crate::dispatching::Dispatcher::queue_limit
+/// [`Dispatcher::queue_metrics`]: crate::dispatching::Dispatcher::queue_metrics
+/// [`Dispatcher::middleware`]: crate::dispatching::Dispatcher::middleware
+/// [`Dispatcher::dependency`]: crate::dispatching::Dispatcher::dependency
+/// [`DispatcherHandlerRx::dependencies`]: crate::dispatching::DispatcherHandlerRx::dependencies
+/// [`DispatcherHandlerRxExt::for_each_per_chat`]: crate::dispatching::DispatcherHandlerRxExt::for_each_per_chat
 pub struct Dispatcher {
     bot: Arc<Bot>,
+    panics: PanicCounter,
+    queues: Arc<HashMap<AllowedUpdate, Arc<QueueControl>>>,
+    middlewares: Vec<BoxedMiddleware>,
+    dependencies: Dependencies,
+    pending_handlers: Mutex<Vec<PendingHandler>>,
 
     messages_queue: Tx<Message>,
     edited_messages_queue: Tx<Message>,
@@ -66,6 +158,7 @@ pub struct Dispatcher {
     pre_checkout_queries_queue: Tx<PreCheckoutQuery>,
     polls_queue: Tx<Poll>,
     poll_answers_queue: Tx<PollAnswer>,
+    mini_program_data_queue: Tx<MiniProgramData>,
 }
 
 impl Dispatcher {
@@ -74,6 +167,16 @@ impl Dispatcher {
     pub fn new(bot: Arc<Bot>) -> Self {
         Self {
             bot,
+            panics: PanicCounter::default(),
+            queues: Arc::new(
+                KINDS
+                    .iter()
+                    .map(|&kind| (kind, Arc::new(QueueControl::default())))
+                    .collect(),
+            ),
+            middlewares: Vec::new(),
+            dependencies: Dependencies::new(),
+            pending_handlers: Mutex::new(Vec::new()),
             messages_queue: None,
             edited_messages_queue: None,
             channel_posts_queue: None,
@@ -85,29 +188,160 @@ impl Dispatcher {
             pre_checkout_queries_queue: None,
             polls_queue: None,
             poll_answers_queue: None,
+            mini_program_data_queue: None,
+        }
+    }
+
+    /// Returns a counter of panics in the handlers of this dispatcher.
+    ///
+    /// The counter is shared, so it keeps counting after being returned.
+    #[must_use]
+    pub fn panic_counter(&self) -> PanicCounter {
+        self.panics.clone()
+    }
+
+    /// Limits the queues of all the update kinds to `capacity` updates.
+    ///
+    /// When a queue is full, new updates are handled according to `policy`.
+    /// Note that [`QueuePolicy::Block`] blocks updates of all the kinds.
+    ///
+    /// # Panics
+    /// If `capacity` is zero.
+    ///
+    /// [`QueuePolicy::Block`]: crate::dispatching::QueuePolicy::Block
+    #[must_use]
+    pub fn queue_limit(self, capacity: usize, policy: QueuePolicy) -> Self {
+        for control in self.queues.values() {
+            control.set_limit(capacity, policy);
+        }
+        self
+    }
+
+    /// Limits the queue of the update `kind` to `capacity` updates,
+    /// overriding [`Dispatcher::queue_limit`].
+    ///
+    /// # Panics
+    /// If `capacity` is zero.
+    ///
+    /// [`Dispatcher::queue_limit`]: crate::dispatching::Dispatcher::queue_limit
+    #[must_use]
+    pub fn queue_limit_for(
+        self,
+        kind: AllowedUpdate,
+        capacity: usize,
+        policy: QueuePolicy,
+    ) -> Self {
+        self.queues[&kind].set_limit(capacity, policy);
+        self
+    }
+
+    /// Returns metrics of the queues of all the update kinds.
+    ///
+    /// The metrics are live, i.e. they keep changing after being returned.
+    #[must_use]
+    pub fn queue_metrics(&self) -> QueueMetrics {
+        QueueMetrics { queues: Arc::clone(&self.queues) }
+    }
+
+    /// Adds a middleware, which updates of all the kinds pass through
+    /// before getting to their handlers.
+    ///
+    /// Middlewares run in the order they were added. See [the module-level
+    /// documentation](crate::dispatching::middleware) for details.
+    #[must_use]
+    pub fn middleware<M>(mut self, middleware: M) -> Self
+    where
+        M: Middleware<Update, Output = Update> + 'static,
+    {
+        self.middlewares.push(Box::new(middleware));
+        self
+    }
+
+    /// Adds `value` to the [`Dependencies`] of the handlers, replacing a
+    /// value of the same type, if any.
+    ///
+    /// The handlers receive their dependencies when the dispatching starts,
+    /// so it doesn't matter whether they were added before or after `value`.
+    ///
+    /// [`Dependencies`]: crate::dispatching::Dependencies
+    #[must_use]
+    pub fn dependency<T>(mut self, value: T) -> Self
+    where
+        T: Send + Sync + 'static,
+    {
+        self.dependencies.insert(value);
+        self
+    }
+
+    /// Adds a shared `value` to the [`Dependencies`] of the handlers, like
+    /// [`Dispatcher::dependency`].
+    ///
+    /// Unlike [`Dispatcher::dependency`], `T` can be a trait object.
+    ///
+    /// [`Dependencies`]: crate::dispatching::Dependencies
+    /// [`Dispatcher::dependency`]: crate::dispatching::Dispatcher::dependency
+    #[must_use]
+    pub fn dependency_arc<T>(mut self, value: Arc<T>) -> Self
+    where
+        T: ?Sized + Send + Sync + 'static,
+    {
+        self.dependencies.insert_arc(value);
+        self
+    }
+
+    /// Passes `update` through the middlewares, returning the update
+    /// together with the bot to handle it, unless it's short-circuited.
+    async fn apply_middlewares(
+        &self,
+        update: Update,
+    ) -> Option<UpdateWithCx<Update>> {
+        let mut cx = UpdateWithCx { bot: Arc::clone(&self.bot), update };
+        for middleware in &self.middlewares {
+            cx = middleware.handle(cx).await?;
         }
+        Some(cx)
     }
 
     #[must_use]
-    fn new_tx<H, Upd>(&self, h: H) -> Tx<Upd>
+    fn new_tx<H, Upd>(&self, h: H, kind: AllowedUpdate) -> Tx<Upd>
     where
         H: DispatcherHandler<Upd> + Send + 'static,
         Upd: Send + 'static,
     {
-        let (tx, rx) = mpsc::unbounded_channel();
-        tokio::spawn(async move {
-            let fut = h.handle(rx);
-            fut.await;
-        });
+        let control = Arc::clone(&self.queues[&kind]);
+        let (tx, rx) = queue(kind_name(kind), self.panics.clone(), control);
+        let spawn = move |dependencies: Dependencies| {
+            let rx = rx.with_dependencies(dependencies);
+            tokio::spawn(async move {
+                let queue = rx.reopen();
+                let fut = h.handle(rx);
+                if let Err(payload) = AssertUnwindSafe(fut).catch_unwind().await
+                {
+                    queue.report_panic(&*payload);
+                }
+                queue.abandon();
+            });
+        };
+        self.pending_handlers.lock().unwrap().push(Box::new(spawn));
         Some(tx)
     }
 
+    /// Spawns the handlers added since the last call, giving them all the
+    /// dependencies added so far.
+    fn spawn_handlers(&self) {
+        let pending =
+            std::mem::take(&mut *self.pending_handlers.lock().unwrap());
+        for spawn in pending {
+            spawn(self.dependencies.clone());
+        }
+    }
+
     #[must_use]
     pub fn messages_handler<H>(mut self, h: H) -> Self
     where
         H: DispatcherHandler<Message> + 'static + Send,
     {
-        self.messages_queue = self.new_tx(h);
+        self.messages_queue = self.new_tx(h, AllowedUpdate::Message);
         self
     }
 
@@ -116,7 +350,8 @@ impl Dispatcher {
     where
         H: DispatcherHandler<Message> + 'static + Send,
     {
-        self.edited_messages_queue = self.new_tx(h);
+        self.edited_messages_queue =
+            self.new_tx(h, AllowedUpdate::EditedMessage);
         self
     }
 
@@ -125,7 +360,7 @@ impl Dispatcher {
     where
         H: DispatcherHandler<Message> + 'static + Send,
     {
-        self.channel_posts_queue = self.new_tx(h);
+        self.channel_posts_queue = self.new_tx(h, AllowedUpdate::ChannelPost);
         self
     }
 
@@ -134,7 +369,8 @@ impl Dispatcher {
     where
         H: DispatcherHandler<Message> + 'static + Send,
     {
-        self.edited_channel_posts_queue = self.new_tx(h);
+        self.edited_channel_posts_queue =
+            self.new_tx(h, AllowedUpdate::EditedChannelPost);
         self
     }
 
@@ -143,7 +379,7 @@ impl Dispatcher {
     where
         H: DispatcherHandler<InlineQuery> + 'static + Send,
     {
-        self.inline_queries_queue = self.new_tx(h);
+        self.inline_queries_queue = self.new_tx(h, AllowedUpdate::InlineQuery);
         self
     }
 
@@ -152,7 +388,8 @@ impl Dispatcher {
     where
         H: DispatcherHandler<ChosenInlineResult> + 'static + Send,
     {
-        self.chosen_inline_results_queue = self.new_tx(h);
+        self.chosen_inline_results_queue =
+            self.new_tx(h, AllowedUpdate::ChosenInlineResult);
         self
     }
 
@@ -161,7 +398,8 @@ impl Dispatcher {
     where
         H: DispatcherHandler<CallbackQuery> + 'static + Send,
     {
-        self.callback_queries_queue = self.new_tx(h);
+        self.callback_queries_queue =
+            self.new_tx(h, AllowedUpdate::CallbackQuery);
         self
     }
 
@@ -170,7 +408,8 @@ impl Dispatcher {
     where
         H: DispatcherHandler<ShippingQuery> + 'static + Send,
     {
-        self.shipping_queries_queue = self.new_tx(h);
+        self.shipping_queries_queue =
+            self.new_tx(h, AllowedUpdate::ShippingQuery);
         self
     }
 
@@ -179,7 +418,8 @@ impl Dispatcher {
     where
         H: DispatcherHandler<PreCheckoutQuery> + 'static + Send,
     {
-        self.pre_checkout_queries_queue = self.new_tx(h);
+        self.pre_checkout_queries_queue =
+            self.new_tx(h, AllowedUpdate::PreCheckoutQuery);
         self
     }
 
@@ -188,7 +428,7 @@ impl Dispatcher {
     where
         H: DispatcherHandler<Poll> + 'static + Send,
     {
-        self.polls_queue = self.new_tx(h);
+        self.polls_queue = self.new_tx(h, AllowedUpdate::Poll);
         self
     }
 
@@ -197,7 +437,17 @@ impl Dispatcher {
     where
         H: DispatcherHandler<PollAnswer> + 'static + Send,
     {
-        self.poll_answers_queue = self.new_tx(h);
+        self.poll_answers_queue = self.new_tx(h, AllowedUpdate::PollAnswer);
+        self
+    }
+
+    #[must_use]
+    pub fn mini_program_data_handler<H>(mut self, h: H) -> Self
+    where
+        H: DispatcherHandler<MiniProgramData> + 'static + Send,
+    {
+        self.mini_program_data_queue =
+            self.new_tx(h, AllowedUpdate::MiniProgramData);
         self
     }
 
@@ -226,6 +476,7 @@ impl Dispatcher {
         Eh: ErrorHandler<ListenerE> + 'a,
         ListenerE: Debug,
     {
+        self.spawn_handlers();
         let update_listener = Box::pin(update_listener);
 
         update_listener
@@ -246,98 +497,340 @@ impl Dispatcher {
                         }
                     };
 
+                    let UpdateWithCx { bot, update } =
+                        match self.apply_middlewares(update).await {
+                            Some(cx) => cx,
+                            None => return,
+                        };
+
+                    let id = update.id;
                     match update.kind {
                         UpdateKind::Message(message) => {
                             send!(
-                                &self.bot,
+                                &bot,
                                 &self.messages_queue,
+                                id,
                                 message,
                                 UpdateKind::Message
                             );
                         }
                         UpdateKind::EditedMessage(message) => {
                             send!(
-                                &self.bot,
+                                &bot,
                                 &self.edited_messages_queue,
+                                id,
                                 message,
                                 UpdateKind::EditedMessage
                             );
                         }
                         UpdateKind::ChannelPost(post) => {
                             send!(
-                                &self.bot,
+                                &bot,
                                 &self.channel_posts_queue,
+                                id,
                                 post,
                                 UpdateKind::ChannelPost
                             );
                         }
                         UpdateKind::EditedChannelPost(post) => {
                             send!(
-                                &self.bot,
+                                &bot,
                                 &self.edited_channel_posts_queue,
+                                id,
                                 post,
                                 UpdateKind::EditedChannelPost
                             );
                         }
                         UpdateKind::InlineQuery(query) => {
                             send!(
-                                &self.bot,
+                                &bot,
                                 &self.inline_queries_queue,
+                                id,
                                 query,
                                 UpdateKind::InlineQuery
                             );
                         }
                         UpdateKind::ChosenInlineResult(result) => {
                             send!(
-                                &self.bot,
+                                &bot,
                                 &self.chosen_inline_results_queue,
+                                id,
                                 result,
                                 UpdateKind::ChosenInlineResult
                             );
                         }
                         UpdateKind::CallbackQuery(query) => {
                             send!(
-                                &self.bot,
+                                &bot,
                                 &self.callback_queries_queue,
+                                id,
                                 query,
                                 UpdateKind::CallbackQuer
                             );
                         }
                         UpdateKind::ShippingQuery(query) => {
                             send!(
-                                &self.bot,
+                                &bot,
                                 &self.shipping_queries_queue,
+                                id,
                                 query,
                                 UpdateKind::ShippingQuery
                             );
                         }
                         UpdateKind::PreCheckoutQuery(query) => {
                             send!(
-                                &self.bot,
+                                &bot,
                                 &self.pre_checkout_queries_queue,
+                                id,
                                 query,
                                 UpdateKind::PreCheckoutQuery
                             );
                         }
                         UpdateKind::Poll(poll) => {
                             send!(
-                                &self.bot,
+                                &bot,
                                 &self.polls_queue,
+                                id,
                                 poll,
                                 UpdateKind::Poll
                             );
                         }
                         UpdateKind::PollAnswer(answer) => {
                             send!(
-                                &self.bot,
+                                &bot,
                                 &self.poll_answers_queue,
+                                id,
                                 answer,
                                 UpdateKind::PollAnswer
                             );
                         }
+                        UpdateKind::MiniProgramData(data) => {
+                            send!(
+                                &bot,
+                                &self.mini_program_data_queue,
+                                id,
+                                data,
+                                UpdateKind::MiniProgramData
+                            );
+                        }
                     }
                 }
             })
             .await
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        dispatching::{
+            dialogue::{DialogueDispatcher, DialogueStage, DialogueWithCx},
+            middleware, restart_on_panic, DispatcherHandlerRx,
+        },
+        error_handlers::IgnoringErrorHandlerSafe,
+        testing::update_injector,
+        types::UpdateBuilder,
+    };
+    use std::{convert::Infallible, time::Duration};
+    use tokio::sync::mpsc;
+
+    async fn dispatch_texts<H>(
+        dispatcher: Dispatcher,
+        handler: H,
+        texts: &[&str],
+    ) -> Dispatcher
+    where
+        H: DispatcherHandler<Message> + Send + 'static,
+    {
+        let (injector, listener) = update_injector();
+        for text in texts {
+            injector.inject_message(
+                UpdateBuilder::message().text(*text).build_message(),
+            );
+        }
+        drop(injector);
+
+        let dispatcher = dispatcher.messages_handler(handler);
+        dispatcher
+            .dispatch_with_listener(listener, IgnoringErrorHandlerSafe::new())
+            .await;
+        dispatcher
+    }
+
+    fn handler(
+        handled: mpsc::UnboundedSender<String>,
+    ) -> impl DispatcherHandler<Message> {
+        move |rx: DispatcherHandlerRx<Message>| {
+            rx.for_each(move |cx| {
+                let text = cx.update.text_owned().unwrap();
+                if text == "panic" {
+                    panic!("a panic in a handler");
+                }
+                handled.send(text).unwrap();
+                async {}
+            })
+        }
+    }
+
+    async fn receive_all(
+        mut rx: mpsc::UnboundedReceiver<String>,
+    ) -> Vec<String> {
+        let mut texts = Vec::new();
+        while let Ok(Some(text)) =
+            tokio::time::timeout(Duration::from_millis(200), rx.recv()).await
+        {
+            texts.push(text);
+        }
+        texts
+    }
+
+    #[tokio::test]
+    async fn panicked_handler_stops() {
+        let (tx, rx) = mpsc::unbounded_channel();
+        let dispatcher = dispatch_texts(
+            Dispatcher::new(Bot::new("")),
+            handler(tx),
+            &["a", "panic", "b"],
+        )
+        .await;
+
+        assert_eq!(receive_all(rx).await, vec!["a"]);
+        assert_eq!(dispatcher.panic_counter().get(), 1);
+    }
+
+    #[tokio::test]
+    async fn restarted_handler_continues() {
+        let (tx, rx) = mpsc::unbounded_channel();
+        let dispatcher = dispatch_texts(
+            Dispatcher::new(Bot::new("")),
+            restart_on_panic(move || handler(tx.clone())),
+            &["a", "panic", "b", "panic", "c"],
+        )
+        .await;
+
+        assert_eq!(receive_all(rx).await, vec!["a", "b", "c"]);
+        assert_eq!(dispatcher.panic_counter().get(), 2);
+    }
+
+    #[tokio::test]
+    async fn panicked_dialogue_continues() {
+        let (tx, rx) = mpsc::unbounded_channel();
+        let dialogues = DialogueDispatcher::new(
+            move |cx: DialogueWithCx<Message, (), Infallible>| {
+                let text = cx.cx.update.text_owned().unwrap();
+                if text == "panic" {
+                    panic!("a panic in a dialogue");
+                }
+                tx.send(text).unwrap();
+                async { DialogueStage::Next(()) }
+            },
+        );
+        let dispatcher = dispatch_texts(
+            Dispatcher::new(Bot::new("")),
+            dialogues,
+            &["a", "panic", "b"],
+        )
+        .await;
+
+        assert_eq!(receive_all(rx).await, vec!["a", "b"]);
+        assert_eq!(dispatcher.panic_counter().get(), 1);
+    }
+
+    #[tokio::test]
+    async fn queue_metrics() {
+        let (_stop, stopped) = tokio::sync::oneshot::channel::<()>();
+        let dispatcher = dispatch_texts(
+            Dispatcher::new(Bot::new(""))
+                .queue_limit(100, QueuePolicy::Block)
+                .queue_limit_for(
+                    AllowedUpdate::Message,
+                    2,
+                    QueuePolicy::DropNewest,
+                ),
+            // Never takes updates from the queue.
+            |rx: DispatcherHandlerRx<Message>| async move {
+                let _rx = rx;
+                let _ = stopped.await;
+            },
+            &["a", "b", "c"],
+        )
+        .await;
+
+        let metrics = dispatcher.queue_metrics();
+        assert_eq!(metrics.depth(AllowedUpdate::Message), 2);
+        assert_eq!(metrics.dropped(AllowedUpdate::Message), 1);
+        assert_eq!(metrics.depth(AllowedUpdate::EditedMessage), 0);
+    }
+
+    #[tokio::test]
+    async fn middlewares_short_circuit_updates() {
+        let (tx, rx) = mpsc::unbounded_channel();
+        let not_secret = |cx: &UpdateWithCx<Update>| match &cx.update.kind {
+            UpdateKind::Message(message) => message.text() != Some("secret"),
+            _ => true,
+        };
+        let (ids_tx, ids_rx) = mpsc::unbounded_channel();
+        dispatch_texts(
+            Dispatcher::new(Bot::new(""))
+                .middleware(middleware::inspect(
+                    move |cx: &UpdateWithCx<Update>| {
+                        ids_tx.send(cx.update.id.to_string()).unwrap()
+                    },
+                ))
+                .middleware(middleware::filter(not_secret)),
+            handler(tx),
+            &["a", "secret", "b"],
+        )
+        .await;
+
+        assert_eq!(receive_all(rx).await, vec!["a", "b"]);
+        assert_eq!(receive_all(ids_rx).await.len(), 3);
+    }
+
+    #[tokio::test]
+    async fn handlers_get_dependencies() {
+        let (tx, rx) = mpsc::unbounded_channel();
+        dispatch_texts(
+            Dispatcher::new(Bot::new("")).dependency("Hello".to_owned()),
+            move |rx: DispatcherHandlerRx<Message>| {
+                let greeting = rx.dependencies().require::<String>();
+                rx.for_each(move |cx| {
+                    let text = cx.update.text().unwrap();
+                    tx.send(format!("{}, {}", greeting, text)).unwrap();
+                    async {}
+                })
+            },
+            &["Ann"],
+        )
+        .await;
+
+        assert_eq!(receive_all(rx).await, vec!["Hello, Ann"]);
+    }
+
+    #[tokio::test]
+    async fn handlers_get_dependencies_added_after_them() {
+        let (tx, rx) = mpsc::unbounded_channel();
+        let dispatcher = Dispatcher::new(Bot::new(""))
+            .messages_handler(move |rx: DispatcherHandlerRx<Message>| {
+                let greeting = rx.dependencies().require::<String>();
+                rx.for_each(move |cx| {
+                    let text = cx.update.text().unwrap();
+                    tx.send(format!("{}, {}", greeting, text)).unwrap();
+                    async {}
+                })
+            })
+            .dependency("Hello".to_owned());
+
+        let (injector, listener) = update_injector();
+        injector.inject_message(
+            UpdateBuilder::message().text("Ann").build_message(),
+        );
+        drop(injector);
+        dispatcher
+            .dispatch_with_listener(listener, IgnoringErrorHandlerSafe::new())
+            .await;
+
+        assert_eq!(receive_all(rx).await, vec!["Hello, Ann"]);
+    }
+}
@@ -1,7 +1,7 @@
-use std::future::Future;
+use std::{future::Future, panic::AssertUnwindSafe};
 
 use crate::dispatching::{DispatcherHandlerRx, UpdateWithCx};
-use futures::future::BoxFuture;
+use futures::{future::BoxFuture, FutureExt};
 
 /// An asynchronous handler of a stream of updates used in [`Dispatcher`].
 ///
@@ -31,3 +31,61 @@ where
         Box::pin(async move { self(updates).await })
     }
 }
+
+/// A handler created by `factory` anew whenever the previous one panics.
+///
+/// The update that caused a panic isn't handled again, while the following
+/// updates go to the new handler. Without this wrapper, a [`Dispatcher`]
+/// stops passing updates to a handler once it has panicked.
+///
+/// ## Example
+/// ```no_run
+/// use teloxide::{dispatching::restart_on_panic, prelude::*};
+///
+/// # #[tokio::main]
+/// # async fn main_() {
+/// Dispatcher::new(Bot::from_env())
+///     .messages_handler(restart_on_panic(|| {
+///         |rx: DispatcherHandlerRx<Message>| {
+///             rx.for_each(|message| async move {
+///                 message.answer_str("pong").await.log_on_error().await;
+///             })
+///         }
+///     }))
+///     .dispatch()
+///     .await;
+/// # }
+/// ```
+///
+/// [`Dispatcher`]: crate::dispatching::Dispatcher
+pub fn restart_on_panic<F>(factory: F) -> RestartOnPanic<F> {
+    RestartOnPanic { factory }
+}
+
+/// A handler returned by [`restart_on_panic`].
+///
+/// [`restart_on_panic`]: crate::dispatching::restart_on_panic
+pub struct RestartOnPanic<F> {
+    factory: F,
+}
+
+impl<Upd, F, H> DispatcherHandler<Upd> for RestartOnPanic<F>
+where
+    F: Fn() -> H + Send + 'static,
+    H: DispatcherHandler<Upd>,
+{
+    fn handle(self, updates: DispatcherHandlerRx<Upd>) -> BoxFuture<'static, ()>
+    where
+        UpdateWithCx<Upd>: Send + 'static,
+    {
+        Box::pin(async move {
+            loop {
+                let handler = (self.factory)().handle(updates.reopen());
+                match AssertUnwindSafe(handler).catch_unwind().await {
+                    Ok(()) => break,
+                    Err(payload) => updates.report_panic(&*payload),
+                }
+            }
+        })
+    }
+}
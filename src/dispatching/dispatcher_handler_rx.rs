@@ -0,0 +1,518 @@
+use std::{
+    any::Any,
+    collections::{HashMap, VecDeque},
+    iter::FromIterator,
+    pin::Pin,
+    sync::{
+        atomic::{AtomicU8, AtomicUsize, Ordering},
+        Arc, Mutex, MutexGuard,
+    },
+    task::{Context, Poll, Waker},
+};
+
+use futures::{future, Stream, StreamExt};
+
+use crate::{
+    dispatching::{Dependencies, UpdateWithCx},
+    types::AllowedUpdate,
+};
+
+/// A stream of updates, consumed by [`Dispatcher`]'s handlers.
+///
+/// Besides [`Stream`], updates can be received with [`recv`]. A stream of
+/// predefined updates, e.g. for testing a handler, can be collected from an
+/// iterator:
+///
+/// ```
+/// use teloxide::{
+///     dispatching::{DispatcherHandlerRx, UpdateWithCx},
+///     Bot,
+/// };
+///
+/// let rx: DispatcherHandlerRx<i32> = vec![1, 2]
+///     .into_iter()
+///     .map(|update| UpdateWithCx { bot: Bot::new("token"), update })
+///     .collect();
+/// ```
+///
+/// [`Dispatcher`]: crate::dispatching::Dispatcher
+/// [`Stream`]: futures::Stream
+/// [`recv`]: crate::dispatching::DispatcherHandlerRx::recv
+pub struct DispatcherHandlerRx<Upd> {
+    queue: Arc<Queue<Upd>>,
+    dependencies: Dependencies,
+}
+
+impl<Upd> DispatcherHandlerRx<Upd> {
+    /// Receives the next update, or `None` if there will be no more updates.
+    pub async fn recv(&mut self) -> Option<UpdateWithCx<Upd>> {
+        self.next().await
+    }
+
+    /// Returns the dependencies added to the [`Dispatcher`].
+    ///
+    /// [`Dispatcher`]: crate::dispatching::Dispatcher
+    #[must_use]
+    pub fn dependencies(&self) -> &Dependencies {
+        &self.dependencies
+    }
+
+    /// Replaces the dependencies, e.g. with fakes in tests.
+    #[must_use]
+    pub fn with_dependencies(mut self, dependencies: Dependencies) -> Self {
+        self.dependencies = dependencies;
+        self
+    }
+
+    /// A new stream of the same updates, replacing this one after its
+    /// handler has panicked.
+    pub(crate) fn reopen(&self) -> Self {
+        Self {
+            queue: Arc::clone(&self.queue),
+            dependencies: self.dependencies.clone(),
+        }
+    }
+
+    /// The identifier of the update taken last, if any.
+    pub(crate) fn in_flight(&self) -> Option<i64> {
+        self.queue.lock().in_flight
+    }
+
+    /// Marks the queue as having no handler, so that next updates are
+    /// rejected and counted as dropped instead of piling up.
+    pub(crate) fn abandon(&self) {
+        let mut state = self.queue.lock();
+        if !state.closed {
+            log::error!(
+                "The handler of {} has stopped, next updates of this kind are \
+                 dropped",
+                self.queue.kind
+            );
+        }
+        state.abandoned = true;
+        self.queue
+            .control
+            .dropped
+            .fetch_add(state.updates.len(), Ordering::Relaxed);
+        state.updates.clear();
+        self.queue.control.depth.store(0, Ordering::Relaxed);
+        state.wake_senders();
+    }
+
+    /// Logs and counts a panic of the handler of these updates.
+    pub(crate) fn report_panic(&self, payload: &(dyn Any + Send)) {
+        self.report_panic_of(self.in_flight(), payload);
+    }
+
+    /// Logs and counts a panic of the handler of these updates while it was
+    /// handling the update `update_id`.
+    pub(crate) fn report_panic_of(
+        &self,
+        update_id: Option<i64>,
+        payload: &(dyn Any + Send),
+    ) {
+        let message = payload
+            .downcast_ref::<&str>()
+            .copied()
+            .or_else(|| payload.downcast_ref::<String>().map(String::as_str))
+            .unwrap_or("<unknown>");
+
+        match update_id {
+            Some(update_id) => log::error!(
+                "A handler of {} panicked while handling the update #{}: {}",
+                self.queue.kind,
+                update_id,
+                message
+            ),
+            None => log::error!(
+                "A handler of {} panicked: {}",
+                self.queue.kind,
+                message
+            ),
+        }
+        self.queue.panics.increment();
+    }
+}
+
+impl<Upd> Stream for DispatcherHandlerRx<Upd> {
+    type Item = UpdateWithCx<Upd>;
+
+    fn poll_next(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Self::Item>> {
+        let mut state = self.queue.lock();
+
+        match state.updates.pop_front() {
+            Some((update_id, update)) => {
+                state.in_flight = update_id;
+                self.queue
+                    .control
+                    .depth
+                    .store(state.updates.len(), Ordering::Relaxed);
+                state.wake_senders();
+                Poll::Ready(Some(update))
+            }
+            None if state.closed => Poll::Ready(None),
+            None => {
+                state.waker = Some(cx.waker().clone());
+                Poll::Pending
+            }
+        }
+    }
+}
+
+impl<Upd> FromIterator<UpdateWithCx<Upd>> for DispatcherHandlerRx<Upd> {
+    fn from_iter<I>(iter: I) -> Self
+    where
+        I: IntoIterator<Item = UpdateWithCx<Upd>>,
+    {
+        let queue = Queue::new(
+            "updates",
+            PanicCounter::default(),
+            Arc::new(QueueControl::default()),
+        );
+        {
+            let mut state = queue.lock();
+            state.updates.extend(iter.into_iter().map(|cx| (None, cx)));
+            state.closed = true;
+        }
+        Self { queue: Arc::new(queue), dependencies: Dependencies::new() }
+    }
+}
+
+/// The number of panics of [`Dispatcher`]'s handlers.
+///
+/// See [`Dispatcher::panic_counter`].
+///
+/// [`Dispatcher`]: crate::dispatching::Dispatcher
+/// [`Dispatcher::panic_counter`]: crate::dispatching::Dispatcher::panic_counter
+#[derive(Clone, Debug, Default)]
+pub struct PanicCounter(Arc<AtomicUsize>);
+
+impl PanicCounter {
+    /// The number of panics so far.
+    pub fn get(&self) -> usize {
+        self.0.load(Ordering::Relaxed)
+    }
+
+    fn increment(&self) {
+        self.0.fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+/// The sending half of a queue of updates.
+pub(crate) struct QueueTx<Upd> {
+    queue: Arc<Queue<Upd>>,
+}
+
+impl<Upd> QueueTx<Upd> {
+    /// Pushes an update, or returns it back if its handler is gone, counting
+    /// it as dropped.
+    ///
+    /// If the queue is full, waits for a free place or drops an update,
+    /// depending on the [`QueuePolicy`].
+    ///
+    /// [`QueuePolicy`]: crate::dispatching::QueuePolicy
+    pub(crate) async fn send(
+        &self,
+        update_id: Option<i64>,
+        update: UpdateWithCx<Upd>,
+    ) -> Result<(), UpdateWithCx<Upd>> {
+        let control = &self.queue.control;
+        let mut update = Some(update);
+
+        future::poll_fn(|cx| {
+            let mut state = self.queue.lock();
+            if state.abandoned {
+                control.dropped.fetch_add(1, Ordering::Relaxed);
+                return Poll::Ready(Err(update.take().unwrap()));
+            }
+
+            let capacity = control.capacity.load(Ordering::Relaxed);
+            if capacity != UNBOUNDED && state.updates.len() >= capacity {
+                match control.policy() {
+                    QueuePolicy::Block => {
+                        state.senders.push(cx.waker().clone());
+                        return Poll::Pending;
+                    }
+                    QueuePolicy::DropOldest => {
+                        if let Some((dropped_id, _)) = state.updates.pop_front()
+                        {
+                            log_dropped(self.queue.kind, dropped_id);
+                        }
+                    }
+                    QueuePolicy::DropNewest => {
+                        log_dropped(self.queue.kind, update_id);
+                        control.dropped.fetch_add(1, Ordering::Relaxed);
+                        return Poll::Ready(Ok(()));
+                    }
+                }
+                control.dropped.fetch_add(1, Ordering::Relaxed);
+            }
+
+            state.updates.push_back((update_id, update.take().unwrap()));
+            control.depth.store(state.updates.len(), Ordering::Relaxed);
+            if let Some(waker) = state.waker.take() {
+                waker.wake();
+            }
+            Poll::Ready(Ok(()))
+        })
+        .await
+    }
+}
+
+fn log_dropped(kind: &str, update_id: Option<i64>) {
+    match update_id {
+        Some(id) => log::warn!(
+            "The queue of {} is full, the update #{} is dropped",
+            kind,
+            id
+        ),
+        None => {
+            log::warn!("The queue of {} is full, an update is dropped", kind)
+        }
+    }
+}
+
+impl<Upd> Drop for QueueTx<Upd> {
+    fn drop(&mut self) {
+        let mut state = self.queue.lock();
+        state.closed = true;
+        if let Some(waker) = state.waker.take() {
+            waker.wake();
+        }
+    }
+}
+
+/// Creates a queue of updates of the `kind`, whose handler panics are
+/// counted by `panics` and whose limits and metrics are in `control`.
+pub(crate) fn queue<Upd>(
+    kind: &'static str,
+    panics: PanicCounter,
+    control: Arc<QueueControl>,
+) -> (QueueTx<Upd>, DispatcherHandlerRx<Upd>) {
+    let queue = Arc::new(Queue::new(kind, panics, control));
+    let rx = DispatcherHandlerRx {
+        queue: Arc::clone(&queue),
+        dependencies: Dependencies::new(),
+    };
+    (QueueTx { queue }, rx)
+}
+
+/// What to do with an update, when the queue of its kind is full.
+///
+/// See [`Dispatcher::queue_limit`].
+///
+/// [`Dispatcher::queue_limit`]: crate::dispatching::Dispatcher::queue_limit
+#[derive(Copy, Clone, Debug, Eq, Hash, PartialEq)]
+pub enum QueuePolicy {
+    /// Stop receiving updates until the handler takes an update from the
+    /// queue. Updates of other kinds wait too.
+    Block,
+
+    /// Drop the oldest update in the queue to make room for the new one.
+    DropOldest,
+
+    /// Drop the new update.
+    DropNewest,
+}
+
+const UNBOUNDED: usize = 0;
+
+/// The limits and metrics of a queue, shared between the [`Dispatcher`] and
+/// the queue.
+///
+/// [`Dispatcher`]: crate::dispatching::Dispatcher
+#[derive(Debug, Default)]
+pub(crate) struct QueueControl {
+    capacity: AtomicUsize,
+    policy: AtomicU8,
+    depth: AtomicUsize,
+    dropped: AtomicUsize,
+}
+
+impl QueueControl {
+    pub(crate) fn set_limit(&self, capacity: usize, policy: QueuePolicy) {
+        assert!(capacity > 0, "the capacity of a queue must be positive");
+        self.policy.store(policy as u8, Ordering::Relaxed);
+        self.capacity.store(capacity, Ordering::Relaxed);
+    }
+
+    fn policy(&self) -> QueuePolicy {
+        match self.policy.load(Ordering::Relaxed) {
+            x if x == QueuePolicy::DropOldest as u8 => QueuePolicy::DropOldest,
+            x if x == QueuePolicy::DropNewest as u8 => QueuePolicy::DropNewest,
+            _ => QueuePolicy::Block,
+        }
+    }
+}
+
+/// Metrics of the queues of a [`Dispatcher`], per update kind.
+///
+/// See [`Dispatcher::queue_metrics`].
+///
+/// [`Dispatcher`]: crate::dispatching::Dispatcher
+/// [`Dispatcher::queue_metrics`]: crate::dispatching::Dispatcher::queue_metrics
+#[derive(Clone, Debug)]
+pub struct QueueMetrics {
+    pub(crate) queues: Arc<HashMap<AllowedUpdate, Arc<QueueControl>>>,
+}
+
+impl QueueMetrics {
+    /// The number of updates of the `kind` waiting for their handler.
+    pub fn depth(&self, kind: AllowedUpdate) -> usize {
+        self.get(kind, |control| &control.depth)
+    }
+
+    /// The number of updates of the `kind` dropped because their queue was
+    /// full or their handler has stopped.
+    pub fn dropped(&self, kind: AllowedUpdate) -> usize {
+        self.get(kind, |control| &control.dropped)
+    }
+
+    fn get<F>(&self, kind: AllowedUpdate, f: F) -> usize
+    where
+        F: FnOnce(&QueueControl) -> &AtomicUsize,
+    {
+        self.queues
+            .get(&kind)
+            .map_or(0, |control| f(control).load(Ordering::Relaxed))
+    }
+}
+
+struct Queue<Upd> {
+    kind: &'static str,
+    panics: PanicCounter,
+    control: Arc<QueueControl>,
+    state: Mutex<State<Upd>>,
+}
+
+struct State<Upd> {
+    updates: VecDeque<(Option<i64>, UpdateWithCx<Upd>)>,
+    /// The identifier of the last received update.
+    in_flight: Option<i64>,
+    /// The handler waiting for updates.
+    waker: Option<Waker>,
+    /// The dispatcher waiting for a free place in the queue.
+    senders: Vec<Waker>,
+    closed: bool,
+    abandoned: bool,
+}
+
+impl<Upd> State<Upd> {
+    fn wake_senders(&mut self) {
+        self.senders.drain(..).for_each(Waker::wake);
+    }
+}
+
+impl<Upd> Queue<Upd> {
+    fn new(
+        kind: &'static str,
+        panics: PanicCounter,
+        control: Arc<QueueControl>,
+    ) -> Self {
+        Self {
+            kind,
+            panics,
+            control,
+            state: Mutex::new(State {
+                updates: VecDeque::new(),
+                in_flight: None,
+                waker: None,
+                senders: Vec::new(),
+                closed: false,
+                abandoned: false,
+            }),
+        }
+    }
+
+    fn lock(&self) -> MutexGuard<'_, State<Upd>> {
+        // The lock is never held across user code, so it can't be poisoned.
+        self.state.lock().unwrap()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Bot;
+    use futures::FutureExt;
+
+    fn limited_queue(
+        policy: QueuePolicy,
+    ) -> (QueueTx<i32>, DispatcherHandlerRx<i32>, Arc<QueueControl>) {
+        let control = Arc::new(QueueControl::default());
+        control.set_limit(2, policy);
+        let (tx, rx) =
+            queue("numbers", PanicCounter::default(), Arc::clone(&control));
+        (tx, rx, control)
+    }
+
+    fn cx(update: i32) -> UpdateWithCx<i32> {
+        UpdateWithCx { bot: Bot::new(""), update }
+    }
+
+    async fn received(
+        tx: QueueTx<i32>,
+        rx: DispatcherHandlerRx<i32>,
+    ) -> Vec<i32> {
+        drop(tx);
+        rx.map(|cx| cx.update).collect().await
+    }
+
+    #[tokio::test]
+    async fn drop_oldest() {
+        let (tx, rx, control) = limited_queue(QueuePolicy::DropOldest);
+        for i in 1..=4 {
+            tx.send(Some(i.into()), cx(i)).await.unwrap();
+        }
+
+        assert_eq!(control.depth.load(Ordering::Relaxed), 2);
+        assert_eq!(control.dropped.load(Ordering::Relaxed), 2);
+        assert_eq!(received(tx, rx).await, vec![3, 4]);
+        assert_eq!(control.depth.load(Ordering::Relaxed), 0);
+    }
+
+    #[tokio::test]
+    async fn drop_newest() {
+        let (tx, rx, control) = limited_queue(QueuePolicy::DropNewest);
+        for i in 1..=4 {
+            tx.send(Some(i.into()), cx(i)).await.unwrap();
+        }
+
+        assert_eq!(control.dropped.load(Ordering::Relaxed), 2);
+        assert_eq!(received(tx, rx).await, vec![1, 2]);
+    }
+
+    #[tokio::test]
+    async fn block() {
+        let (tx, mut rx, control) = limited_queue(QueuePolicy::Block);
+        tx.send(Some(1), cx(1)).await.unwrap();
+        tx.send(Some(2), cx(2)).await.unwrap();
+
+        let mut blocked = Box::pin(tx.send(Some(3), cx(3)));
+        assert!((&mut blocked).now_or_never().is_none());
+
+        assert_eq!(rx.recv().await.unwrap().update, 1);
+        blocked.await.unwrap();
+        assert_eq!(control.dropped.load(Ordering::Relaxed), 0);
+        assert_eq!(received(tx, rx).await, vec![2, 3]);
+    }
+
+    #[tokio::test]
+    async fn abandoned_queue_rejects_updates() {
+        let (tx, rx, control) = limited_queue(QueuePolicy::Block);
+        tx.send(Some(1), cx(1)).await.unwrap();
+        tx.send(Some(2), cx(2)).await.unwrap();
+        let mut blocked = Box::pin(tx.send(Some(3), cx(3)));
+        assert!((&mut blocked).now_or_never().is_none());
+
+        rx.abandon();
+
+        assert_eq!(blocked.await.unwrap_err().update, 3);
+        assert_eq!(tx.send(Some(4), cx(4)).await.unwrap_err().update, 4);
+        assert_eq!(control.depth.load(Ordering::Relaxed), 0);
+        assert_eq!(control.dropped.load(Ordering::Relaxed), 4);
+    }
+}
@@ -1,7 +1,21 @@
 use crate::{
-    prelude::UpdateWithCx, types::Message, utils::command::BotCommand,
+    dispatching::dialogue::GetChatId,
+    prelude::UpdateWithCx,
+    types::Message,
+    utils::command::{default_syntaxes, BotCommand, CommandSyntax},
+    Bot,
 };
-use futures::{stream::BoxStream, Stream, StreamExt};
+use futures::{
+    future::{self, BoxFuture},
+    stream::{self, BoxStream, Fuse, FusedStream, FuturesUnordered},
+    Future, FutureExt, Stream, StreamExt,
+};
+use std::{
+    collections::{hash_map::Entry, HashMap, VecDeque},
+    sync::Arc,
+    time::Duration,
+};
+use tokio::time::{self, Instant};
 
 /// An extension trait to be used with [`DispatcherHandlerRx`].
 ///
@@ -16,6 +30,12 @@ pub trait DispatcherHandlerRxExt {
 
     /// Extracts only commands with their arguments from this stream of
     /// arbitrary messages.
+    ///
+    /// Commands are taken from the text of a message or, if there is none,
+    /// from its caption. They are recognised at the start and as `${/...}`
+    /// tokens anywhere in it, see [`default_syntaxes`].
+    ///
+    /// [`default_syntaxes`]: crate::utils::command::default_syntaxes
     fn commands<C, N>(
         self,
         bot_name: N,
@@ -24,6 +44,103 @@ pub trait DispatcherHandlerRxExt {
         Self: Stream<Item = UpdateWithCx<Message>>,
         C: BotCommand,
         N: Into<String> + Send;
+
+    /// Extracts only commands written in `syntax` with their arguments from
+    /// this stream of arbitrary messages.
+    ///
+    /// Commands are taken from the text of a message or, if there is none,
+    /// from its caption.
+    fn commands_with_syntax<C, N, S>(
+        self,
+        bot_name: N,
+        syntax: S,
+    ) -> BoxStream<'static, (UpdateWithCx<Message>, C, Vec<String>)>
+    where
+        Self: Stream<Item = UpdateWithCx<Message>>,
+        C: BotCommand,
+        N: Into<String> + Send,
+        S: CommandSyntax + 'static;
+
+    /// Runs `f` for each update, concurrently for updates from different
+    /// chats, but one by one for updates from the same chat, in the order
+    /// they come.
+    ///
+    /// At most `limit` updates are handled at the same time, and a limit of
+    /// `None` or `Some(0)` means no limit, like in
+    /// [`StreamExt::for_each_concurrent`]. When the limit is reached, updates
+    /// stay in the [`Dispatcher`]'s queue. Updates from a chat whose previous
+    /// update is still being handled wait in memory.
+    ///
+    /// ## Example
+    /// ```no_run
+    /// use teloxide::prelude::*;
+    ///
+    /// # #[tokio::main]
+    /// # async fn main_() {
+    /// Dispatcher::new(Bot::from_env())
+    ///     .messages_handler(|rx: DispatcherHandlerRx<Message>| {
+    ///         rx.for_each_per_chat(32, |message| async move {
+    ///             message.answer_str("pong").await.log_on_error().await;
+    ///         })
+    ///     })
+    ///     .dispatch()
+    ///     .await;
+    /// # }
+    /// ```
+    ///
+    /// [`StreamExt::for_each_concurrent`]: futures::StreamExt::for_each_concurrent
+    /// [`Dispatcher`]: crate::dispatching::Dispatcher
+    fn for_each_per_chat<Upd, L, F, Fut>(
+        self,
+        limit: L,
+        f: F,
+    ) -> BoxFuture<'static, ()>
+    where
+        Self: Stream<Item = UpdateWithCx<Upd>>,
+        Upd: GetChatId + Send + 'static,
+        L: Into<Option<usize>>,
+        F: FnMut(UpdateWithCx<Upd>) -> Fut + Send + 'static,
+        Fut: Future<Output = ()> + Send + 'static;
+
+    /// Collects messages of the same album into a single update.
+    ///
+    /// Messages with the same [`Message::media_group_id`] are buffered until
+    /// no more of them come within `window` and then yielded together,
+    /// ordered by their identifiers. Other messages are yielded as single
+    /// messages right away, so they may overtake albums. When the stream
+    /// ends, the buffered albums are yielded immediately.
+    ///
+    /// ## Example
+    /// ```no_run
+    /// use std::time::Duration;
+    /// use teloxide::prelude::*;
+    ///
+    /// # #[tokio::main]
+    /// # async fn main_() {
+    /// Dispatcher::new(Bot::from_env())
+    ///     .messages_handler(|rx: DispatcherHandlerRx<Message>| {
+    ///         rx.media_groups(Duration::from_secs(1)).for_each(|cx| async move {
+    ///             let text = format!("Got {} messages", cx.update.len());
+    ///             cx.bot
+    ///                 .send_message(cx.update[0].chat_id(), text)
+    ///                 .send()
+    ///                 .await
+    ///                 .log_on_error()
+    ///                 .await;
+    ///         })
+    ///     })
+    ///     .dispatch()
+    ///     .await;
+    /// # }
+    /// ```
+    ///
+    /// [`Message::media_group_id`]: crate::types::Message::media_group_id
+    fn media_groups(
+        self,
+        window: Duration,
+    ) -> BoxStream<'static, UpdateWithCx<Vec<Message>>>
+    where
+        Self: Stream<Item = UpdateWithCx<Message>>;
 }
 
 impl<T> DispatcherHandlerRxExt for T
@@ -49,23 +166,425 @@ where
         Self: Stream<Item = UpdateWithCx<Message>>,
         C: BotCommand,
         N: Into<String> + Send,
+    {
+        self.commands_with_syntax(bot_name, default_syntaxes())
+    }
+
+    fn commands_with_syntax<C, N, S>(
+        self,
+        bot_name: N,
+        syntax: S,
+    ) -> BoxStream<'static, (UpdateWithCx<Message>, C, Vec<String>)>
+    where
+        Self: Stream<Item = UpdateWithCx<Message>>,
+        C: BotCommand,
+        N: Into<String> + Send,
+        S: CommandSyntax + 'static,
     {
         let bot_name = bot_name.into();
+        let syntax = Arc::new(syntax);
 
-        Box::pin(self.text_messages().filter_map(move |(cx, text)| {
+        Box::pin(self.filter_map(move |cx| {
             let bot_name = bot_name.clone();
+            let syntax = Arc::clone(&syntax);
 
             async move {
-                C::parse(&text, &bot_name).map(|(command, args)| {
-                    (
-                        cx,
-                        command,
-                        args.into_iter()
-                            .map(ToOwned::to_owned)
-                            .collect::<Vec<String>>(),
-                    )
-                })
+                let message = &cx.update;
+                let text = message.text().or_else(|| message.caption())?;
+                let (command, args) = C::parse_with(text, &bot_name, &*syntax)?;
+                let args = args.into_iter().map(ToOwned::to_owned).collect();
+                Some((cx, command, args))
             }
         }))
     }
+
+    fn for_each_per_chat<Upd, L, F, Fut>(
+        self,
+        limit: L,
+        f: F,
+    ) -> BoxFuture<'static, ()>
+    where
+        Self: Stream<Item = UpdateWithCx<Upd>>,
+        Upd: GetChatId + Send + 'static,
+        L: Into<Option<usize>>,
+        F: FnMut(UpdateWithCx<Upd>) -> Fut + Send + 'static,
+        Fut: Future<Output = ()> + Send + 'static,
+    {
+        let limit = match limit.into() {
+            None | Some(0) => usize::MAX,
+            Some(limit) => limit,
+        };
+        Box::pin(for_each_per_chat(Box::pin(self), limit, f))
+    }
+
+    fn media_groups(
+        self,
+        window: Duration,
+    ) -> BoxStream<'static, UpdateWithCx<Vec<Message>>>
+    where
+        Self: Stream<Item = UpdateWithCx<Message>>,
+    {
+        let albums = Albums {
+            messages: Box::pin(self).fuse(),
+            window,
+            pending: HashMap::new(),
+            ready: VecDeque::new(),
+        };
+        Box::pin(stream::unfold(albums, |mut albums| async move {
+            albums.next().await.map(|album| (album, albums))
+        }))
+    }
+}
+
+/// The state of [`DispatcherHandlerRxExt::media_groups`].
+struct Albums<S> {
+    messages: Fuse<S>,
+    window: Duration,
+    /// Incomplete albums by their media group identifiers.
+    pending: HashMap<String, Album>,
+    ready: VecDeque<UpdateWithCx<Vec<Message>>>,
+}
+
+struct Album {
+    bot: Arc<Bot>,
+    messages: Vec<Message>,
+    /// When the album is considered complete.
+    deadline: Instant,
+}
+
+impl<S> Albums<S>
+where
+    S: Stream<Item = UpdateWithCx<Message>> + Unpin,
+{
+    async fn next(&mut self) -> Option<UpdateWithCx<Vec<Message>>> {
+        loop {
+            if let Some(album) = self.ready.pop_front() {
+                return Some(album);
+            }
+            if self.messages.is_terminated() {
+                let deadline = self.pending.values().map(|a| a.deadline).max();
+                self.complete(deadline?);
+                continue;
+            }
+
+            let deadline = self.pending.values().map(|a| a.deadline).min();
+            let mut timer = match deadline {
+                Some(deadline) => time::delay_until(deadline).boxed(),
+                None => future::pending().boxed(),
+            }
+            .fuse();
+            futures::select! {
+                cx = self.messages.next() => {
+                    if let Some(cx) = cx {
+                        self.push(cx);
+                    }
+                }
+                _ = timer => self.complete(Instant::now()),
+            }
+        }
+    }
+
+    fn push(&mut self, cx: UpdateWithCx<Message>) {
+        let UpdateWithCx { bot, update: message } = cx;
+        let media_group_id = match message.media_group_id() {
+            Some(media_group_id) => media_group_id.to_owned(),
+            None => {
+                self.ready
+                    .push_back(UpdateWithCx { bot, update: vec![message] });
+                return;
+            }
+        };
+
+        let deadline = Instant::now() + self.window;
+        let album = self
+            .pending
+            .entry(media_group_id)
+            .or_insert_with(|| Album { bot, messages: Vec::new(), deadline });
+        album.messages.push(message);
+        album.deadline = deadline;
+    }
+
+    /// Makes the albums whose deadline is not later than `now` ready, in the
+    /// order of their deadlines.
+    fn complete(&mut self, now: Instant) {
+        let mut complete: Vec<_> = self
+            .pending
+            .iter()
+            .filter(|(_, album)| album.deadline <= now)
+            .map(|(id, album)| (album.deadline, id.clone()))
+            .collect();
+        complete.sort();
+
+        for (_, id) in complete {
+            if let Some(mut album) = self.pending.remove(&id) {
+                album.messages.sort_by_key(|message| message.id);
+                self.ready.push_back(UpdateWithCx {
+                    bot: album.bot,
+                    update: album.messages,
+                });
+            }
+        }
+    }
+}
+
+async fn for_each_per_chat<S, Upd, F, Fut>(updates: S, limit: usize, mut f: F)
+where
+    S: Stream<Item = UpdateWithCx<Upd>> + Unpin,
+    Upd: GetChatId,
+    F: FnMut(UpdateWithCx<Upd>) -> Fut,
+    Fut: Future<Output = ()>,
+{
+    let mut updates = updates.fuse();
+    let mut running = FuturesUnordered::new();
+    // Chats with an update being handled, along with their next updates.
+    let mut busy_chats: HashMap<i64, VecDeque<UpdateWithCx<Upd>>> =
+        HashMap::new();
+
+    let mut start = |cx: UpdateWithCx<Upd>| {
+        let chat_id = cx.chat_id();
+        f(cx).map(move |()| chat_id)
+    };
+
+    loop {
+        let chat_id = if running.len() < limit && !updates.is_terminated() {
+            futures::select! {
+                cx = updates.next() => {
+                    if let Some(cx) = cx {
+                        match busy_chats.entry(cx.chat_id()) {
+                            Entry::Occupied(waiting) => {
+                                waiting.into_mut().push_back(cx)
+                            }
+                            Entry::Vacant(chat) => {
+                                chat.insert(VecDeque::new());
+                                running.push(start(cx));
+                            }
+                        }
+                    }
+                    continue;
+                }
+                chat_id = running.select_next_some() => chat_id,
+            }
+        } else {
+            match running.next().await {
+                Some(chat_id) => chat_id,
+                None => break,
+            }
+        };
+
+        if let Entry::Occupied(mut waiting) = busy_chats.entry(chat_id) {
+            match waiting.get_mut().pop_front() {
+                Some(cx) => running.push(start(cx)),
+                None => {
+                    waiting.remove();
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        types::UpdateBuilder,
+        utils::command::{BotCommand, MentionSyntax},
+        Bot,
+    };
+    use futures::stream;
+    use std::{
+        sync::{
+            atomic::{AtomicUsize, Ordering},
+            Mutex,
+        },
+        time::Duration,
+    };
+
+    #[derive(BotCommand, Debug, PartialEq)]
+    #[command(rename = "lowercase")]
+    enum Command {
+        Help,
+        Ban,
+    }
+
+    fn messages(texts: &[&str]) -> Vec<UpdateWithCx<Message>> {
+        let bot = Bot::new("");
+        texts
+            .iter()
+            .map(|text| UpdateWithCx {
+                bot: Arc::clone(&bot),
+                update: UpdateBuilder::message().text(*text).build_message(),
+            })
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn commands_use_default_syntaxes() {
+        let texts = ["/help", "hi ${/ban 3}", "@bot ban", "just text"];
+        let commands: Vec<_> = stream::iter(messages(&texts))
+            .commands::<Command, _>("bot")
+            .map(|(_, command, args)| (command, args))
+            .collect()
+            .await;
+
+        assert_eq!(
+            commands,
+            vec![(Command::Help, vec![]), (Command::Ban, vec!["3".to_owned()])]
+        );
+    }
+
+    #[tokio::test]
+    async fn commands_with_custom_syntax() {
+        let texts = ["/help", "@bot ban 3"];
+        let commands: Vec<_> = stream::iter(messages(&texts))
+            .commands_with_syntax::<Command, _, _>("bot", MentionSyntax::new())
+            .map(|(_, command, args)| (command, args))
+            .collect()
+            .await;
+
+        assert_eq!(commands, vec![(Command::Ban, vec!["3".to_owned()])]);
+    }
+
+    #[tokio::test]
+    async fn commands_in_captions() {
+        let bot = Bot::new("");
+        let update = UpdateBuilder::message()
+            .photo(vec![])
+            .caption("/ban 3")
+            .build_message();
+        let photo = UpdateWithCx { bot, update };
+        let commands: Vec<_> = stream::iter(vec![photo])
+            .commands::<Command, _>("bot")
+            .map(|(_, command, args)| (command, args))
+            .collect()
+            .await;
+
+        assert_eq!(commands, vec![(Command::Ban, vec!["3".to_owned()])]);
+    }
+
+    fn chat_messages(messages: &[(i64, &str)]) -> Vec<UpdateWithCx<Message>> {
+        let bot = Bot::new("");
+        messages
+            .iter()
+            .map(|(chat_id, text)| UpdateWithCx {
+                bot: Arc::clone(&bot),
+                update: UpdateBuilder::message()
+                    .in_group(1, *chat_id)
+                    .text(*text)
+                    .build_message(),
+            })
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn per_chat_keeps_order_within_chat() {
+        let handled = Arc::new(Mutex::new(Vec::new()));
+        let messages =
+            chat_messages(&[(1, "slow"), (1, "a"), (2, "b"), (1, "c")]);
+
+        let log = Arc::clone(&handled);
+        stream::iter(messages)
+            .for_each_per_chat(None, move |cx| {
+                let log = Arc::clone(&log);
+                async move {
+                    let text = cx.update.text_owned().unwrap();
+                    if text == "slow" {
+                        tokio::time::delay_for(Duration::from_millis(50)).await;
+                    }
+                    log.lock().unwrap().push(text);
+                }
+            })
+            .await;
+
+        assert_eq!(*handled.lock().unwrap(), vec!["b", "slow", "a", "c"]);
+    }
+
+    #[tokio::test]
+    async fn per_chat_limits_concurrency() {
+        let running = Arc::new(AtomicUsize::new(0));
+        let max_running = Arc::new(AtomicUsize::new(0));
+        let messages = chat_messages(&[(1, "a"), (2, "b"), (3, "c"), (4, "d")]);
+
+        let (now, max) = (Arc::clone(&running), Arc::clone(&max_running));
+        stream::iter(messages)
+            .for_each_per_chat(2, move |_| {
+                let (now, max) = (Arc::clone(&now), Arc::clone(&max));
+                async move {
+                    let running = now.fetch_add(1, Ordering::SeqCst) + 1;
+                    max.fetch_max(running, Ordering::SeqCst);
+                    tokio::time::delay_for(Duration::from_millis(10)).await;
+                    now.fetch_sub(1, Ordering::SeqCst);
+                }
+            })
+            .await;
+
+        assert_eq!(max_running.load(Ordering::SeqCst), 2);
+    }
+
+    fn album_message(id: i64, media_group_id: Option<&str>) -> Message {
+        let builder = UpdateBuilder::message().message_id(id).photo(vec![]);
+        match media_group_id {
+            Some(media_group_id) => builder.media_group_id(media_group_id),
+            None => builder,
+        }
+        .build_message()
+    }
+
+    fn ids(album: UpdateWithCx<Vec<Message>>) -> Vec<i64> {
+        album.update.iter().map(|message| message.id).collect()
+    }
+
+    #[tokio::test]
+    async fn media_groups_wait_for_window() {
+        time::pause();
+        let bot = Bot::new("");
+        let (tx, rx) = futures::channel::mpsc::unbounded();
+        let mut albums = rx.media_groups(Duration::from_millis(100));
+        let send = |id, media_group_id| {
+            tx.unbounded_send(UpdateWithCx {
+                bot: Arc::clone(&bot),
+                update: album_message(id, media_group_id),
+            })
+            .unwrap()
+        };
+
+        send(2, Some("a"));
+        send(1, Some("a"));
+        send(3, None);
+        send(4, Some("b"));
+        assert_eq!(ids(albums.next().await.unwrap()), [3]);
+
+        time::advance(Duration::from_millis(50)).await;
+        send(5, Some("b"));
+        assert!(albums.next().now_or_never().is_none());
+
+        time::advance(Duration::from_millis(50)).await;
+        assert_eq!(ids(albums.next().await.unwrap()), [1, 2]);
+        assert!(albums.next().now_or_never().is_none());
+
+        time::advance(Duration::from_millis(50)).await;
+        assert_eq!(ids(albums.next().await.unwrap()), [4, 5]);
+
+        drop(tx);
+        assert!(albums.next().await.is_none());
+    }
+
+    #[tokio::test]
+    async fn media_groups_flush_at_end() {
+        let bot = Bot::new("");
+        let messages: Vec<_> = [(1, Some("a")), (2, None), (3, Some("a"))]
+            .iter()
+            .map(|&(id, media_group_id)| UpdateWithCx {
+                bot: Arc::clone(&bot),
+                update: album_message(id, media_group_id),
+            })
+            .collect();
+
+        let albums: Vec<_> = stream::iter(messages)
+            .media_groups(Duration::from_secs(60))
+            .map(ids)
+            .collect()
+            .await;
+
+        assert_eq!(albums, [vec![2], vec![1, 3]]);
+    }
 }
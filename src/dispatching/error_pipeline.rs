@@ -0,0 +1,671 @@
+//! Handling errors returned by handlers.
+//!
+//! Instead of calling [`OnError`] inside of every handler, a handler can
+//! return a `Result` and leave its errors to an [`ErrorPipeline`]. The
+//! pipeline passes each error through a chain of [`ErrorPolicy`]s, such as:
+//!
+//!  - [`Retry`], which runs the handler again;
+//!  - [`ReportMetrics`], which reports errors to a [`MetricsSink`];
+//!  - [`NotifyChat`], which notifies a chat (e.g. of admins);
+//!  - [`ReplyToUser`], which tells the user that something went wrong;
+//!  - [`LogError`], which logs errors.
+//!
+//! A pipeline is built for one kind of updates, so each kind gets its own
+//! policies:
+//!
+//! ```no_run
+//! use std::time::Duration;
+//! use teloxide::{
+//!     dispatching::error_pipeline::{
+//!         ErrorPipeline, LogError, NotifyChat, ReplyToUser, Retry,
+//!     },
+//!     prelude::*,
+//!     types::CallbackQuery,
+//! };
+//!
+//! # #[tokio::main]
+//! # async fn main_() {
+//! const ADMIN_CHAT: i64 = 123;
+//!
+//! let messages = ErrorPipeline::new("message")
+//!     .policy(Retry::new(3).delay(Duration::from_secs(1)).when(
+//!         |error: &RequestError| {
+//!             matches!(error, RequestError::NetworkError(_))
+//!         },
+//!     ))
+//!     .policy(NotifyChat::new(ADMIN_CHAT))
+//!     .policy(ReplyToUser::new());
+//! let callback_queries: ErrorPipeline<CallbackQuery, RequestError> =
+//!     ErrorPipeline::new("callback_query").policy(LogError::new());
+//!
+//! Dispatcher::new(Bot::from_env())
+//!     .messages_handler(messages.handler(
+//!         |cx: UpdateWithCx<Message>| async move {
+//!             cx.answer_str("pong").await?;
+//!             Ok(())
+//!         },
+//!     ))
+//!     .callback_queries_handler(callback_queries.handler(
+//!         |cx: UpdateWithCx<CallbackQuery>| async move {
+//!             cx.bot.answer_callback_query(cx.update.id).send().await?;
+//!             Ok(())
+//!         },
+//!     ))
+//!     .dispatch()
+//!     .await;
+//! # }
+//! ```
+//!
+//! [`OnError`]: crate::error_handlers::OnError
+
+use std::{fmt::Debug, future::Future, sync::Arc, time::Duration};
+
+use futures::{future::BoxFuture, StreamExt};
+
+use crate::{
+    dispatching::{DispatcherHandler, DispatcherHandlerRx, UpdateWithCx},
+    requests::{Request, ResponseResult},
+    types::{CallbackQuery, ChatId, Message},
+};
+
+/// A failed attempt to handle an update, passed to [`ErrorPolicy`]s.
+///
+/// [`ErrorPolicy`]: crate::dispatching::error_pipeline::ErrorPolicy
+#[derive(Debug)]
+pub struct Failure<'a, Upd, E> {
+    /// The kind of updates the [`ErrorPipeline`] was built for.
+    ///
+    /// [`ErrorPipeline`]: crate::dispatching::error_pipeline::ErrorPipeline
+    pub kind: &'static str,
+
+    /// The update, which the handler failed to handle.
+    pub cx: &'a UpdateWithCx<Upd>,
+
+    /// The error returned by the handler.
+    pub error: &'a E,
+
+    /// The number of the failed attempt, starting from 1.
+    pub attempt: usize,
+}
+
+/// What an [`ErrorPipeline`] does after an [`ErrorPolicy`] has seen an
+/// error.
+///
+/// [`ErrorPipeline`]: crate::dispatching::error_pipeline::ErrorPipeline
+/// [`ErrorPolicy`]: crate::dispatching::error_pipeline::ErrorPolicy
+#[derive(Copy, Clone, Debug, Eq, Hash, PartialEq)]
+pub enum PolicyDecision {
+    /// Pass the error to the next policy.
+    Continue,
+
+    /// Don't pass the error to the rest of the policies.
+    Stop,
+
+    /// Run the handler again after the delay, without passing the error to
+    /// the rest of the policies.
+    Retry(Duration),
+}
+
+/// A step of an [`ErrorPipeline`].
+///
+/// [`ErrorPipeline`]: crate::dispatching::error_pipeline::ErrorPipeline
+pub trait ErrorPolicy<Upd, E>: Send + Sync {
+    #[must_use]
+    fn on_error<'a>(
+        &'a self,
+        failure: &'a Failure<'a, Upd, E>,
+    ) -> BoxFuture<'a, PolicyDecision>;
+}
+
+/// A chain of [`ErrorPolicy`]s applied to errors of handlers of one kind of
+/// updates.
+///
+/// See [the module-level documentation](crate::dispatching::error_pipeline).
+///
+/// [`ErrorPolicy`]: crate::dispatching::error_pipeline::ErrorPolicy
+pub struct ErrorPipeline<Upd, E> {
+    kind: &'static str,
+    policies: Vec<Box<dyn ErrorPolicy<Upd, E>>>,
+}
+
+impl<Upd, E> ErrorPipeline<Upd, E>
+where
+    Upd: Clone + Send + Sync + 'static,
+    E: Send + Sync + 'static,
+{
+    /// Creates a pipeline without policies, which drops all errors.
+    ///
+    /// `kind` names the kind of updates in [`Failure`]s, e.g. in metrics.
+    ///
+    /// [`Failure`]: crate::dispatching::error_pipeline::Failure
+    #[must_use]
+    pub fn new(kind: &'static str) -> Self {
+        Self { kind, policies: Vec::new() }
+    }
+
+    /// Appends `policy` to the chain.
+    #[must_use]
+    pub fn policy<P>(mut self, policy: P) -> Self
+    where
+        P: ErrorPolicy<Upd, E> + 'static,
+    {
+        self.policies.push(Box::new(policy));
+        self
+    }
+
+    /// Handles `cx` with `handler`, passing its errors through the policies.
+    pub async fn run<H, Fut>(&self, cx: UpdateWithCx<Upd>, handler: &H)
+    where
+        H: Fn(UpdateWithCx<Upd>) -> Fut,
+        Fut: Future<Output = Result<(), E>>,
+    {
+        let mut attempt = 1;
+
+        while let Err(error) = handler(cx.clone()).await {
+            let failure =
+                Failure { kind: self.kind, cx: &cx, error: &error, attempt };
+
+            match self.decide(&failure).await {
+                Some(delay) => tokio::time::delay_for(delay).await,
+                None => return,
+            }
+            attempt += 1;
+        }
+    }
+
+    /// Returns a [`Dispatcher`]'s handler, which handles updates one by one
+    /// with `handler`, passing its errors through the policies.
+    ///
+    /// [`Dispatcher`]: crate::dispatching::Dispatcher
+    pub fn handler<H, Fut>(self, handler: H) -> impl DispatcherHandler<Upd>
+    where
+        H: Fn(UpdateWithCx<Upd>) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<(), E>> + Send + 'static,
+    {
+        let pipeline = Arc::new(self);
+        let handler = Arc::new(handler);
+
+        move |rx: DispatcherHandlerRx<Upd>| {
+            rx.for_each(move |cx| {
+                let pipeline = Arc::clone(&pipeline);
+                let handler = Arc::clone(&handler);
+                async move { pipeline.run(cx, &*handler).await }
+            })
+        }
+    }
+
+    /// Returns the delay before the next attempt, if any.
+    async fn decide(&self, failure: &Failure<'_, Upd, E>) -> Option<Duration> {
+        for policy in &self.policies {
+            match policy.on_error(failure).await {
+                PolicyDecision::Continue => {}
+                PolicyDecision::Stop => return None,
+                PolicyDecision::Retry(delay) => return Some(delay),
+            }
+        }
+        None
+    }
+}
+
+type Predicate<E> = Box<dyn Fn(&E) -> bool + Send + Sync>;
+
+/// Runs a handler again, up to a limited number of attempts.
+///
+/// Once the attempts are exhausted (or the error isn't retriable), the error
+/// is passed to the next policy.
+pub struct Retry<E> {
+    max_attempts: usize,
+    delay: Duration,
+    when: Option<Predicate<E>>,
+}
+
+impl<E> Retry<E> {
+    /// Handles an update at most `max_attempts` times, including the first
+    /// attempt.
+    #[must_use]
+    pub fn new(max_attempts: usize) -> Self {
+        Self { max_attempts, delay: Duration::from_secs(0), when: None }
+    }
+
+    /// A delay between attempts. Zero by default.
+    #[must_use]
+    pub fn delay(mut self, val: Duration) -> Self {
+        self.delay = val;
+        self
+    }
+
+    /// Retries only errors for which `predicate` returns `true`.
+    #[must_use]
+    pub fn when<F>(mut self, predicate: F) -> Self
+    where
+        F: Fn(&E) -> bool + Send + Sync + 'static,
+    {
+        self.when = Some(Box::new(predicate));
+        self
+    }
+}
+
+impl<Upd, E> ErrorPolicy<Upd, E> for Retry<E>
+where
+    Upd: Sync,
+    E: Sync,
+{
+    fn on_error<'a>(
+        &'a self,
+        failure: &'a Failure<'a, Upd, E>,
+    ) -> BoxFuture<'a, PolicyDecision> {
+        let retriable = match &self.when {
+            Some(when) => when(failure.error),
+            None => true,
+        };
+        let decision = if retriable && failure.attempt < self.max_attempts {
+            PolicyDecision::Retry(self.delay)
+        } else {
+            PolicyDecision::Continue
+        };
+        Box::pin(async move { decision })
+    }
+}
+
+/// Logs errors.
+#[derive(Clone, Debug)]
+pub struct LogError {
+    text: String,
+}
+
+impl LogError {
+    /// Logs errors in the format
+    /// `{text} ({kind}, attempt {attempt}): {error:?}`.
+    #[must_use]
+    pub fn with_custom_text<T>(text: T) -> Self
+    where
+        T: Into<String>,
+    {
+        Self { text: text.into() }
+    }
+
+    /// A shortcut for `LogError::with_custom_text("Error")`.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::with_custom_text("Error")
+    }
+}
+
+impl Default for LogError {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<Upd, E> ErrorPolicy<Upd, E> for LogError
+where
+    Upd: Sync,
+    E: Debug + Sync,
+{
+    fn on_error<'a>(
+        &'a self,
+        failure: &'a Failure<'a, Upd, E>,
+    ) -> BoxFuture<'a, PolicyDecision> {
+        log::error!(
+            "{text} ({kind}, attempt {attempt}): {error:?}",
+            text = self.text,
+            kind = failure.kind,
+            attempt = failure.attempt,
+            error = failure.error
+        );
+        Box::pin(async { PolicyDecision::Continue })
+    }
+}
+
+/// An error reported to a [`MetricsSink`].
+///
+/// [`MetricsSink`]: crate::dispatching::error_pipeline::MetricsSink
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct ErrorEvent {
+    /// The kind of updates the [`ErrorPipeline`] was built for.
+    ///
+    /// [`ErrorPipeline`]: crate::dispatching::error_pipeline::ErrorPipeline
+    pub kind: &'static str,
+
+    /// The error, formatted with [`Debug`].
+    ///
+    /// [`Debug`]: std::fmt::Debug
+    pub error: String,
+
+    /// The number of the failed attempt, starting from 1.
+    pub attempt: usize,
+}
+
+/// A receiver of [`ErrorEvent`]s, e.g. a metrics exporter.
+///
+/// Implemented for `Fn(ErrorEvent)` closures.
+///
+/// [`ErrorEvent`]: crate::dispatching::error_pipeline::ErrorEvent
+pub trait MetricsSink: Send + Sync {
+    fn record(&self, event: ErrorEvent);
+}
+
+impl<F> MetricsSink for F
+where
+    F: Fn(ErrorEvent) + Send + Sync,
+{
+    fn record(&self, event: ErrorEvent) {
+        self(event)
+    }
+}
+
+/// Reports every failed attempt to a [`MetricsSink`].
+///
+/// [`MetricsSink`]: crate::dispatching::error_pipeline::MetricsSink
+pub struct ReportMetrics<S> {
+    sink: S,
+}
+
+impl<S> ReportMetrics<S>
+where
+    S: MetricsSink,
+{
+    #[must_use]
+    pub fn new(sink: S) -> Self {
+        Self { sink }
+    }
+}
+
+impl<Upd, E, S> ErrorPolicy<Upd, E> for ReportMetrics<S>
+where
+    Upd: Sync,
+    E: Debug + Sync,
+    S: MetricsSink,
+{
+    fn on_error<'a>(
+        &'a self,
+        failure: &'a Failure<'a, Upd, E>,
+    ) -> BoxFuture<'a, PolicyDecision> {
+        self.sink.record(ErrorEvent {
+            kind: failure.kind,
+            error: format!("{:?}", failure.error),
+            attempt: failure.attempt,
+        });
+        Box::pin(async { PolicyDecision::Continue })
+    }
+}
+
+/// Sends a description of errors to a chat, e.g. a chat of admins.
+#[derive(Clone, Debug)]
+pub struct NotifyChat {
+    chat_id: ChatId,
+}
+
+impl NotifyChat {
+    #[must_use]
+    pub fn new<C>(chat_id: C) -> Self
+    where
+        C: Into<ChatId>,
+    {
+        Self { chat_id: chat_id.into() }
+    }
+}
+
+impl<Upd, E> ErrorPolicy<Upd, E> for NotifyChat
+where
+    Upd: Sync,
+    E: Debug + Sync,
+{
+    fn on_error<'a>(
+        &'a self,
+        failure: &'a Failure<'a, Upd, E>,
+    ) -> BoxFuture<'a, PolicyDecision> {
+        Box::pin(async move {
+            let text = format!(
+                "An error while handling an update ({}, attempt {}): {:?}",
+                failure.kind, failure.attempt, failure.error
+            );
+            if let Err(error) = failure
+                .cx
+                .bot
+                .send_message(self.chat_id.clone(), text)
+                .send()
+                .await
+            {
+                log::error!("Failed to notify a chat of an error: {:?}", error);
+            }
+            PolicyDecision::Continue
+        })
+    }
+}
+
+/// An update, which can be answered with an error notice by
+/// [`ReplyToUser`].
+///
+/// [`ReplyToUser`]: crate::dispatching::error_pipeline::ReplyToUser
+pub trait ReplyOnError: Sized {
+    #[must_use]
+    fn reply_on_error<'a>(
+        cx: &'a UpdateWithCx<Self>,
+        text: &'a str,
+    ) -> BoxFuture<'a, ResponseResult<()>>;
+}
+
+impl ReplyOnError for Message {
+    fn reply_on_error<'a>(
+        cx: &'a UpdateWithCx<Self>,
+        text: &'a str,
+    ) -> BoxFuture<'a, ResponseResult<()>> {
+        Box::pin(async move { cx.reply_to(text).send().await.map(drop) })
+    }
+}
+
+impl ReplyOnError for CallbackQuery {
+    fn reply_on_error<'a>(
+        cx: &'a UpdateWithCx<Self>,
+        text: &'a str,
+    ) -> BoxFuture<'a, ResponseResult<()>> {
+        Box::pin(async move {
+            cx.bot
+                .answer_callback_query(cx.update.id.clone())
+                .text(text)
+                .show_alert(true)
+                .send()
+                .await
+                .map(drop)
+        })
+    }
+}
+
+/// Tells the user, whose update has failed, that something went wrong.
+#[derive(Clone, Debug)]
+pub struct ReplyToUser {
+    text: String,
+}
+
+impl ReplyToUser {
+    /// Replies with "Something went wrong, please try again later.".
+    #[must_use]
+    pub fn new() -> Self {
+        Self::with_text("Something went wrong, please try again later.")
+    }
+
+    #[must_use]
+    pub fn with_text<T>(text: T) -> Self
+    where
+        T: Into<String>,
+    {
+        Self { text: text.into() }
+    }
+}
+
+impl Default for ReplyToUser {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<Upd, E> ErrorPolicy<Upd, E> for ReplyToUser
+where
+    Upd: ReplyOnError + Sync,
+    E: Sync,
+{
+    fn on_error<'a>(
+        &'a self,
+        failure: &'a Failure<'a, Upd, E>,
+    ) -> BoxFuture<'a, PolicyDecision> {
+        Box::pin(async move {
+            if let Err(error) =
+                Upd::reply_on_error(failure.cx, &self.text).await
+            {
+                log::error!("Failed to reply to a user: {:?}", error);
+            }
+            PolicyDecision::Continue
+        })
+    }
+}
+
+/// Stops the chain for errors matching the predicate, e.g. to keep expected
+/// errors away from the admins.
+pub struct StopWhen<E> {
+    predicate: Predicate<E>,
+}
+
+impl<E> StopWhen<E> {
+    #[must_use]
+    pub fn new<F>(predicate: F) -> Self
+    where
+        F: Fn(&E) -> bool + Send + Sync + 'static,
+    {
+        Self { predicate: Box::new(predicate) }
+    }
+}
+
+impl<Upd, E> ErrorPolicy<Upd, E> for StopWhen<E>
+where
+    Upd: Sync,
+    E: Sync,
+{
+    fn on_error<'a>(
+        &'a self,
+        failure: &'a Failure<'a, Upd, E>,
+    ) -> BoxFuture<'a, PolicyDecision> {
+        let decision = if (self.predicate)(failure.error) {
+            PolicyDecision::Stop
+        } else {
+            PolicyDecision::Continue
+        };
+        Box::pin(async move { decision })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        testing::{MockResponse, MockServer},
+        types::UpdateBuilder,
+        RequestError,
+    };
+    use serde_json::json;
+    use std::sync::{
+        atomic::{AtomicUsize, Ordering},
+        Mutex,
+    };
+
+    #[derive(Debug, PartialEq)]
+    enum TestError {
+        Transient,
+        Fatal,
+    }
+
+    fn cx(bot: Arc<crate::Bot>) -> UpdateWithCx<Message> {
+        let update = UpdateBuilder::message()
+            .message_id(5)
+            .in_group(1, 10)
+            .build_message();
+        UpdateWithCx { bot, update }
+    }
+
+    #[tokio::test]
+    async fn retries_then_succeeds() {
+        let server = MockServer::start().await;
+        let attempts = AtomicUsize::new(0);
+        let pipeline = ErrorPipeline::new("message")
+            .policy(Retry::new(3).when(|e| *e == TestError::Transient));
+
+        pipeline
+            .run(cx(server.bot()), &|_| async {
+                match attempts.fetch_add(1, Ordering::SeqCst) {
+                    0 | 1 => Err(TestError::Transient),
+                    _ => Ok(()),
+                }
+            })
+            .await;
+
+        assert_eq!(attempts.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test]
+    async fn exhausted_retries_reach_next_policies() {
+        let server = MockServer::start().await;
+        let events = Arc::new(Mutex::new(Vec::new()));
+        let sink = {
+            let events = Arc::clone(&events);
+            move |event| events.lock().unwrap().push(event)
+        };
+        let pipeline = ErrorPipeline::new("message")
+            .policy(ReportMetrics::new(sink))
+            .policy(Retry::new(2).when(|e| *e == TestError::Transient))
+            .policy(StopWhen::new(|e| *e == TestError::Transient));
+
+        pipeline
+            .run(cx(server.bot()), &|_| async { Err(TestError::Transient) })
+            .await;
+        pipeline
+            .run(cx(server.bot()), &|_| async { Err(TestError::Fatal) })
+            .await;
+
+        let event = |error: &str, attempt| ErrorEvent {
+            kind: "message",
+            error: error.to_owned(),
+            attempt,
+        };
+        assert_eq!(
+            *events.lock().unwrap(),
+            vec![
+                event("Transient", 1),
+                event("Transient", 2),
+                event("Fatal", 1)
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn notifies_admins_and_replies_to_user() {
+        let server = MockServer::start().await;
+        let message = cx(server.bot()).update;
+        server.respond_always(
+            "sendMessage",
+            MockResponse::ok(serde_json::to_value(&message).unwrap()),
+        );
+        let pipeline = ErrorPipeline::new("message")
+            .policy(NotifyChat::new(99))
+            .policy(ReplyToUser::with_text("Oops"));
+
+        pipeline
+            .run(cx(server.bot()), &|_| async {
+                Err(RequestError::RetryAfter(1))
+            })
+            .await;
+
+        let calls = server.calls_to("sendMessage");
+        assert_eq!(calls.len(), 2);
+        assert_eq!(calls[0].params.get("chat_id"), Some(json!(99)));
+        assert_eq!(
+            calls[0].params.get("text"),
+            Some(json!(
+                "An error while handling an update (message, attempt 1): \
+                 RetryAfter(1)"
+            ))
+        );
+        assert_eq!(calls[1].params.get("chat_id"), Some(json!(10)));
+        assert_eq!(calls[1].params.get("reply_to_message_id"), Some(json!(5)));
+        assert_eq!(calls[1].params.get("text"), Some(json!("Oops")));
+    }
+}
@@ -0,0 +1,441 @@
+//! Cross-cutting logic around handlers.
+//!
+//! A [`Middleware`] receives an update before a handler does and decides
+//! what the handler gets: the same update, a transformed or enriched one, or
+//! nothing at all. This is the place for authorization checks, allow-lists
+//! of guilds, logging, detecting a locale and the like.
+//!
+//! Middlewares can be applied either to updates of all kinds, with
+//! [`Dispatcher::middleware`], or to a single handler, with
+//! [`Middleware::wrap`]. They are composed with [`Middleware::then`] and run
+//! in the order they were added:
+//!
+//! ```no_run
+//! use teloxide::{
+//!     dispatching::middleware::{self, Middleware},
+//!     prelude::*,
+//!     types::{Update, UpdateKind},
+//! };
+//!
+//! # #[tokio::main]
+//! # async fn main_() {
+//! const ALLOWED_GUILD: i64 = 42;
+//!
+//! let only_allowed_guild = middleware::filter(|cx: &UpdateWithCx<Update>| {
+//!     match &cx.update.kind {
+//!         UpdateKind::Message(message) => {
+//!             message.chat.guild_id == ALLOWED_GUILD
+//!         }
+//!         _ => true,
+//!     }
+//! });
+//! let log = middleware::inspect(|cx: &UpdateWithCx<Update>| {
+//!     log::info!("Update #{} is accepted", cx.update.id)
+//! });
+//! let with_locale = middleware::map(|message: Message| {
+//!     let locale = message
+//!         .from()
+//!         .and_then(|user| user.language_code.clone())
+//!         .unwrap_or_else(|| "en".to_owned());
+//!     (message, locale)
+//! });
+//!
+//! Dispatcher::new(Bot::from_env())
+//!     .middleware(only_allowed_guild.then(log))
+//!     .messages_handler(with_locale.wrap(
+//!         |rx: DispatcherHandlerRx<(Message, String)>| {
+//!             rx.for_each(|cx| async move {
+//!                 let (message, locale) = cx.update;
+//!                 log::info!("#{} in {}", message.id, locale);
+//!             })
+//!         },
+//!     ))
+//!     .dispatch()
+//!     .await;
+//! # }
+//! ```
+//!
+//! [`Dispatcher::middleware`]: crate::dispatching::Dispatcher::middleware
+
+use std::{future::Future, marker::PhantomData, sync::Arc};
+
+use futures::{
+    future::{self, BoxFuture},
+    FutureExt, StreamExt,
+};
+
+use crate::dispatching::{
+    dispatcher_handler_rx::{queue, QueueControl},
+    DispatcherHandler, DispatcherHandlerRx, PanicCounter, QueuePolicy,
+    UpdateWithCx,
+};
+
+/// A step between a [`Dispatcher`] and a handler.
+///
+/// Returning `None` short-circuits an update: neither the following
+/// middlewares nor the handler receive it. Besides the implementations in
+/// [this module](crate::dispatching::middleware), middlewares are
+/// asynchronous functions:
+///
+/// ```
+/// use teloxide::{
+///     dispatching::middleware::Middleware, prelude::*, types::Update,
+/// };
+///
+/// fn log() -> impl Middleware<Update, Output = Update> {
+///     |cx: UpdateWithCx<Update>| async move {
+///         log::debug!("{:?}", cx.update);
+///         Some(cx)
+///     }
+/// }
+/// ```
+///
+/// [`Dispatcher`]: crate::dispatching::Dispatcher
+pub trait Middleware<Upd>: Send + Sync {
+    /// The type of updates passed further.
+    type Output: Send + 'static;
+
+    #[must_use]
+    fn handle<'a>(
+        &'a self,
+        cx: UpdateWithCx<Upd>,
+    ) -> BoxFuture<'a, Option<UpdateWithCx<Self::Output>>>
+    where
+        Upd: 'a;
+
+    /// Returns a middleware, which passes updates through `self` and then
+    /// through `next`.
+    #[must_use]
+    fn then<M>(self, next: M) -> Chain<Self, M>
+    where
+        Self: Sized,
+        M: Middleware<Self::Output>,
+    {
+        Chain { first: self, second: next }
+    }
+
+    /// Returns a [`Dispatcher`]'s handler, which passes updates through
+    /// `self` to `handler`.
+    ///
+    /// [`Dispatcher`]: crate::dispatching::Dispatcher
+    #[must_use]
+    fn wrap<H>(self, handler: H) -> Wrap<Self, H>
+    where
+        Self: Sized,
+        H: DispatcherHandler<Self::Output>,
+    {
+        Wrap { middleware: self, handler }
+    }
+}
+
+impl<Upd, Out, F, Fut> Middleware<Upd> for F
+where
+    F: Fn(UpdateWithCx<Upd>) -> Fut + Send + Sync,
+    Out: Send + 'static,
+    Fut: Future<Output = Option<UpdateWithCx<Out>>> + Send + 'static,
+{
+    type Output = Out;
+
+    fn handle<'a>(
+        &'a self,
+        cx: UpdateWithCx<Upd>,
+    ) -> BoxFuture<'a, Option<UpdateWithCx<Out>>>
+    where
+        Upd: 'a,
+    {
+        Box::pin(self(cx))
+    }
+}
+
+/// Two middlewares, one after another.
+///
+/// See [`Middleware::then`].
+///
+/// [`Middleware::then`]: crate::dispatching::middleware::Middleware::then
+pub struct Chain<A, B> {
+    first: A,
+    second: B,
+}
+
+impl<Upd, A, B> Middleware<Upd> for Chain<A, B>
+where
+    Upd: Send,
+    A: Middleware<Upd>,
+    B: Middleware<A::Output>,
+{
+    type Output = B::Output;
+
+    fn handle<'a>(
+        &'a self,
+        cx: UpdateWithCx<Upd>,
+    ) -> BoxFuture<'a, Option<UpdateWithCx<B::Output>>>
+    where
+        Upd: 'a,
+    {
+        Box::pin(async move {
+            let cx = self.first.handle(cx).await?;
+            self.second.handle(cx).await
+        })
+    }
+}
+
+/// Returns a middleware, which passes only updates satisfying `predicate`.
+#[must_use]
+pub fn filter<Upd, P>(predicate: P) -> Filter<Upd, P>
+where
+    P: Fn(&UpdateWithCx<Upd>) -> bool + Send + Sync,
+{
+    Filter { predicate, phantom: PhantomData }
+}
+
+/// A middleware returned by [`filter`].
+///
+/// [`filter`]: crate::dispatching::middleware::filter
+pub struct Filter<Upd, P> {
+    predicate: P,
+    phantom: PhantomData<fn(Upd)>,
+}
+
+impl<Upd, P> Middleware<Upd> for Filter<Upd, P>
+where
+    Upd: Send + 'static,
+    P: Fn(&UpdateWithCx<Upd>) -> bool + Send + Sync,
+{
+    type Output = Upd;
+
+    fn handle<'a>(
+        &'a self,
+        cx: UpdateWithCx<Upd>,
+    ) -> BoxFuture<'a, Option<UpdateWithCx<Upd>>>
+    where
+        Upd: 'a,
+    {
+        let passed = (self.predicate)(&cx);
+        Box::pin(future::ready(if passed { Some(cx) } else { None }))
+    }
+}
+
+/// Returns a middleware, which calls `f` on each update and passes it
+/// unchanged.
+#[must_use]
+pub fn inspect<Upd, F>(f: F) -> Inspect<Upd, F>
+where
+    F: Fn(&UpdateWithCx<Upd>) + Send + Sync,
+{
+    Inspect { f, phantom: PhantomData }
+}
+
+/// A middleware returned by [`inspect`].
+///
+/// [`inspect`]: crate::dispatching::middleware::inspect
+pub struct Inspect<Upd, F> {
+    f: F,
+    phantom: PhantomData<fn(Upd)>,
+}
+
+impl<Upd, F> Middleware<Upd> for Inspect<Upd, F>
+where
+    Upd: Send + 'static,
+    F: Fn(&UpdateWithCx<Upd>) + Send + Sync,
+{
+    type Output = Upd;
+
+    fn handle<'a>(
+        &'a self,
+        cx: UpdateWithCx<Upd>,
+    ) -> BoxFuture<'a, Option<UpdateWithCx<Upd>>>
+    where
+        Upd: 'a,
+    {
+        (self.f)(&cx);
+        Box::pin(future::ready(Some(cx)))
+    }
+}
+
+/// Returns a middleware, which replaces each update with the result of `f`.
+///
+/// It's the way to enrich updates, e.g. by pairing them with data from a
+/// database.
+#[must_use]
+pub fn map<Upd, Out, F>(f: F) -> Map<Upd, F>
+where
+    F: Fn(Upd) -> Out + Send + Sync,
+{
+    Map { f, phantom: PhantomData }
+}
+
+/// A middleware returned by [`map`].
+///
+/// [`map`]: crate::dispatching::middleware::map
+pub struct Map<Upd, F> {
+    f: F,
+    phantom: PhantomData<fn(Upd)>,
+}
+
+impl<Upd, Out, F> Middleware<Upd> for Map<Upd, F>
+where
+    Upd: Send,
+    Out: Send + 'static,
+    F: Fn(Upd) -> Out + Send + Sync,
+{
+    type Output = Out;
+
+    fn handle<'a>(
+        &'a self,
+        cx: UpdateWithCx<Upd>,
+    ) -> BoxFuture<'a, Option<UpdateWithCx<Out>>>
+    where
+        Upd: 'a,
+    {
+        let UpdateWithCx { bot, update } = cx;
+        let update = (self.f)(update);
+        Box::pin(future::ready(Some(UpdateWithCx { bot, update })))
+    }
+}
+
+/// A handler behind a middleware.
+///
+/// See [`Middleware::wrap`].
+///
+/// [`Middleware::wrap`]: crate::dispatching::middleware::Middleware::wrap
+pub struct Wrap<M, H> {
+    middleware: M,
+    handler: H,
+}
+
+impl<Upd, M, H> DispatcherHandler<Upd> for Wrap<M, H>
+where
+    Upd: Send + 'static,
+    M: Middleware<Upd> + 'static,
+    H: DispatcherHandler<M::Output>,
+{
+    fn handle(self, updates: DispatcherHandlerRx<Upd>) -> BoxFuture<'static, ()>
+    where
+        UpdateWithCx<Upd>: Send + 'static,
+    {
+        // A single-update queue keeps the backpressure of the dispatcher's
+        // queue: the next update is taken only after the handler has taken
+        // the previous one.
+        let control = Arc::new(QueueControl::default());
+        control.set_limit(1, QueuePolicy::Block);
+        let (tx, rx) = queue("updates", PanicCounter::default(), control);
+        let rx = rx.with_dependencies(updates.dependencies().clone());
+
+        let middleware = self.middleware;
+        let forward = async move {
+            let mut updates = updates;
+            while let Some(cx) = updates.next().await {
+                let update_id = updates.in_flight();
+                if let Some(cx) = middleware.handle(cx).await {
+                    if tx.send(update_id, cx).await.is_err() {
+                        break;
+                    }
+                }
+            }
+        };
+
+        // Once the handler has stopped, the queue is abandoned, so that the
+        // forwarding stops instead of waiting for a free place forever.
+        let queue = rx.reopen();
+        let handle = self.handler.handle(rx).map(move |()| queue.abandon());
+
+        future::join(forward, handle).map(drop).boxed()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Bot;
+    use std::{sync::Mutex, time::Duration};
+
+    fn updates(numbers: &[i32]) -> DispatcherHandlerRx<i32> {
+        numbers
+            .iter()
+            .map(|&update| UpdateWithCx { bot: Bot::new(""), update })
+            .collect()
+    }
+
+    async fn handle<M>(middleware: M, numbers: &[i32]) -> Vec<M::Output>
+    where
+        M: Middleware<i32> + 'static,
+    {
+        let (tx, rx) = tokio::sync::oneshot::channel();
+        let handler = |rx: DispatcherHandlerRx<M::Output>| async move {
+            let handled: Vec<_> = rx.map(|cx| cx.update).collect().await;
+            let _ = tx.send(handled);
+        };
+
+        middleware.wrap(handler).handle(updates(numbers)).await;
+        rx.await.unwrap()
+    }
+
+    #[tokio::test]
+    async fn short_circuit() {
+        let handled = handle(
+            filter(|cx: &UpdateWithCx<i32>| cx.update % 2 == 0),
+            &[1, 2, 3, 4],
+        )
+        .await;
+
+        assert_eq!(handled, vec![2, 4]);
+    }
+
+    #[tokio::test]
+    async fn enrich() {
+        let handled = handle(map(|n: i32| (n, n.to_string())), &[1, 2]).await;
+
+        assert_eq!(handled, vec![(1, "1".to_owned()), (2, "2".to_owned())]);
+    }
+
+    #[tokio::test]
+    async fn stopped_handler() {
+        let (tx, rx) = tokio::sync::oneshot::channel();
+        let handler = |rx: DispatcherHandlerRx<i32>| async move {
+            let handled: Vec<_> =
+                rx.take(1).map(|cx| cx.update).collect().await;
+            let _ = tx.send(handled);
+        };
+
+        let handle = inspect(|_: &UpdateWithCx<i32>| {})
+            .wrap(handler)
+            .handle(updates(&[1, 2, 3]));
+        tokio::time::timeout(Duration::from_secs(5), handle).await.unwrap();
+
+        assert_eq!(rx.await.unwrap(), vec![1]);
+    }
+
+    #[tokio::test]
+    async fn chain_in_order() {
+        let seen = Arc::new(Mutex::new(Vec::new()));
+        let record = |stage: &'static str| {
+            let seen = Arc::clone(&seen);
+            inspect(move |cx: &UpdateWithCx<i32>| {
+                seen.lock().unwrap().push((stage, cx.update))
+            })
+        };
+
+        let middleware = record("first")
+            .then(|cx: UpdateWithCx<i32>| async move {
+                if cx.update == 2 {
+                    None
+                } else {
+                    Some(UpdateWithCx { bot: cx.bot, update: cx.update * 10 })
+                }
+            })
+            .then(record("second"));
+        let handled = handle(middleware, &[1, 2, 3]).await;
+
+        assert_eq!(handled, vec![10, 30]);
+        assert_eq!(
+            *seen.lock().unwrap(),
+            vec![
+                ("first", 1),
+                ("second", 10),
+                ("first", 2),
+                ("first", 3),
+                ("second", 30),
+            ]
+        );
+    }
+}
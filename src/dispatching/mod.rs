@@ -1,12 +1,12 @@
 //! Updates dispatching.
 //!
 //! The key type here is [`Dispatcher`]. It encapsulates [`Bot`] and handlers
-//! for [the 11 update kinds].
+//! for [the 12 update kinds].
 //!
-//! You can register a maximum of 11 handlers for [the 11 update kinds]. Every
-//! handler accept [`tokio::sync::mpsc::UnboundedReceiver`] (the RX halve of an
-//! asynchronous unbounded MPSC channel). Inside a body of your handler, you
-//! typically asynchronously concurrently iterate through updates like this:
+//! You can register a maximum of 12 handlers for [the 12 update kinds]. Every
+//! handler accept [`DispatcherHandlerRx`] (a stream of updates of its kind).
+//! Inside a body of your handler, you typically asynchronously concurrently
+//! iterate through updates like this:
 //!
 //! ```
 //! use teloxide::prelude::*;
@@ -25,12 +25,18 @@
 //! **Note** that handlers must implement [`DispatcherHandler`], which means
 //! that:
 //!  - You are able to supply [`DialogueDispatcher`] as a handler.
-//!  - You are able to supply functions that accept
-//!    [`tokio::sync::mpsc::UnboundedReceiver`] and return `Future<Output = ()`
-//!    as a handler.
+//!  - You are able to supply functions that accept [`DispatcherHandlerRx`]
+//!    and return `Future<Output = ()` as a handler.
 //!
 //! Since they implement [`DispatcherHandler`] too!
 //!
+//! Handlers of single updates, which return `Result`, can be turned into a
+//! [`DispatcherHandler`] by an [`ErrorPipeline`], which applies retries,
+//! notifications and other policies to their errors. Logic shared by
+//! handlers, such as authorization checks, belongs to [`middleware`]s, and
+//! updates of one kind can be split between several handlers by a
+//! [`Router`].
+//!
 //! # Examples
 //! ### The ping-pong bot
 //! This bot has a single message handler, which answers "pong" to each incoming
@@ -67,30 +73,39 @@
 //! [See more examples](https://github.com/teloxide/teloxide/tree/master/examples).
 //!
 //! [`Dispatcher`]: crate::dispatching::Dispatcher
-//! [the 11 update kinds]: crate::types::UpdateKind
+//! [the 12 update kinds]: crate::types::UpdateKind
 //! [`Update`]: crate::types::Update
 //! [`ErrorHandler`]: crate::dispatching::ErrorHandler
 //! [`DispatcherHandler`]: crate::dispatching::DispatcherHandler
+//! [`ErrorPipeline`]: crate::dispatching::ErrorPipeline
+//! [`middleware`]: crate::dispatching::middleware
+//! [`Router`]: crate::dispatching::router::Router
 //! [`DialogueDispatcher`]: crate::dispatching::dialogue::DialogueDispatcher
 //! [`DispatcherHandlerResult`]: crate::dispatching::DispatcherHandlerResult
 //! [`Bot`]: crate::Bot
-//! [`tokio::sync::mpsc::UnboundedReceiver`]: https://docs.rs/tokio/0.2.11/tokio/sync/mpsc/struct.UnboundedReceiver.html
+//! [`DispatcherHandlerRx`]: crate::dispatching::DispatcherHandlerRx
 //! [examples/dialogue_bot]: https://github.com/teloxide/teloxide/tree/master/examples/dialogue_bot
 
 pub mod dialogue;
+mod dependencies;
 mod dispatcher;
 mod dispatcher_handler;
+mod dispatcher_handler_rx;
 mod dispatcher_handler_rx_ext;
+pub mod error_pipeline;
+pub mod middleware;
+pub mod router;
 pub mod update_listeners;
 mod update_with_cx;
 
+pub use dependencies::Dependencies;
 pub use dispatcher::Dispatcher;
-pub use dispatcher_handler::DispatcherHandler;
+pub use dispatcher_handler::{
+    restart_on_panic, DispatcherHandler, RestartOnPanic,
+};
+pub use dispatcher_handler_rx::{
+    DispatcherHandlerRx, PanicCounter, QueueMetrics, QueuePolicy,
+};
 pub use dispatcher_handler_rx_ext::DispatcherHandlerRxExt;
-use tokio::sync::mpsc::UnboundedReceiver;
+pub use error_pipeline::ErrorPipeline;
 pub use update_with_cx::UpdateWithCx;
-
-/// A type of a stream, consumed by [`Dispatcher`]'s handlers.
-///
-/// [`Dispatcher`]: crate::dispatching::Dispatcher
-pub type DispatcherHandlerRx<Upd> = UnboundedReceiver<UpdateWithCx<Upd>>;
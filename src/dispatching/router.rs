@@ -0,0 +1,595 @@
+//! Routing updates of one kind to several handlers.
+//!
+//! Instead of branching by hand inside a single handler, a [`Router`] sends
+//! each update to the first route whose [`Predicate`] it satisfies, or to
+//! the fallback route. Each route is an ordinary [`DispatcherHandler`], so
+//! routers can be nested.
+//!
+//! ```no_run
+//! use teloxide::{
+//!     dispatching::{
+//!         router::{self, Predicate, Router},
+//!         DispatcherHandler,
+//!     },
+//!     prelude::*,
+//!     utils::command::BotCommand,
+//! };
+//!
+//! #[derive(BotCommand)]
+//! #[command(rename = "lowercase")]
+//! enum Command {
+//!     Help,
+//!     Ban,
+//! }
+//!
+//! fn answer(text: &'static str) -> impl DispatcherHandler<Message> {
+//!     move |rx: DispatcherHandlerRx<Message>| {
+//!         rx.for_each(move |cx| async move {
+//!             cx.answer_str(text).await.log_on_error().await;
+//!         })
+//!     }
+//! }
+//!
+//! # #[tokio::main]
+//! # async fn main_() {
+//! const GUILD: i64 = 42;
+//!
+//! let messages = Router::new()
+//!     .route(router::command::<Command>("my_bot"), answer("A command"))
+//!     .route(router::guild(GUILD).and(router::photo()), answer("A photo"))
+//!     .route(router::private(), answer("A private message"))
+//!     .route(router::text_matches(r"^(hi|hello)\b"), answer("Hello!"))
+//!     .route(router::reply_to_bot(), answer("A reply to a bot"))
+//!     .fallback(answer("Something else"));
+//!
+//! Dispatcher::new(Bot::from_env())
+//!     .messages_handler(messages)
+//!     .dispatch()
+//!     .await;
+//! # }
+//! ```
+//!
+//! [`DispatcherHandler`]: crate::dispatching::DispatcherHandler
+
+use std::sync::Arc;
+
+use futures::{
+    future::{self, BoxFuture},
+    FutureExt, StreamExt,
+};
+use regex::Regex;
+
+use crate::{
+    dispatching::{
+        dispatcher_handler_rx::{queue, QueueControl, QueueTx},
+        DispatcherHandler, DispatcherHandlerRx, PanicCounter, QueuePolicy,
+        UpdateWithCx,
+    },
+    types::{MediaKind, Message, MessageKind},
+    utils::command::{default_syntaxes, BotCommand},
+};
+
+/// A condition of a route of a [`Router`].
+///
+/// Besides the predicates in [this module](crate::dispatching::router),
+/// predicates are functions of `&UpdateWithCx<Upd>` returning `bool`.
+///
+/// [`Router`]: crate::dispatching::router::Router
+pub trait Predicate<Upd>: Send + Sync {
+    fn matches(&self, cx: &UpdateWithCx<Upd>) -> bool;
+
+    /// Returns a predicate satisfied by updates satisfying both `self` and
+    /// `other`.
+    #[must_use]
+    fn and<P>(self, other: P) -> And<Self, P>
+    where
+        Self: Sized,
+        P: Predicate<Upd>,
+    {
+        And(self, other)
+    }
+
+    /// Returns a predicate satisfied by updates satisfying `self`, `other`
+    /// or both.
+    #[must_use]
+    fn or<P>(self, other: P) -> Or<Self, P>
+    where
+        Self: Sized,
+        P: Predicate<Upd>,
+    {
+        Or(self, other)
+    }
+
+    /// Returns a predicate satisfied by updates not satisfying `self`.
+    #[must_use]
+    fn not(self) -> Not<Self>
+    where
+        Self: Sized,
+    {
+        Not(self)
+    }
+}
+
+impl<Upd, F> Predicate<Upd> for F
+where
+    F: Fn(&UpdateWithCx<Upd>) -> bool + Send + Sync,
+{
+    fn matches(&self, cx: &UpdateWithCx<Upd>) -> bool {
+        self(cx)
+    }
+}
+
+/// A predicate returned by [`Predicate::and`].
+///
+/// [`Predicate::and`]: crate::dispatching::router::Predicate::and
+pub struct And<A, B>(A, B);
+
+impl<Upd, A, B> Predicate<Upd> for And<A, B>
+where
+    A: Predicate<Upd>,
+    B: Predicate<Upd>,
+{
+    fn matches(&self, cx: &UpdateWithCx<Upd>) -> bool {
+        self.0.matches(cx) && self.1.matches(cx)
+    }
+}
+
+/// A predicate returned by [`Predicate::or`].
+///
+/// [`Predicate::or`]: crate::dispatching::router::Predicate::or
+pub struct Or<A, B>(A, B);
+
+impl<Upd, A, B> Predicate<Upd> for Or<A, B>
+where
+    A: Predicate<Upd>,
+    B: Predicate<Upd>,
+{
+    fn matches(&self, cx: &UpdateWithCx<Upd>) -> bool {
+        self.0.matches(cx) || self.1.matches(cx)
+    }
+}
+
+/// A predicate returned by [`Predicate::not`].
+///
+/// [`Predicate::not`]: crate::dispatching::router::Predicate::not
+pub struct Not<P>(P);
+
+impl<Upd, P> Predicate<Upd> for Not<P>
+where
+    P: Predicate<Upd>,
+{
+    fn matches(&self, cx: &UpdateWithCx<Upd>) -> bool {
+        !self.0.matches(cx)
+    }
+}
+
+/// Messages from the guild with the identifier `guild_id`.
+#[must_use]
+pub fn guild(guild_id: i64) -> impl Predicate<Message> {
+    move |cx: &UpdateWithCx<Message>| cx.update.chat.guild_id == guild_id
+}
+
+/// Messages from the channel (or the private chat) with the identifier
+/// `channel_id`.
+#[must_use]
+pub fn channel(channel_id: i64) -> impl Predicate<Message> {
+    move |cx: &UpdateWithCx<Message>| cx.update.chat.id == channel_id
+}
+
+/// Messages from private chats.
+#[must_use]
+pub fn private() -> impl Predicate<Message> {
+    |cx: &UpdateWithCx<Message>| cx.update.chat.is_private()
+}
+
+/// Messages from public chats, i.e. channels, groups and supergroups.
+#[must_use]
+pub fn public() -> impl Predicate<Message> {
+    |cx: &UpdateWithCx<Message>| !cx.update.chat.is_private()
+}
+
+/// Messages with photos.
+#[must_use]
+pub fn photo() -> impl Predicate<Message> {
+    media(|media| matches!(media, MediaKind::Photo(_)))
+}
+
+/// Messages whose [`MediaKind`] satisfies `f`.
+///
+/// [`MediaKind`]: crate::types::MediaKind
+#[must_use]
+pub fn media<F>(f: F) -> impl Predicate<Message>
+where
+    F: Fn(&MediaKind) -> bool + Send + Sync,
+{
+    move |cx: &UpdateWithCx<Message>| match &cx.update.kind {
+        MessageKind::Common(common) => f(&common.media_kind),
+        _ => false,
+    }
+}
+
+/// Messages with commands of `C`, written in one of the
+/// [default syntaxes] in the text or the caption.
+///
+/// [default syntaxes]: crate::utils::command::default_syntaxes
+#[must_use]
+pub fn command<C>(bot_name: &str) -> impl Predicate<Message>
+where
+    C: BotCommand,
+{
+    let bot_name = bot_name.to_owned();
+    let syntaxes = default_syntaxes();
+
+    move |cx: &UpdateWithCx<Message>| {
+        matches!(
+            text_or_caption(&cx.update),
+            Some(text) if C::parse_with(text, &bot_name, &syntaxes).is_some()
+        )
+    }
+}
+
+/// Messages whose text (or caption) matches the regular expression `re`.
+///
+/// # Panics
+/// If `re` is not a valid regular expression.
+#[must_use]
+pub fn text_matches(re: &str) -> impl Predicate<Message> {
+    let re = Regex::new(re).expect("Invalid regular expression");
+
+    move |cx: &UpdateWithCx<Message>| {
+        matches!(
+            text_or_caption(&cx.update),
+            Some(text) if re.is_match(text)
+        )
+    }
+}
+
+fn text_or_caption(message: &Message) -> Option<&str> {
+    message.text().or_else(|| message.caption())
+}
+
+/// Replies to messages of bots.
+///
+/// See [`reply_to_user`] to match replies to a particular bot.
+///
+/// [`reply_to_user`]: crate::dispatching::router::reply_to_user
+#[must_use]
+pub fn reply_to_bot() -> impl Predicate<Message> {
+    |cx: &UpdateWithCx<Message>| {
+        matches!(
+            replied_user(&cx.update),
+            Some(user) if user.is_bot
+        )
+    }
+}
+
+/// Replies to messages of the user (or the bot) with the identifier
+/// `user_id`.
+#[must_use]
+pub fn reply_to_user(user_id: i64) -> impl Predicate<Message> {
+    move |cx: &UpdateWithCx<Message>| {
+        matches!(
+            replied_user(&cx.update),
+            Some(user) if user.id == user_id
+        )
+    }
+}
+
+fn replied_user(message: &Message) -> Option<&crate::types::User> {
+    message.reply_to_message()?.from()
+}
+
+type BoxedHandler<Upd> =
+    Box<dyn FnOnce(DispatcherHandlerRx<Upd>) -> BoxFuture<'static, ()> + Send>;
+
+fn boxed<Upd, H>(handler: H) -> BoxedHandler<Upd>
+where
+    Upd: Send + 'static,
+    H: DispatcherHandler<Upd> + Send + 'static,
+{
+    Box::new(move |rx| handler.handle(rx))
+}
+
+struct Route<Upd> {
+    predicate: Box<dyn Predicate<Upd>>,
+    handler: BoxedHandler<Upd>,
+}
+
+/// A [`Dispatcher`]'s handler, which sends each update to the first matching
+/// route.
+///
+/// Updates matching no route go to the fallback route, if any, or are
+/// dropped. A route gets the next update only after it has taken the
+/// previous one, so a slow route holds back the others.
+///
+/// See [the module-level documentation](crate::dispatching::router).
+///
+/// [`Dispatcher`]: crate::dispatching::Dispatcher
+pub struct Router<Upd> {
+    routes: Vec<Route<Upd>>,
+    fallback: Option<BoxedHandler<Upd>>,
+}
+
+impl<Upd> Router<Upd>
+where
+    Upd: Send + 'static,
+{
+    /// Creates a router without routes, which drops all updates.
+    #[must_use]
+    pub fn new() -> Self {
+        Self { routes: Vec::new(), fallback: None }
+    }
+
+    /// Appends a route to `handler` of updates satisfying `predicate`.
+    #[must_use]
+    pub fn route<P, H>(mut self, predicate: P, handler: H) -> Self
+    where
+        P: Predicate<Upd> + 'static,
+        H: DispatcherHandler<Upd> + Send + 'static,
+    {
+        self.routes.push(Route {
+            predicate: Box::new(predicate),
+            handler: boxed(handler),
+        });
+        self
+    }
+
+    /// Sets `handler` of updates matching no route.
+    #[must_use]
+    pub fn fallback<H>(mut self, handler: H) -> Self
+    where
+        H: DispatcherHandler<Upd> + Send + 'static,
+    {
+        self.fallback = Some(boxed(handler));
+        self
+    }
+}
+
+impl<Upd> Default for Router<Upd>
+where
+    Upd: Send + 'static,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn route_queue<Upd>() -> (QueueTx<Upd>, DispatcherHandlerRx<Upd>) {
+    let control = Arc::new(QueueControl::default());
+    control.set_limit(1, QueuePolicy::Block);
+    queue("routed updates", PanicCounter::default(), control)
+}
+
+impl<Upd> DispatcherHandler<Upd> for Router<Upd>
+where
+    Upd: Send + 'static,
+{
+    fn handle(self, updates: DispatcherHandlerRx<Upd>) -> BoxFuture<'static, ()>
+    where
+        UpdateWithCx<Upd>: Send + 'static,
+    {
+        let mut handlers = Vec::new();
+        let dependencies = updates.dependencies().clone();
+        let mut start = |handler: BoxedHandler<Upd>| {
+            let (tx, rx) = route_queue();
+            let rx = rx.with_dependencies(dependencies.clone());
+            // Once the handler has stopped, its queue is abandoned, so that
+            // the updates routed to it are dropped instead of blocking the
+            // other routes.
+            let queue = rx.reopen();
+            handlers.push(handler(rx).map(move |()| queue.abandon()));
+            tx
+        };
+
+        let routes: Vec<_> = self
+            .routes
+            .into_iter()
+            .map(|route| (route.predicate, start(route.handler)))
+            .collect();
+        let fallback = self.fallback.map(start);
+
+        let forward = async move {
+            let mut updates = updates;
+            while let Some(cx) = updates.next().await {
+                let tx = routes
+                    .iter()
+                    .find(|(predicate, _)| predicate.matches(&cx))
+                    .map(|(_, tx)| tx)
+                    .or(fallback.as_ref());
+
+                let update_id = updates.in_flight();
+                match tx {
+                    Some(tx) => {
+                        if tx.send(update_id, cx).await.is_err() {
+                            log::error!(
+                                "The handler of a route has stopped, but an \
+                                 update #{:?} is routed to it",
+                                update_id
+                            );
+                        }
+                    }
+                    None => log::debug!(
+                        "The update #{:?} matches no route",
+                        update_id
+                    ),
+                }
+            }
+        };
+
+        future::join(forward, future::join_all(handlers)).map(drop).boxed()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        types::{MediaPhoto, PhotoSize, UpdateBuilder, User},
+        Bot,
+    };
+    use std::time::Duration;
+    use tokio::sync::mpsc;
+
+    #[derive(teloxide_macros::BotCommand)]
+    #[command(rename = "lowercase")]
+    enum Command {
+        Help,
+    }
+
+    fn user(id: i64, is_bot: bool) -> User {
+        User { id, is_bot, ..User::default() }
+    }
+
+    fn handler(
+        name: &'static str,
+        tx: mpsc::UnboundedSender<(&'static str, i64)>,
+    ) -> impl DispatcherHandler<Message> {
+        move |rx: DispatcherHandlerRx<Message>| {
+            rx.for_each(move |cx| {
+                tx.send((name, cx.update.id)).unwrap();
+                async {}
+            })
+        }
+    }
+
+    async fn route(messages: Vec<Message>) -> Vec<(&'static str, i64)> {
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        let router = Router::new()
+            .route(command::<Command>("bot"), handler("command", tx.clone()))
+            .route(guild(7).and(photo()), handler("photo", tx.clone()))
+            .route(private(), handler("private", tx.clone()))
+            .route(text_matches("^hi"), handler("greeting", tx.clone()))
+            .route(reply_to_bot(), handler("reply", tx.clone()))
+            .fallback(handler("fallback", tx));
+
+        let updates = messages
+            .into_iter()
+            .map(|update| UpdateWithCx { bot: Bot::new(""), update })
+            .collect();
+        router.handle(updates).await;
+
+        let mut routed = Vec::new();
+        while let Some(item) = rx.recv().await {
+            routed.push(item);
+        }
+        routed
+    }
+
+    #[tokio::test]
+    async fn routes_in_order() {
+        let photo = MediaKind::Photo(MediaPhoto {
+            photo: vec![PhotoSize {
+                file_id: "id".to_owned(),
+                file_unique_id: "unique_id".to_owned(),
+                width: 1,
+                height: 1,
+                file_size: None,
+            }],
+            caption: None,
+            caption_entities: vec![],
+            media_group_id: None,
+        });
+        let bot_message = UpdateBuilder::message().from(user(1, true));
+        let message = || UpdateBuilder::message().in_group(7, 8);
+
+        let routed = route(vec![
+            message().message_id(1).text("/help").build_message(),
+            message().message_id(2).media(photo.clone()).build_message(),
+            UpdateBuilder::message()
+                .message_id(3)
+                .in_group(9, 8)
+                .media(photo.clone())
+                .build_message(),
+            UpdateBuilder::message()
+                .message_id(4)
+                .in_private(7, 8)
+                .text("hi")
+                .build_message(),
+            message().message_id(5).text("hi there").build_message(),
+            message()
+                .message_id(6)
+                .text("ok")
+                .reply_to(bot_message.text("?").build_message())
+                .build_message(),
+            message().message_id(7).text("bye").build_message(),
+            UpdateBuilder::message()
+                .message_id(8)
+                .in_group(9, 8)
+                .media(photo.clone())
+                .caption("/help")
+                .build_message(),
+            UpdateBuilder::message()
+                .message_id(9)
+                .in_group(9, 8)
+                .media(photo)
+                .caption("hi all")
+                .build_message(),
+        ])
+        .await;
+
+        assert_eq!(routed.len(), 9);
+        let route_of =
+            |id| routed.iter().find(|(_, routed)| *routed == id).unwrap().0;
+        assert_eq!(route_of(1), "command");
+        assert_eq!(route_of(2), "photo");
+        assert_eq!(route_of(3), "fallback");
+        assert_eq!(route_of(4), "private");
+        assert_eq!(route_of(5), "greeting");
+        assert_eq!(route_of(6), "reply");
+        assert_eq!(route_of(7), "fallback");
+        assert_eq!(route_of(8), "command");
+        assert_eq!(route_of(9), "greeting");
+    }
+
+    #[tokio::test]
+    async fn stopped_route() {
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        let once = {
+            let tx = tx.clone();
+            move |rx: DispatcherHandlerRx<Message>| {
+                rx.take(1).for_each(move |cx| {
+                    tx.send(("once", cx.update.id)).unwrap();
+                    async {}
+                })
+            }
+        };
+        let router = Router::new()
+            .route(private(), once)
+            .fallback(handler("fallback", tx));
+
+        let message = |id| UpdateBuilder::message().message_id(id);
+        let updates = vec![
+            message(1).in_private(1, 2).text("a").build_message(),
+            message(2).in_private(1, 2).text("b").build_message(),
+            message(3).in_private(1, 2).text("c").build_message(),
+            message(4).in_group(1, 2).text("d").build_message(),
+        ]
+        .into_iter()
+        .map(|update| UpdateWithCx { bot: Bot::new(""), update })
+        .collect();
+        let handle = router.handle(updates);
+        tokio::time::timeout(Duration::from_secs(5), handle).await.unwrap();
+
+        let mut routed = Vec::new();
+        while let Some(item) = rx.recv().await {
+            routed.push(item);
+        }
+        assert_eq!(routed, vec![("once", 1), ("fallback", 4)]);
+    }
+
+    #[tokio::test]
+    async fn combinators() {
+        let message = UpdateWithCx {
+            bot: Bot::new(""),
+            update: UpdateBuilder::message()
+                .in_group(1, 2)
+                .text("hi")
+                .build_message(),
+        };
+
+        assert!(guild(1).and(channel(2)).matches(&message));
+        assert!(!guild(1).and(channel(3)).matches(&message));
+        assert!(guild(3).or(public()).matches(&message));
+        assert!(private().not().matches(&message));
+    }
+}
@@ -20,7 +20,7 @@ use crate::requests::{SearchGuildMember};
 /// overview](crate::dispatching).
 ///
 /// [`Dispatcher`]: crate::dispatching::Dispatcher
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 pub struct UpdateWithCx<Upd> {
     pub bot: Arc<Bot>,
     pub update: Upd,
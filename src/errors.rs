@@ -12,6 +12,43 @@ pub enum DownloadError {
 
     #[error("An I/O error: {0}")]
     Io(#[source] std::io::Error),
+
+    /// Resolving the file to download has failed.
+    #[error("A request error: {0}")]
+    Request(#[source] RequestError),
+
+    /// The file is larger than the limit set by [`FileDownload::max_size`].
+    ///
+    /// [`FileDownload::max_size`]: crate::net::FileDownload::max_size
+    #[from(ignore)]
+    #[error("The file is larger than {limit} bytes")]
+    TooLarge { limit: u64 },
+
+    /// The size of the file differs from the one set by
+    /// [`FileDownload::expected_size`].
+    ///
+    /// [`FileDownload::expected_size`]: crate::net::FileDownload::expected_size
+    #[from(ignore)]
+    #[error("Expected a file of {expected} bytes, got {actual} bytes")]
+    SizeMismatch { expected: u64, actual: u64 },
+
+    /// The file doesn't have the checksum set by [`FileDownload::checksum`].
+    ///
+    /// [`FileDownload::checksum`]: crate::net::FileDownload::checksum
+    #[from(ignore)]
+    #[error("The checksum of the file doesn't match")]
+    ChecksumMismatch,
+
+    /// The server has responded with a part of the file other than the one
+    /// starting at [`FileDownload::resume_from`].
+    ///
+    /// [`FileDownload::resume_from`]: crate::net::FileDownload::resume_from
+    #[from(ignore)]
+    #[error(
+        "Requested the file from byte {offset}, got the range \
+         {content_range:?}"
+    )]
+    UnexpectedRange { offset: u64, content_range: Option<String> },
 }
 
 //</editor-fold>
@@ -38,6 +75,11 @@ pub enum RequestError {
 
     #[error("An error while parsing JSON: {0}")]
     InvalidJson(#[source] serde_json::Error),
+
+    /// An I/O error while preparing a file for uploading, e.g. a file which
+    /// doesn't exist.
+    #[error("An I/O error: {0}")]
+    Io(#[source] std::io::Error),
 }
 
 //</editor-fold>
@@ -511,6 +553,17 @@ pub enum ApiErrorKind {
     #[serde(rename = "Bad Request: invalid file id")]
     FileIdInvalid,
 
+    /// Occurs when bot tries to send a file by a wrong file id or URL.
+    ///
+    /// May happen in methods:
+    /// 1. [`SendDocument`]
+    /// 2. [`SendPhoto`]
+    ///
+    /// [`SendDocument`]: crate::requests::SendDocument
+    /// [`SendPhoto`]: crate::requests::SendPhoto
+    #[serde(rename = "Bad Request: wrong file identifier/HTTP URL specified")]
+    WrongFileIdOrUrl,
+
     #[serde(other)]
     Other,
 }
@@ -30,6 +30,8 @@ pub mod error_handlers;
 mod logging;
 pub mod prelude;
 pub mod requests;
+#[cfg(feature = "testing")]
+pub mod testing;
 pub mod types;
 pub mod utils;
 
@@ -1,20 +1,27 @@
-use reqwest::Client;
+use std::{
+    fmt::{self, Debug},
+    path::Path,
+};
+
+use bytes::Bytes;
+use futures::{Stream, StreamExt};
+use reqwest::{header, Client, Response, StatusCode};
 use tokio::io::{AsyncWrite, AsyncWriteExt};
 
 use crate::errors::DownloadError;
 
-
 pub async fn download_file<D>(
     client: &Client,
     token: &str,
+    api_url: &str,
     path: &str,
     destination: &mut D,
 ) -> Result<(), DownloadError>
-    where
-        D: AsyncWrite + Unpin,
+where
+    D: AsyncWrite + Unpin,
 {
     let mut res = client
-        .get(&super::file_url(token, path))
+        .get(&super::file_url(api_url, token, path))
         .send()
         .await?
         .error_for_status()?;
@@ -26,14 +33,14 @@ pub async fn download_file<D>(
     Ok(())
 }
 
-#[cfg(feature = "unstable-stream")]
 pub async fn download_file_stream(
     client: &Client,
     token: &str,
+    api_url: &str,
     path: &str,
-) -> Result<impl Stream<Item=reqwest::Result<Bytes>>, reqwest::Error> {
+) -> Result<impl Stream<Item = reqwest::Result<Bytes>>, reqwest::Error> {
     let res = client
-        .get(&super::file_url(TELEGRAM_API_URL, token, path))
+        .get(&super::file_url(api_url, token, path))
         .send()
         .await?
         .error_for_status()?;
@@ -46,3 +53,467 @@ pub async fn download_file_stream(
         }
     }))
 }
+
+/// A checksum of a downloaded file, verified by [`FileDownload::checksum`].
+///
+/// The crate doesn't implement any hash functions, so implement this trait
+/// for the one the file was hashed with, e.g. using the `sha2` crate.
+///
+/// [`FileDownload::checksum`]: crate::net::FileDownload::checksum
+pub trait Checksum: Send {
+    /// Feeds the next chunk of the file.
+    fn update(&mut self, chunk: &[u8]);
+
+    /// Returns `true` if the chunks fed so far have the expected checksum.
+    fn verify(&mut self) -> bool;
+}
+
+/// A download of a file from the Bot API server.
+///
+/// Created by [`Bot::download`]. Unlike [`Bot::download_file`], it can resume
+/// an interrupted download and verify the downloaded file.
+///
+/// ## Examples
+///
+/// ```no_run
+/// use teloxide::{requests::Request, types::File as TgFile, Bot};
+///
+/// # async fn run() -> Result<(), Box<dyn std::error::Error>> {
+/// let bot = Bot::new("TOKEN");
+/// let TgFile { file_path, file_size, .. } =
+///     bot.get_file("*file_id*").send().await?;
+///
+/// // Continue downloading into a partially downloaded file.
+/// let downloaded = tokio::fs::metadata("video.mp4").await?.len();
+/// bot.download(&file_path)
+///     .resume_from(downloaded)
+///     .expected_size(file_size.into())
+///     .max_size(20 * 1024 * 1024)
+///     .to_path("video.mp4")
+///     .await?;
+/// # Ok(()) }
+/// ```
+///
+/// [`Bot::download`]: crate::Bot::download
+/// [`Bot::download_file`]: crate::Bot::download_file
+pub struct FileDownload {
+    client: Client,
+    url: String,
+    offset: u64,
+    max_size: Option<u64>,
+    expected_size: Option<u64>,
+    checksum: Option<Box<dyn Checksum>>,
+}
+
+impl FileDownload {
+    pub(crate) fn new(
+        client: &Client,
+        token: &str,
+        api_url: &str,
+        path: &str,
+    ) -> Self {
+        Self {
+            client: client.clone(),
+            url: super::file_url(api_url, token, path),
+            offset: 0,
+            max_size: None,
+            expected_size: None,
+            checksum: None,
+        }
+    }
+
+    /// Downloads only the part of the file starting at `offset` bytes, e.g.
+    /// the size of a partially downloaded file.
+    ///
+    /// The part is requested with the `Range` header. If the server ignores
+    /// it, the first `offset` bytes are downloaded and skipped. If the server
+    /// responds with another part, or says that there is nothing after
+    /// `offset` while the file isn't `offset` bytes long, the download fails
+    /// with [`DownloadError::UnexpectedRange`].
+    ///
+    /// [`DownloadError::UnexpectedRange`]: crate::DownloadError::UnexpectedRange
+    pub fn resume_from(mut self, offset: u64) -> Self {
+        self.offset = offset;
+        self
+    }
+
+    /// Fails with [`DownloadError::TooLarge`] if the file is larger than
+    /// `limit` bytes, without downloading more than `limit` bytes.
+    ///
+    /// [`DownloadError::TooLarge`]: crate::DownloadError::TooLarge
+    pub fn max_size(mut self, limit: u64) -> Self {
+        self.max_size = Some(limit);
+        self
+    }
+
+    /// Fails with [`DownloadError::SizeMismatch`] if the size of the file
+    /// isn't `size` bytes, e.g. the [`File::file_size`] from
+    /// [`Bot::get_file`].
+    ///
+    /// [`DownloadError::SizeMismatch`]: crate::DownloadError::SizeMismatch
+    /// [`File::file_size`]: crate::types::File::file_size
+    /// [`Bot::get_file`]: crate::Bot::get_file
+    pub fn expected_size(mut self, size: u64) -> Self {
+        self.expected_size = Some(size);
+        self
+    }
+
+    /// Fails with [`DownloadError::ChecksumMismatch`] if the downloaded bytes
+    /// don't have the `checksum`.
+    ///
+    /// When resuming, only the bytes after the offset are fed to the
+    /// checksum, so feed the beginning of the file to it beforehand.
+    ///
+    /// [`DownloadError::ChecksumMismatch`]: crate::DownloadError::ChecksumMismatch
+    pub fn checksum<C>(mut self, checksum: C) -> Self
+    where
+        C: Checksum + 'static,
+    {
+        self.checksum = Some(Box::new(checksum));
+        self
+    }
+
+    /// Starts the download and returns the file as a stream of chunks.
+    ///
+    /// The checks are done while streaming: a stream of an invalid file ends
+    /// with an error.
+    pub async fn stream(
+        self,
+    ) -> Result<impl Stream<Item = Result<Bytes, DownloadError>>, DownloadError>
+    {
+        let mut request = self.client.get(&self.url);
+        if self.offset > 0 {
+            request = request
+                .header(header::RANGE, format!("bytes={}-", self.offset));
+        }
+        let response = request.send().await?;
+
+        let content_range = response
+            .headers()
+            .get(header::CONTENT_RANGE)
+            .and_then(|range| range.to_str().ok())
+            .map(ToOwned::to_owned);
+        let offset = self.offset;
+        let unexpected_range = || DownloadError::UnexpectedRange {
+            offset,
+            content_range: content_range.clone(),
+        };
+
+        let (response, skip) = match response.status() {
+            // The whole file has already been downloaded, if it's exactly
+            // `offset` bytes long.
+            StatusCode::RANGE_NOT_SATISFIABLE if offset > 0 => {
+                let size = content_range
+                    .as_deref()
+                    .and_then(range_total)
+                    .or(self.expected_size);
+                if size != Some(offset) {
+                    return Err(unexpected_range());
+                }
+                (None, 0)
+            }
+            StatusCode::PARTIAL_CONTENT => {
+                if content_range.as_deref().and_then(range_start)
+                    != Some(offset)
+                {
+                    return Err(unexpected_range());
+                }
+                (Some(response.error_for_status()?), 0)
+            }
+            _ => (Some(response.error_for_status()?), offset),
+        };
+
+        if let (Some(limit), Some(response)) = (self.max_size, &response) {
+            let size =
+                response.content_length().unwrap_or(0) + self.offset - skip;
+            if size > limit {
+                return Err(DownloadError::TooLarge { limit });
+            }
+        }
+
+        let state = State {
+            response,
+            skip,
+            downloaded: self.offset,
+            max_size: self.max_size,
+            expected_size: self.expected_size,
+            checksum: self.checksum,
+        };
+        Ok(futures::stream::unfold(Some(state), |state| async {
+            let mut state = state?;
+            match state.next().await {
+                Ok(Some(chunk)) => Some((Ok(chunk), Some(state))),
+                Ok(None) => None,
+                Err(error) => Some((Err(error), None)),
+            }
+        }))
+    }
+
+    /// Downloads the file into `destination` and returns the number of
+    /// written bytes.
+    pub async fn to<D>(self, destination: &mut D) -> Result<u64, DownloadError>
+    where
+        D: AsyncWrite + Unpin,
+    {
+        let stream = self.stream().await?;
+        futures::pin_mut!(stream);
+
+        let mut written = 0;
+        while let Some(chunk) = stream.next().await {
+            let chunk = chunk?;
+            destination.write_all(&chunk).await?;
+            written += chunk.len() as u64;
+        }
+        destination.flush().await?;
+        Ok(written)
+    }
+
+    /// Downloads the file into the file at `path` and returns the number of
+    /// written bytes.
+    ///
+    /// When resuming, the file is appended to, otherwise it's truncated.
+    pub async fn to_path<P>(self, path: P) -> Result<u64, DownloadError>
+    where
+        P: AsRef<Path>,
+    {
+        let mut file = tokio::fs::OpenOptions::new()
+            .create(true)
+            .write(true)
+            .append(self.offset > 0)
+            .truncate(self.offset == 0)
+            .open(path)
+            .await?;
+        self.to(&mut file).await
+    }
+}
+
+impl Debug for FileDownload {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("FileDownload")
+            .field("offset", &self.offset)
+            .field("max_size", &self.max_size)
+            .field("expected_size", &self.expected_size)
+            .field("checksum", &self.checksum.is_some())
+            .finish()
+    }
+}
+
+/// The first byte of a `Content-Range` like `bytes 4-9/10`.
+fn range_start(content_range: &str) -> Option<u64> {
+    let range = content_range.strip_prefix("bytes ")?;
+    range.split('-').next()?.trim().parse().ok()
+}
+
+/// The size of the file from a `Content-Range` like `bytes 4-9/10` or
+/// `bytes */10`.
+fn range_total(content_range: &str) -> Option<u64> {
+    let range = content_range.strip_prefix("bytes ")?;
+    range.rsplit('/').next()?.trim().parse().ok()
+}
+
+struct State {
+    response: Option<Response>,
+    /// The number of bytes to skip if the server has ignored `Range`.
+    skip: u64,
+    /// The number of bytes downloaded so far, including the offset.
+    downloaded: u64,
+    max_size: Option<u64>,
+    expected_size: Option<u64>,
+    checksum: Option<Box<dyn Checksum>>,
+}
+
+impl State {
+    /// Returns the next chunk or checks the file after the last one.
+    async fn next(&mut self) -> Result<Option<Bytes>, DownloadError> {
+        loop {
+            let response = match &mut self.response {
+                Some(response) => response,
+                None => return self.finish().map(|_| None),
+            };
+            let mut chunk = match response.chunk().await? {
+                Some(chunk) => chunk,
+                None => {
+                    self.response = None;
+                    continue;
+                }
+            };
+
+            if self.skip > 0 {
+                let skipped = self.skip.min(chunk.len() as u64);
+                self.skip -= skipped;
+                chunk = chunk.slice(skipped as usize..);
+                if chunk.is_empty() {
+                    continue;
+                }
+            }
+
+            self.downloaded += chunk.len() as u64;
+            if let Some(limit) = self.max_size {
+                if self.downloaded > limit {
+                    return Err(DownloadError::TooLarge { limit });
+                }
+            }
+            if let Some(checksum) = &mut self.checksum {
+                checksum.update(&chunk);
+            }
+            return Ok(Some(chunk));
+        }
+    }
+
+    fn finish(&mut self) -> Result<(), DownloadError> {
+        if let Some(expected) = self.expected_size {
+            if self.downloaded != expected {
+                return Err(DownloadError::SizeMismatch {
+                    expected,
+                    actual: self.downloaded,
+                });
+            }
+        }
+        if let Some(checksum) = &mut self.checksum {
+            if !checksum.verify() {
+                return Err(DownloadError::ChecksumMismatch);
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::testing::{MockResponse, MockServer};
+
+    /// The sum of bytes.
+    struct Sum {
+        sum: u64,
+        expected: u64,
+    }
+
+    impl Checksum for Sum {
+        fn update(&mut self, chunk: &[u8]) {
+            self.sum += chunk.iter().map(|&b| u64::from(b)).sum::<u64>();
+        }
+
+        fn verify(&mut self) -> bool {
+            self.sum == self.expected
+        }
+    }
+
+    async fn download(
+        download: FileDownload,
+    ) -> Result<Vec<u8>, DownloadError> {
+        let mut data = Vec::new();
+        download.to(&mut data).await?;
+        Ok(data)
+    }
+
+    #[tokio::test]
+    async fn resume() {
+        let server = MockServer::start().await;
+        server.serve_file("photos/a.jpg", &b"0123456789"[..]);
+        let bot = server.bot();
+
+        let data = download(bot.download("photos/a.jpg")).await.unwrap();
+        assert_eq!(data, b"0123456789");
+
+        let rest =
+            bot.download("photos/a.jpg").resume_from(4).expected_size(10);
+        assert_eq!(download(rest).await.unwrap(), b"456789");
+
+        let done =
+            bot.download("photos/a.jpg").resume_from(10).expected_size(10);
+        assert_eq!(download(done).await.unwrap(), b"");
+
+        // The size of the file is taken from `Content-Range`.
+        let done = bot.download("photos/a.jpg").resume_from(10);
+        assert_eq!(download(done).await.unwrap(), b"");
+
+        let past_end = bot.download("photos/a.jpg").resume_from(12);
+        assert!(matches!(
+            download(past_end).await,
+            Err(DownloadError::UnexpectedRange { offset: 12, .. })
+        ));
+
+        assert!(matches!(
+            download(bot.download("photos/b.jpg")).await,
+            Err(DownloadError::NetworkError(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn checks() {
+        let server = MockServer::start().await;
+        server.serve_file("a.txt", &b"\x01\x02\x03"[..]);
+        let bot = server.bot();
+
+        assert!(matches!(
+            download(bot.download("a.txt").max_size(2)).await,
+            Err(DownloadError::TooLarge { limit: 2 })
+        ));
+        assert!(matches!(
+            download(bot.download("a.txt").expected_size(4)).await,
+            Err(DownloadError::SizeMismatch { expected: 4, actual: 3 })
+        ));
+        assert!(matches!(
+            download(
+                bot.download("a.txt").checksum(Sum { sum: 0, expected: 7 })
+            )
+            .await,
+            Err(DownloadError::ChecksumMismatch)
+        ));
+
+        let valid = bot
+            .download("a.txt")
+            .max_size(3)
+            .expected_size(3)
+            .checksum(Sum { sum: 0, expected: 6 });
+        assert_eq!(download(valid).await.unwrap(), b"\x01\x02\x03");
+    }
+
+    #[tokio::test]
+    async fn download_to_path() {
+        let server = MockServer::start().await;
+        server
+            .respond(
+                "getFile",
+                MockResponse::ok(json!({
+                    "file_id": "id",
+                    "file_unique_id": "unique",
+                    "file_size": 5,
+                    "file_path": "documents/a.txt"
+                })),
+            )
+            .serve_file("documents/a.txt", &b"hello"[..]);
+        let path = std::env::temp_dir()
+            .join(format!("teloxide-download-{}.txt", std::process::id()));
+
+        let file = server.bot().download_to_path("id", &path).await.unwrap();
+        assert_eq!(file.file_path, "documents/a.txt");
+        assert_eq!(tokio::fs::read(&path).await.unwrap(), b"hello");
+
+        // Resume the download into the partially downloaded file.
+        tokio::fs::write(&path, b"he").await.unwrap();
+        server
+            .bot()
+            .download("documents/a.txt")
+            .resume_from(2)
+            .to_path(&path)
+            .await
+            .unwrap();
+        assert_eq!(tokio::fs::read(&path).await.unwrap(), b"hello");
+
+        tokio::fs::remove_file(&path).await.unwrap();
+    }
+
+    #[test]
+    fn content_range() {
+        assert_eq!(range_start("bytes 4-9/10"), Some(4));
+        assert_eq!(range_total("bytes 4-9/10"), Some(10));
+        assert_eq!(range_start("bytes */10"), None);
+        assert_eq!(range_total("bytes */10"), Some(10));
+        assert_eq!(range_total("bytes 4-9/*"), None);
+        assert_eq!(range_start("4-9/10"), None);
+    }
+}
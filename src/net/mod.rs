@@ -1,25 +1,25 @@
-#[cfg(feature = "unstable-stream")]
-pub use download::download_file_stream;
-
 pub use self::{
-    download::download_file,
+    download::{download_file, download_file_stream, Checksum, FileDownload},
     request::{request_json, request_multipart},
     telegram_response::TelegramResponse,
+    transport::{
+        FormPart, HttpBody, HttpRequest, HttpResponse, PartData, PartStream,
+        ReqwestTransport, Transport, TransportMiddleware,
+    },
 };
+pub(crate) use self::transport::{Layered, SharedTransport};
 
 mod download;
 mod request;
 mod telegram_response;
+mod transport;
 
-use std::env;
-
-const TELEGRAM_API_URL: &str = "https://api.telegram.org";
+pub(crate) const TELEGRAM_API_URL: &str = "https://api.telegram.org";
 
 /// Creates URL for making HTTPS requests. See the [Telegram documentation].
 ///
 /// [Telegram documentation]: https://core.telegram.org/bots/api#making-requests
-fn method_url(token: &str, method_name: &str) -> String {
-    let base = env::var("TELEGRAM_API_URL").ok().unwrap_or(TELEGRAM_API_URL.to_string());
+fn method_url(base: &str, token: &str, method_name: &str) -> String {
     format!(
         "{url}/bot{token}/{method}",
         url = base,
@@ -31,8 +31,7 @@ fn method_url(token: &str, method_name: &str) -> String {
 /// Creates URL for downloading a file. See the [Telegram documentation].
 ///
 /// [Telegram documentation]: https://core.telegram.org/bots/api#file
-fn file_url(token: &str, file_path: &str) -> String {
-    let base = env::var("TELEGRAM_API_URL").ok().unwrap_or(TELEGRAM_API_URL.to_string());
+fn file_url(base: &str, token: &str, file_path: &str) -> String {
     format!(
         "{url}/file/bot{token}/{file}",
         url = base,
@@ -48,6 +47,7 @@ mod tests {
     #[test]
     fn method_url_test() {
         let url = method_url(
+            TELEGRAM_API_URL,
             "535362388:AAF7-g0gYncWnm5IyfZlpPRqRRv6kNAGlao",
             "methodName",
         );
@@ -61,6 +61,7 @@ mod tests {
     #[test]
     fn file_url_test() {
         let url = file_url(
+            TELEGRAM_API_URL,
             "535362388:AAF7-g0gYncWnm5IyfZlpPRqRRv6kNAGlao",
             "AgADAgADyqoxG2g8aEsu_KjjVsGF4-zetw8ABAEAAwIAA20AA_8QAwABFgQ",
         );
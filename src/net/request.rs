@@ -1,32 +1,38 @@
-use reqwest::{multipart::Form, Client, Response};
+use reqwest::header::HeaderMap;
 use serde::{de::DeserializeOwned, Serialize};
 
 use crate::{requests::ResponseResult, RequestError};
 
-use super::TelegramResponse;
+use super::{
+    FormPart, HttpBody, HttpRequest, HttpResponse, TelegramResponse, Transport,
+};
 
 pub async fn request_multipart<T>(
-    client: &Client,
+    transport: &dyn Transport,
     token: &str,
+    api_url: &str,
     method_name: &str,
-    params: Form,
+    params: Vec<FormPart>,
 ) -> ResponseResult<T>
     where
         T: DeserializeOwned,
 {
-    let response = client
-        .post(&super::method_url(token, method_name))
-        .multipart(params)
-        .send()
-        .await
-        .map_err(RequestError::NetworkError)?;
+    let response = transport
+        .send(HttpRequest {
+            method_name: method_name.to_owned(),
+            url: super::method_url(api_url, token, method_name),
+            headers: HeaderMap::new(),
+            body: HttpBody::Multipart(params),
+        })
+        .await?;
 
-    process_response(response).await
+    process_response(response, method_name)
 }
 
 pub async fn request_json<T, P>(
-    client: &Client,
+    transport: &dyn Transport,
     token: &str,
+    api_url: &str,
     method_name: &str,
     params: &P,
 ) -> ResponseResult<T>
@@ -34,16 +40,20 @@ pub async fn request_json<T, P>(
         T: DeserializeOwned + Serialize + std::fmt::Debug + Clone,
         P: Serialize,
 {
-    let response = client
-        .post(&super::method_url(token, method_name))
-        .json(params)
-        .send()
-        .await
-        .map_err(RequestError::NetworkError)?;
+    let params =
+        serde_json::to_string(params).map_err(RequestError::InvalidJson)?;
+    let response = transport
+        .send(HttpRequest {
+            method_name: method_name.to_owned(),
+            url: super::method_url(api_url, token, method_name),
+            headers: HeaderMap::new(),
+            body: HttpBody::Json(params),
+        })
+        .await?;
 
     log::debug!("recved response");
 
-    match process_response(response).await {
+    match process_response(response, method_name) {
         Ok(v) => {
             // let req = serde_json::to_string(&params).unwrap();
             // let res = serde_json::to_string(&v).unwrap();
@@ -58,19 +68,26 @@ pub async fn request_json<T, P>(
     }
 }
 
-async fn process_response<T>(response: Response) -> ResponseResult<T>
+fn process_response<T>(
+    response: HttpResponse,
+    method_name: &str,
+) -> ResponseResult<T>
     where
         T: DeserializeOwned,
 {
-    let url = response.url().to_string();
-    let s = &response.text().await.map_err(RequestError::NetworkError)?;
+    let s = &String::from_utf8_lossy(&response.body);
 
     match serde_json::from_str::<TelegramResponse<T>>(s) {
         Ok(v) => {
-            log::debug!("url={}, body={}", url, s);
+            log::debug!("method={}, body={}", method_name, s);
             v.into() }
         Err(err) => {
-            log::error!("url={}, body={}, err={}", url, s, err.to_string());
+            log::error!(
+                "method={}, body={}, err={}",
+                method_name,
+                s,
+                err.to_string()
+            );
             Err(RequestError::InvalidJson(err))
         }
     }
@@ -0,0 +1,281 @@
+use std::{
+    fmt::{self, Debug},
+    io,
+    pin::Pin,
+    sync::Arc,
+};
+
+use bytes::{Bytes, BytesMut};
+use futures::{Stream, TryStreamExt};
+use reqwest::{
+    header::HeaderMap,
+    multipart::{Form, Part},
+    Body, Client, StatusCode,
+};
+
+use crate::{requests::ResponseResult, RequestError};
+
+/// A way of sending requests to the Bot API.
+///
+/// All the [requests] of a [`Bot`] are sent by its transport, which is
+/// [`ReqwestTransport`] unless set by [`Bot::with_transport`]. A transport
+/// receives a request already addressed to the Bot API server and returns
+/// the raw response, which is then parsed by the request.
+///
+/// To add behaviour to the current transport, such as authorization headers
+/// or tracing, use [`TransportMiddleware`]. For tests, see
+/// [`InMemoryTransport`].
+///
+/// [requests]: crate::requests
+/// [`Bot`]: crate::Bot
+/// [`Bot::with_transport`]: crate::Bot::with_transport
+/// [`ReqwestTransport`]: crate::net::ReqwestTransport
+/// [`TransportMiddleware`]: crate::net::TransportMiddleware
+/// [`InMemoryTransport`]: crate::testing::InMemoryTransport
+#[async_trait::async_trait]
+pub trait Transport: Send + Sync {
+    /// Sends `request` and returns the response of the server.
+    ///
+    /// Only failures to get a response are errors: a response with an
+    /// unsuccessful status is returned as is.
+    async fn send(&self, request: HttpRequest) -> ResponseResult<HttpResponse>;
+}
+
+/// A step between a [`Bot`] and its [`Transport`], set by
+/// [`Bot::with_transport_middleware`].
+///
+/// A middleware can change a request before passing it to `next`, change the
+/// response, or respond by itself:
+///
+/// ```
+/// use reqwest::header::HeaderValue;
+/// use teloxide::{
+///     net::{HttpRequest, HttpResponse, Transport, TransportMiddleware},
+///     requests::ResponseResult,
+/// };
+///
+/// struct Signature(String);
+///
+/// #[async_trait::async_trait]
+/// impl TransportMiddleware for Signature {
+///     async fn handle(
+///         &self,
+///         mut request: HttpRequest,
+///         next: &dyn Transport,
+///     ) -> ResponseResult<HttpResponse> {
+///         request.headers.insert(
+///             "X-Signature",
+///             HeaderValue::from_str(&self.0).expect("Invalid signature"),
+///         );
+///         next.send(request).await
+///     }
+/// }
+/// ```
+///
+/// [`Bot`]: crate::Bot
+/// [`Transport`]: crate::net::Transport
+/// [`Bot::with_transport_middleware`]: crate::Bot::with_transport_middleware
+#[async_trait::async_trait]
+pub trait TransportMiddleware: Send + Sync {
+    /// Handles `request`, which would otherwise be sent by `next`.
+    async fn handle(
+        &self,
+        request: HttpRequest,
+        next: &dyn Transport,
+    ) -> ResponseResult<HttpResponse>;
+}
+
+/// A request to the Bot API.
+#[derive(Debug)]
+pub struct HttpRequest {
+    /// A name of the method, e.g. `sendMessage`.
+    pub method_name: String,
+
+    /// The URL of the method, which contains the bot's token.
+    pub url: String,
+
+    /// Headers sent in addition to `Content-Type`, which is determined by
+    /// the body.
+    pub headers: HeaderMap,
+
+    /// Parameters of the method.
+    pub body: HttpBody,
+}
+
+/// Parameters of a [`HttpRequest`].
+///
+/// [`HttpRequest`]: crate::net::HttpRequest
+#[derive(Debug)]
+pub enum HttpBody {
+    /// Parameters serialized to JSON, sent as `application/json`.
+    Json(String),
+
+    /// Parameters sent as `multipart/form-data`.
+    Multipart(Vec<FormPart>),
+}
+
+/// A single part of [`HttpBody::Multipart`].
+///
+/// [`HttpBody::Multipart`]: crate::net::HttpBody::Multipart
+#[derive(Debug)]
+pub struct FormPart {
+    /// A name of the field.
+    pub name: String,
+
+    /// A file name, if the part is a file.
+    pub file_name: Option<String>,
+
+    /// Contents of the part.
+    pub data: PartData,
+}
+
+/// Contents of a [`FormPart`].
+///
+/// [`FormPart`]: crate::net::FormPart
+pub enum PartData {
+    Text(String),
+    Bytes(Bytes),
+
+    /// Contents read while sending, such as a file on a drive.
+    Stream {
+        stream: PartStream,
+
+        /// The number of bytes in the stream, if known. It's sent as the
+        /// length of the part, so it must be exact.
+        length: Option<u64>,
+    },
+}
+
+/// A stream of [`PartData::Stream`].
+///
+/// [`PartData::Stream`]: crate::net::PartData::Stream
+pub type PartStream =
+    Pin<Box<dyn Stream<Item = io::Result<Bytes>> + Send + Sync>>;
+
+impl PartData {
+    /// Collects the contents, reading the stream, if any.
+    pub async fn into_bytes(self) -> io::Result<Bytes> {
+        match self {
+            PartData::Text(text) => Ok(Bytes::from(text)),
+            PartData::Bytes(bytes) => Ok(bytes),
+            PartData::Stream { stream, .. } => {
+                let data = stream
+                    .try_fold(BytesMut::new(), |mut data, chunk| async move {
+                        data.extend_from_slice(&chunk);
+                        Ok(data)
+                    })
+                    .await?;
+                Ok(data.freeze())
+            }
+        }
+    }
+}
+
+impl Debug for PartData {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PartData::Text(text) => f.debug_tuple("Text").field(text).finish(),
+            PartData::Bytes(bytes) => {
+                f.debug_tuple("Bytes").field(&bytes.len()).finish()
+            }
+            PartData::Stream { length, .. } => {
+                f.debug_struct("Stream").field("length", length).finish()
+            }
+        }
+    }
+}
+
+/// A response of the Bot API.
+#[derive(Clone, Debug)]
+pub struct HttpResponse {
+    pub status: StatusCode,
+    pub body: Bytes,
+}
+
+/// A transport which sends requests by [`reqwest::Client`].
+///
+/// [`reqwest::Client`]: https://docs.rs/reqwest/0.10.1/reqwest/struct.Client.html
+#[derive(Clone, Debug, Default)]
+pub struct ReqwestTransport {
+    client: Client,
+}
+
+impl ReqwestTransport {
+    #[must_use]
+    pub fn new(client: Client) -> Self {
+        Self { client }
+    }
+
+    pub fn client(&self) -> &Client {
+        &self.client
+    }
+}
+
+#[async_trait::async_trait]
+impl Transport for ReqwestTransport {
+    async fn send(&self, request: HttpRequest) -> ResponseResult<HttpResponse> {
+        let builder = self.client.post(&request.url).headers(request.headers);
+        let builder = match request.body {
+            HttpBody::Json(json) => builder
+                .header(reqwest::header::CONTENT_TYPE, "application/json")
+                .body(json),
+            HttpBody::Multipart(parts) => builder.multipart(
+                parts.into_iter().fold(Form::new(), |form, part| {
+                    let FormPart { name, file_name, data } = part;
+                    let part = match data {
+                        PartData::Text(text) => Part::text(text),
+                        PartData::Bytes(bytes) => Part::stream(bytes),
+                        PartData::Stream { stream, length: Some(length) } => {
+                            Part::stream_with_length(
+                                Body::wrap_stream(stream),
+                                length,
+                            )
+                        }
+                        PartData::Stream { stream, length: None } => {
+                            Part::stream(Body::wrap_stream(stream))
+                        }
+                    };
+                    match file_name {
+                        Some(file_name) => {
+                            form.part(name, part.file_name(file_name))
+                        }
+                        None => form.part(name, part),
+                    }
+                }),
+            ),
+        };
+
+        let response =
+            builder.send().await.map_err(RequestError::NetworkError)?;
+        let status = response.status();
+        let body =
+            response.bytes().await.map_err(RequestError::NetworkError)?;
+        Ok(HttpResponse { status, body })
+    }
+}
+
+/// The transport of a bot, which is `Debug`.
+#[derive(Clone)]
+pub(crate) struct SharedTransport(pub(crate) Arc<dyn Transport>);
+
+impl Debug for SharedTransport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("Transport")
+    }
+}
+
+/// A transport which passes requests through a middleware to `next`.
+pub(crate) struct Layered<M> {
+    pub(crate) middleware: M,
+    pub(crate) next: Arc<dyn Transport>,
+}
+
+#[async_trait::async_trait]
+impl<M> Transport for Layered<M>
+where
+    M: TransportMiddleware,
+{
+    async fn send(&self, request: HttpRequest) -> ResponseResult<HttpResponse> {
+        self.middleware.handle(request, &*self.next).await
+    }
+}
@@ -27,10 +27,12 @@ impl Request for AddStickerToSet {
 
     async fn send(&self) -> ResponseResult<True> {
         net::request_multipart(
-            self.bot.client(),
+            self.bot.transport(),
             self.bot.token(),
+            self.bot.api_url(),
             "addStickerToSet",
             FormBuilder::new()
+                .progress(self.bot.upload_progress())
                 .add("user_id", &self.user_id)
                 .await
                 .add("name", &self.name)
@@ -41,7 +43,7 @@ impl Request for AddStickerToSet {
                 .await
                 .add("mask_position", &self.mask_position)
                 .await
-                .build(),
+                .build()?,
         )
         .await
     }
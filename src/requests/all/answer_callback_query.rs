@@ -35,8 +35,9 @@ impl Request for AnswerCallbackQuery {
 
     async fn send(&self) -> ResponseResult<True> {
         net::request_json(
-            self.bot.client(),
+            self.bot.transport(),
             self.bot.token(),
+            self.bot.api_url(),
             "answerCallbackQuery",
             &self,
         )
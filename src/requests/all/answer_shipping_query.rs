@@ -33,8 +33,9 @@ impl Request for AnswerShippingQuery {
 
     async fn send(&self) -> ResponseResult<True> {
         net::request_json(
-            self.bot.client(),
+            self.bot.transport(),
             self.bot.token(),
+            self.bot.api_url(),
             "answerShippingQuery",
             &self,
         )
@@ -28,10 +28,12 @@ impl Request for CreateNewStickerSet {
 
     async fn send(&self) -> ResponseResult<True> {
         net::request_multipart(
-            self.bot.client(),
+            self.bot.transport(),
             self.bot.token(),
+            self.bot.api_url(),
             "createNewStickerSet",
             FormBuilder::new()
+                .progress(self.bot.upload_progress())
                 .add("user_id", &self.user_id)
                 .await
                 .add("name", &self.name)
@@ -46,7 +48,7 @@ impl Request for CreateNewStickerSet {
                 .await
                 .add("mask_position", &self.mask_position)
                 .await
-                .build(),
+                .build()?,
         )
         .await
     }
@@ -27,8 +27,9 @@ impl Request for DeleteChatPhoto {
 
     async fn send(&self) -> ResponseResult<True> {
         net::request_json(
-            self.bot.client(),
+            self.bot.transport(),
             self.bot.token(),
+            self.bot.api_url(),
             "deleteChatPhoto",
             &self,
         )
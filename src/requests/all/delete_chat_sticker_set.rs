@@ -32,8 +32,9 @@ impl Request for DeleteChatStickerSet {
 
     async fn send(&self) -> ResponseResult<True> {
         net::request_json(
-            self.bot.client(),
+            self.bot.transport(),
             self.bot.token(),
+            self.bot.api_url(),
             "deleteChatStickerSet",
             &self,
         )
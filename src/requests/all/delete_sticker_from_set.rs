@@ -25,8 +25,9 @@ impl Request for DeleteStickerFromSet {
 
     async fn send(&self) -> ResponseResult<True> {
         net::request_json(
-            self.bot.client(),
+            self.bot.transport(),
             self.bot.token(),
+            self.bot.api_url(),
             "deleteStickerFromSet",
             &self,
         )
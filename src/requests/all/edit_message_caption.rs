@@ -35,8 +35,9 @@ impl Request for EditMessageCaption {
 
     async fn send(&self) -> ResponseResult<Message> {
         net::request_json(
-            self.bot.client(),
+            self.bot.transport(),
             self.bot.token(),
+            self.bot.api_url(),
             "editMessageCaption",
             &self,
         )
@@ -37,8 +37,9 @@ impl Request for EditMessageLiveLocation {
 
     async fn send(&self) -> ResponseResult<Message> {
         net::request_json(
-            self.bot.client(),
+            self.bot.transport(),
             self.bot.token(),
+            self.bot.api_url(),
             "editMessageLiveLocation",
             &self,
         )
@@ -33,7 +33,8 @@ impl Request for EditMessageMedia {
     type Output = Message;
 
     async fn send(&self) -> ResponseResult<Message> {
-        let mut params = FormBuilder::new();
+        let mut params =
+            FormBuilder::new().progress(self.bot.upload_progress());
 
         match &self.chat_or_inline_message {
             ChatOrInlineMessage::Chat { chat_id, message_id } => {
@@ -50,15 +51,16 @@ impl Request for EditMessageMedia {
         }
 
         net::request_multipart(
-            self.bot.client(),
+            self.bot.transport(),
             self.bot.token(),
+            self.bot.api_url(),
             "editMessageMedia",
             params
-                .add("media", &self.media)
+                .add_media("media", &self.media)
                 .await
                 .add("reply_markup", &self.reply_markup)
                 .await
-                .build(),
+                .build()?,
         )
         .await
     }
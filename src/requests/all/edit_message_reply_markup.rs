@@ -33,8 +33,9 @@ impl Request for EditMessageReplyMarkup {
 
     async fn send(&self) -> ResponseResult<Message> {
         net::request_json(
-            self.bot.client(),
+            self.bot.transport(),
             self.bot.token(),
+            self.bot.api_url(),
             "editMessageReplyMarkup",
             &self,
         )
@@ -3,7 +3,11 @@ use serde::Serialize;
 use crate::{
     net,
     requests::{Request, ResponseResult},
-    types::{ChatOrInlineMessage, InlineKeyboardMarkup, Message, ParseMode},
+    types::{
+        ChatOrInlineMessage, InlineKeyboardMarkup, Message, MessageEntity,
+        ParseMode,
+    },
+    utils::message_text::MessageText,
     Bot,
 };
 use std::sync::Arc;
@@ -26,6 +30,7 @@ pub struct EditMessageText {
     chat_or_inline_message: ChatOrInlineMessage,
     text: String,
     parse_mode: Option<ParseMode>,
+    entities: Option<Vec<MessageEntity>>,
     disable_web_page_preview: Option<bool>,
     reply_markup: Option<InlineKeyboardMarkup>,
 }
@@ -36,8 +41,9 @@ impl Request for EditMessageText {
 
     async fn send(&self) -> ResponseResult<Message> {
         net::request_json(
-            self.bot.client(),
+            self.bot.transport(),
             self.bot.token(),
+            self.bot.api_url(),
             "editMessageText",
             &self,
         )
@@ -59,6 +65,7 @@ impl EditMessageText {
             chat_or_inline_message,
             text: text.into(),
             parse_mode: None,
+            entities: None,
             disable_web_page_preview: None,
             reply_markup: None,
         }
@@ -89,6 +96,32 @@ impl EditMessageText {
         self
     }
 
+    /// Special entities that appear in the message text, which can be
+    /// specified instead of [`parse_mode`].
+    ///
+    /// [`parse_mode`]: crate::requests::EditMessageText::parse_mode
+    pub fn entities<T>(mut self, val: T) -> Self
+    where
+        T: Into<Option<Vec<MessageEntity>>>,
+    {
+        self.entities = val.into();
+        self
+    }
+
+    /// Sets both the new text and its [`entities`], resetting [`parse_mode`],
+    /// since the text of [`MessageText`] is plain.
+    ///
+    /// [`entities`]: crate::requests::EditMessageText::entities
+    /// [`parse_mode`]: crate::requests::EditMessageText::parse_mode
+    /// [`MessageText`]: crate::utils::message_text::MessageText
+    pub fn message_text(mut self, val: MessageText) -> Self {
+        let (text, entities) = val.into_parts();
+        self.text = text;
+        self.entities = Some(entities);
+        self.parse_mode = None;
+        self
+    }
+
     /// Disables link previews for links in this message.
     pub fn disable_web_page_preview(mut self, val: bool) -> Self {
         self.disable_web_page_preview = Some(val);
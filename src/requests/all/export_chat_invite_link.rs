@@ -42,8 +42,9 @@ impl Request for ExportChatInviteLink {
     /// Returns the new invite link as `String` on success.
     async fn send(&self) -> ResponseResult<String> {
         net::request_json(
-            self.bot.client(),
+            self.bot.transport(),
             self.bot.token(),
+            self.bot.api_url(),
             "exportChatInviteLink",
             &self,
         )
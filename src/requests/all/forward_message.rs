@@ -28,8 +28,9 @@ impl Request for ForwardMessage {
 
     async fn send(&self) -> ResponseResult<Message> {
         net::request_json(
-            self.bot.client(),
+            self.bot.transport(),
             self.bot.token(),
+            self.bot.api_url(),
             "forwardMessage",
             &self,
         )
@@ -26,8 +26,14 @@ impl Request for GetChat {
     type Output = Chat;
 
     async fn send(&self) -> ResponseResult<Chat> {
-        net::request_json(self.bot.client(), self.bot.token(), "getChat", &self)
-            .await
+        net::request_json(
+            self.bot.transport(),
+            self.bot.token(),
+            self.bot.api_url(),
+            "getChat",
+            &self,
+        )
+        .await
     }
 }
 
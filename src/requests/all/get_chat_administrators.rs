@@ -30,8 +30,9 @@ impl Request for GetChatAdministrators {
     /// administrators except other bots.
     async fn send(&self) -> ResponseResult<Vec<ChatMember>> {
         net::request_json(
-            self.bot.client(),
+            self.bot.transport(),
             self.bot.token(),
+            self.bot.api_url(),
             "getChatAdministrators",
             &self,
         )
@@ -27,8 +27,9 @@ impl Request for GetChatMember {
 
     async fn send(&self) -> ResponseResult<ChatMember> {
         net::request_json(
-            self.bot.client(),
+            self.bot.transport(),
             self.bot.token(),
+            self.bot.api_url(),
             "getChatMember",
             &self,
         )
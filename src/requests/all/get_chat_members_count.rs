@@ -25,8 +25,9 @@ impl Request for GetChatMembersCount {
 
     async fn send(&self) -> ResponseResult<i32> {
         net::request_json(
-            self.bot.client(),
+            self.bot.transport(),
             self.bot.token(),
+            self.bot.api_url(),
             "getChatMembersCount",
             &self,
         )
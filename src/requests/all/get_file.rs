@@ -40,8 +40,14 @@ impl Request for GetFile {
     type Output = File;
 
     async fn send(&self) -> ResponseResult<File> {
-        net::request_json(self.bot.client(), self.bot.token(), "getFile", &self)
-            .await
+        net::request_json(
+            self.bot.transport(),
+            self.bot.token(),
+            self.bot.api_url(),
+            "getFile",
+            &self,
+        )
+        .await
     }
 }
 
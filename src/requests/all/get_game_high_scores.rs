@@ -36,8 +36,9 @@ impl Request for GetGameHighScores {
 
     async fn send(&self) -> ResponseResult<Vec<GameHighScore>> {
         net::request_json(
-            self.bot.client(),
+            self.bot.transport(),
             self.bot.token(),
+            self.bot.api_url(),
             "getGameHighScores",
             &self,
         )
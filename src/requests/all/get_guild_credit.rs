@@ -30,8 +30,9 @@ impl Request for GetGuildCredit {
 
     async fn send(&self) -> ResponseResult<Vec<GuildCredit>> {
         net::request_json(
-            self.bot.client(),
+            self.bot.transport(),
             self.bot.token(),
+            self.bot.api_url(),
             "getCredit",
             &self,
         ).await
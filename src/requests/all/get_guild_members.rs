@@ -28,7 +28,7 @@ impl Request for GetGuildMembers {
     type Output = Vec<ChatMember>;
 
     async fn send(&self) -> ResponseResult<Vec<ChatMember>> {
-        net::request_json(self.bot.client(), self.bot.token(), "getGuildMembers", &self)
+        net::request_json(self.bot.transport(), self.bot.token(), self.bot.api_url(), "getGuildMembers", &self)
             .await
     }
 }
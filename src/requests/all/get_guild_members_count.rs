@@ -26,7 +26,7 @@ impl Request for GetGuildMembersCount {
     type Output = i32;
 
     async fn send(&self) -> ResponseResult<i32> {
-        net::request_json(self.bot.client(), self.bot.token(), "getGuildMembersCount", &self)
+        net::request_json(self.bot.transport(), self.bot.token(), self.bot.api_url(), "getGuildMembersCount", &self)
             .await
     }
 }
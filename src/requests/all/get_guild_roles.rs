@@ -26,7 +26,7 @@ impl Request for GetGuildRoles {
     type Output = Vec<GuildRole>;
 
     async fn send(&self) -> ResponseResult<Vec<GuildRole>> {
-        net::request_json(self.bot.client(), self.bot.token(), "getGuildRoles", &self)
+        net::request_json(self.bot.transport(), self.bot.token(), self.bot.api_url(), "getGuildRoles", &self)
             .await
     }
 }
@@ -38,8 +38,9 @@ impl Request for GetMessage {
 
     async fn send(&self) -> ResponseResult<True> {
         net::request_json(
-            self.bot.client(),
+            self.bot.transport(),
             self.bot.token(),
+            self.bot.api_url(),
             "getMessage",
             &self,
         )
@@ -0,0 +1,54 @@
+use serde::Serialize;
+
+use crate::{
+    net,
+    requests::{Request, ResponseResult},
+    types::{BotCommand, BotCommandScope},
+    Bot,
+};
+use std::sync::Arc;
+
+/// Use this method to get the current list of the bot's commands.
+///
+/// [The official docs](https://core.telegram.org/bots/api#getmycommands).
+#[serde_with_macros::skip_serializing_none]
+#[derive(Debug, Clone, Serialize)]
+pub struct GetMyCommands {
+    #[serde(skip_serializing)]
+    bot: Arc<Bot>,
+    scope: Option<BotCommandScope>,
+}
+
+#[async_trait::async_trait]
+impl Request for GetMyCommands {
+    type Output = Vec<BotCommand>;
+
+    async fn send(&self) -> ResponseResult<Vec<BotCommand>> {
+        net::request_json(
+            self.bot.transport(),
+            self.bot.token(),
+            self.bot.api_url(),
+            "getMyCommands",
+            &self,
+        )
+        .await
+    }
+}
+
+impl GetMyCommands {
+    pub(crate) fn new(bot: Arc<Bot>) -> Self {
+        Self { bot, scope: None }
+    }
+
+    /// Users and chats to get the commands for. Defaults to
+    /// [`BotCommandScope::Default`].
+    ///
+    /// Only the commands set for exactly this scope are returned, i.e.
+    /// commands of a guild aren't returned for a channel of the guild.
+    ///
+    /// [`BotCommandScope::Default`]: crate::types::BotCommandScope::Default
+    pub fn scope(mut self, val: BotCommandScope) -> Self {
+        self.scope = Some(val);
+        self
+    }
+}
@@ -26,7 +26,7 @@ impl Request for GetPrivateChat {
     type Output = Chat;
 
     async fn send(&self) -> ResponseResult<Chat> {
-        net::request_json(self.bot.client(), self.bot.token(), "getPrivateChat", &self)
+        net::request_json(self.bot.transport(), self.bot.token(), self.bot.api_url(), "getPrivateChat", &self)
             .await
     }
 }
@@ -27,7 +27,7 @@ impl Request for GetRoleMembersCount {
     type Output = i32;
 
     async fn send(&self) -> ResponseResult<i32> {
-        net::request_json(self.bot.client(), self.bot.token(), "getRoleMembersCount", &self)
+        net::request_json(self.bot.transport(), self.bot.token(), self.bot.api_url(), "getRoleMembersCount", &self)
             .await
     }
 }
@@ -25,8 +25,9 @@ impl Request for GetStickerSet {
 
     async fn send(&self) -> ResponseResult<StickerSet> {
         net::request_json(
-            self.bot.client(),
+            self.bot.transport(),
             self.bot.token(),
+            self.bot.api_url(),
             "getStickerSet",
             &self,
         )
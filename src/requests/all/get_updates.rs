@@ -41,8 +41,9 @@ impl Request for GetUpdates {
         &self,
     ) -> ResponseResult<Vec<Result<Update, (Value, serde_json::Error)>>> {
         let value: Value = net::request_json(
-            self.bot.client(),
+            self.bot.transport(),
             self.bot.token(),
+            self.bot.api_url(),
             "getUpdates",
             &self,
         )
@@ -27,8 +27,9 @@ impl Request for GetUserProfilePhotos {
 
     async fn send(&self) -> ResponseResult<UserProfilePhotos> {
         net::request_json(
-            self.bot.client(),
+            self.bot.transport(),
             self.bot.token(),
+            self.bot.api_url(),
             "getUserProfilePhotos",
             &self,
         )
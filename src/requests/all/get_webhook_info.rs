@@ -29,8 +29,9 @@ impl Request for GetWebhookInfo {
     #[allow(clippy::trivially_copy_pass_by_ref)]
     async fn send(&self) -> ResponseResult<WebhookInfo> {
         net::request_json(
-            self.bot.client(),
+            self.bot.transport(),
             self.bot.token(),
+            self.bot.api_url(),
             "getWebhookInfo",
             &self,
         )
@@ -35,8 +35,9 @@ impl Request for KickChatMember {
 
     async fn send(&self) -> ResponseResult<True> {
         net::request_json(
-            self.bot.client(),
+            self.bot.transport(),
             self.bot.token(),
+            self.bot.api_url(),
             "kickChatMember",
             &self,
         )
@@ -23,6 +23,7 @@ mod get_chat_members_count;
 mod get_file;
 mod get_game_high_scores;
 mod get_me;
+mod get_my_commands;
 mod get_sticker_set;
 mod get_updates;
 mod get_user_profile_photos;
@@ -56,6 +57,7 @@ mod set_chat_photo;
 mod set_chat_sticker_set;
 mod set_chat_title;
 mod set_game_score;
+mod set_my_commands;
 mod set_sticker_position_in_set;
 mod set_webhook;
 mod stop_message_live_location;
@@ -104,6 +106,7 @@ pub use get_chat_members_count::*;
 pub use get_file::*;
 pub use get_game_high_scores::*;
 pub use get_me::*;
+pub use get_my_commands::*;
 pub use get_sticker_set::*;
 pub use get_updates::*;
 pub use get_user_profile_photos::*;
@@ -137,6 +140,7 @@ pub use set_chat_photo::*;
 pub use set_chat_sticker_set::*;
 pub use set_chat_title::*;
 pub use set_game_score::*;
+pub use set_my_commands::*;
 pub use set_sticker_position_in_set::*;
 pub use set_webhook::*;
 pub use std::pin::Pin;
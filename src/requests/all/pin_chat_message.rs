@@ -31,8 +31,9 @@ impl Request for PinChatMessage {
 
     async fn send(&self) -> ResponseResult<True> {
         net::request_json(
-            self.bot.client(),
+            self.bot.transport(),
             self.bot.token(),
+            self.bot.api_url(),
             "pinChatMessage",
             &self,
         )
@@ -29,7 +29,7 @@ impl Request for SearchGuildMember {
     type Output = Vec<ChatMember>;
 
     async fn send(&self) -> ResponseResult<Vec<ChatMember>> {
-        net::request_json(self.bot.client(), self.bot.token(), "searchGuildMember", &self)
+        net::request_json(self.bot.transport(), self.bot.token(), self.bot.api_url(), "searchGuildMember", &self)
             .await
     }
 }
@@ -27,7 +27,7 @@ impl Request for SearchGuildMemberByUsername {
     type Output = Vec<ChatMember>;
 
     async fn send(&self) -> ResponseResult<Vec<ChatMember>> {
-        net::request_json(self.bot.client(), self.bot.token(), "searchGuildUsers", &self)
+        net::request_json(self.bot.transport(), self.bot.token(), self.bot.api_url(), "searchGuildUsers", &self)
             .await
     }
 }
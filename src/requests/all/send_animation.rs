@@ -1,6 +1,9 @@
 use crate::{
     net,
-    requests::{form_builder::FormBuilder, Request, ResponseResult},
+    requests::{
+        form_builder::FormBuilder, send_cached, Request, ResponseResult,
+        UploadKind,
+    },
     types::{ChatId, InputFile, Message, ParseMode, ReplyMarkup},
     Bot,
 };
@@ -34,35 +37,42 @@ impl Request for SendAnimation {
     type Output = Message;
 
     async fn send(&self) -> ResponseResult<Message> {
-        net::request_multipart(
-            self.bot.client(),
-            self.bot.token(),
-            "sendAnimation",
-            FormBuilder::new()
-                .add("chat_id", &self.chat_id)
-                .await
-                .add("animation", &self.animation)
-                .await
-                .add("duration", &self.duration)
-                .await
-                .add("width", &self.width)
-                .await
-                .add("height", &self.height)
-                .await
-                .add("thumb", &self.thumb)
-                .await
-                .add("caption", &self.caption)
-                .await
-                .add("parse_mode", &self.parse_mode)
-                .await
-                .add("disable_notification", &self.disable_notification)
-                .await
-                .add("reply_to_message_id", &self.reply_to_message_id)
-                .await
-                .add("reply_markup", &self.reply_markup)
-                .await
-                .build(),
-        )
+        let kind = UploadKind::Animation;
+        send_cached(&self.bot, kind, &self.animation, |cached| async move {
+            let file = cached.as_ref().unwrap_or(&self.animation);
+            net::request_multipart(
+                self.bot.transport(),
+                self.bot.token(),
+                self.bot.api_url(),
+                "sendAnimation",
+                FormBuilder::new()
+                    .progress(self.bot.upload_progress())
+                    .add("chat_id", &self.chat_id)
+                    .await
+                    .add("animation", file)
+                    .await
+                    .add("duration", &self.duration)
+                    .await
+                    .add("width", &self.width)
+                    .await
+                    .add("height", &self.height)
+                    .await
+                    .add("thumb", &self.thumb)
+                    .await
+                    .add("caption", &self.caption)
+                    .await
+                    .add("parse_mode", &self.parse_mode)
+                    .await
+                    .add("disable_notification", &self.disable_notification)
+                    .await
+                    .add("reply_to_message_id", &self.reply_to_message_id)
+                    .await
+                    .add("reply_markup", &self.reply_markup)
+                    .await
+                    .build()?,
+            )
+            .await
+        })
         .await
     }
 }
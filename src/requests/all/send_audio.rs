@@ -1,6 +1,9 @@
 use crate::{
     net,
-    requests::{form_builder::FormBuilder, Request, ResponseResult},
+    requests::{
+        form_builder::FormBuilder, send_cached, Request, ResponseResult,
+        UploadKind,
+    },
     types::{ChatId, InputFile, Message, ParseMode, ReplyMarkup},
     Bot,
 };
@@ -38,35 +41,42 @@ impl Request for SendAudio {
     type Output = Message;
 
     async fn send(&self) -> ResponseResult<Message> {
-        net::request_multipart(
-            self.bot.client(),
-            self.bot.token(),
-            "sendAudio",
-            FormBuilder::new()
-                .add("chat_id", &self.chat_id)
-                .await
-                .add("audio", &self.audio)
-                .await
-                .add("caption", &self.caption)
-                .await
-                .add("parse_mode", &self.parse_mode)
-                .await
-                .add("duration", &self.duration)
-                .await
-                .add("performer", &self.performer)
-                .await
-                .add("title", &self.title)
-                .await
-                .add("thumb", &self.thumb)
-                .await
-                .add("disable_notification", &self.disable_notification)
-                .await
-                .add("reply_to_message_id", &self.reply_to_message_id)
-                .await
-                .add("reply_markup", &self.reply_markup)
-                .await
-                .build(),
-        )
+        let kind = UploadKind::Audio;
+        send_cached(&self.bot, kind, &self.audio, |cached| async move {
+            let file = cached.as_ref().unwrap_or(&self.audio);
+            net::request_multipart(
+                self.bot.transport(),
+                self.bot.token(),
+                self.bot.api_url(),
+                "sendAudio",
+                FormBuilder::new()
+                    .progress(self.bot.upload_progress())
+                    .add("chat_id", &self.chat_id)
+                    .await
+                    .add("audio", file)
+                    .await
+                    .add("caption", &self.caption)
+                    .await
+                    .add("parse_mode", &self.parse_mode)
+                    .await
+                    .add("duration", &self.duration)
+                    .await
+                    .add("performer", &self.performer)
+                    .await
+                    .add("title", &self.title)
+                    .await
+                    .add("thumb", &self.thumb)
+                    .await
+                    .add("disable_notification", &self.disable_notification)
+                    .await
+                    .add("reply_to_message_id", &self.reply_to_message_id)
+                    .await
+                    .add("reply_markup", &self.reply_markup)
+                    .await
+                    .build()?,
+            )
+            .await
+        })
         .await
     }
 }
@@ -77,8 +77,9 @@ impl Request for SendChatAction {
 
     async fn send(&self) -> ResponseResult<True> {
         net::request_json(
-            self.bot.client(),
+            self.bot.transport(),
             self.bot.token(),
+            self.bot.api_url(),
             "sendChatAction",
             &self,
         )
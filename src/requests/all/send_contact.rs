@@ -32,8 +32,9 @@ impl Request for SendContact {
 
     async fn send(&self) -> ResponseResult<Message> {
         net::request_json(
-            self.bot.client(),
+            self.bot.transport(),
             self.bot.token(),
+            self.bot.api_url(),
             "sendContact",
             &self,
         )
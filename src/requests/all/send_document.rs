@@ -1,6 +1,9 @@
 use crate::{
     net,
-    requests::{form_builder::FormBuilder, Request, ResponseResult},
+    requests::{
+        form_builder::FormBuilder, send_cached, Request, ResponseResult,
+        UploadKind,
+    },
     types::{ChatId, InputFile, Message, ParseMode, ReplyMarkup},
     Bot,
 };
@@ -30,29 +33,36 @@ impl Request for SendDocument {
     type Output = Message;
 
     async fn send(&self) -> ResponseResult<Message> {
-        net::request_multipart(
-            self.bot.client(),
-            self.bot.token(),
-            "sendDocument",
-            FormBuilder::new()
-                .add("chat_id", &self.chat_id)
-                .await
-                .add("document", &self.document)
-                .await
-                .add("thumb", &self.thumb)
-                .await
-                .add("caption", &self.caption)
-                .await
-                .add("parse_mode", &self.parse_mode)
-                .await
-                .add("disable_notification", &self.disable_notification)
-                .await
-                .add("reply_to_message_id", &self.reply_to_message_id)
-                .await
-                .add("reply_markup", &self.reply_markup)
-                .await
-                .build(),
-        )
+        let kind = UploadKind::Document;
+        send_cached(&self.bot, kind, &self.document, |cached| async move {
+            let file = cached.as_ref().unwrap_or(&self.document);
+            net::request_multipart(
+                self.bot.transport(),
+                self.bot.token(),
+                self.bot.api_url(),
+                "sendDocument",
+                FormBuilder::new()
+                    .progress(self.bot.upload_progress())
+                    .add("chat_id", &self.chat_id)
+                    .await
+                    .add("document", file)
+                    .await
+                    .add("thumb", &self.thumb)
+                    .await
+                    .add("caption", &self.caption)
+                    .await
+                    .add("parse_mode", &self.parse_mode)
+                    .await
+                    .add("disable_notification", &self.disable_notification)
+                    .await
+                    .add("reply_to_message_id", &self.reply_to_message_id)
+                    .await
+                    .add("reply_markup", &self.reply_markup)
+                    .await
+                    .build()?,
+            )
+            .await
+        })
         .await
     }
 }
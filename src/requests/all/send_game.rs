@@ -29,8 +29,9 @@ impl Request for SendGame {
 
     async fn send(&self) -> ResponseResult<Message> {
         net::request_json(
-            self.bot.client(),
+            self.bot.transport(),
             self.bot.token(),
+            self.bot.api_url(),
             "sendGame",
             &self,
         )
@@ -47,8 +47,9 @@ impl Request for SendInvoice {
 
     async fn send(&self) -> ResponseResult<Message> {
         net::request_json(
-            self.bot.client(),
+            self.bot.transport(),
             self.bot.token(),
+            self.bot.api_url(),
             "sendInvoice",
             &self,
         )
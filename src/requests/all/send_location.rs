@@ -31,8 +31,9 @@ impl Request for SendLocation {
 
     async fn send(&self) -> ResponseResult<Message> {
         net::request_json(
-            self.bot.client(),
+            self.bot.transport(),
             self.bot.token(),
+            self.bot.api_url(),
             "sendLocation",
             &self,
         )
@@ -24,19 +24,21 @@ impl Request for SendMediaGroup {
 
     async fn send(&self) -> ResponseResult<Vec<Message>> {
         net::request_multipart(
-            self.bot.client(),
+            self.bot.transport(),
             self.bot.token(),
+            self.bot.api_url(),
             "sendMediaGroup",
             FormBuilder::new()
+                .progress(self.bot.upload_progress())
                 .add("chat_id", &self.chat_id)
                 .await
-                .add("media", &self.media)
+                .add_media("media", &self.media)
                 .await
                 .add("disable_notification", &self.disable_notification)
                 .await
                 .add("reply_to_message_id", &self.reply_to_message_id)
                 .await
-                .build(),
+                .build()?,
         )
         .await
     }
@@ -3,7 +3,8 @@ use serde::{Serialize, Deserialize};
 use crate::{
     net,
     requests::{Request, ResponseResult},
-    types::{ChatId, Message, ParseMode, ReplyMarkup},
+    types::{ChatId, Message, MessageEntity, ParseMode, ReplyMarkup},
+    utils::message_text::MessageText,
     Bot,
 };
 use std::sync::Arc;
@@ -20,6 +21,7 @@ pub struct SendMessage {
     pub text: String,
     pub desc: Option<String>,
     pub parse_mode: Option<ParseMode>,
+    pub entities: Option<Vec<MessageEntity>>,
     pub selective: Option<bool>,
     pub disable_web_page_preview: Option<bool>,
     pub disable_notification: Option<bool>,
@@ -41,8 +43,9 @@ impl Request for SendMessage {
 
     async fn send(&self) -> ResponseResult<Message> {
         net::request_json(
-            self.bot.client(),
+            self.bot.transport(),
             self.bot.token(),
+            self.bot.api_url(),
             "sendMessage",
             &self,
         )
@@ -62,6 +65,7 @@ impl SendMessage {
             text: text.into(),
             desc: None,
             parse_mode: None,
+            entities: None,
             selective: None,
             disable_web_page_preview: None,
             disable_notification: None,
@@ -115,6 +119,32 @@ impl SendMessage {
         self
     }
 
+    /// Special entities that appear in the message text, which can be
+    /// specified instead of [`parse_mode`].
+    ///
+    /// [`parse_mode`]: crate::requests::SendMessage::parse_mode
+    pub fn entities<T>(mut self, value: T) -> Self
+        where
+            T: Into<Option<Vec<MessageEntity>>>,
+    {
+        self.entities = value.into();
+        self
+    }
+
+    /// Sets both the text and its [`entities`], resetting [`parse_mode`],
+    /// since the text of [`MessageText`] is plain.
+    ///
+    /// [`entities`]: crate::requests::SendMessage::entities
+    /// [`parse_mode`]: crate::requests::SendMessage::parse_mode
+    /// [`MessageText`]: crate::utils::message_text::MessageText
+    pub fn message_text(mut self, value: MessageText) -> Self {
+        let (text, entities) = value.into_parts();
+        self.text = text;
+        self.entities = Some(entities);
+        self.parse_mode = None;
+        self
+    }
+
     /// Disables link previews for links in this message.
     pub fn disable_web_page_preview(mut self, value: bool) -> Self {
         self.disable_web_page_preview = Some(value);
@@ -1,6 +1,9 @@
 use crate::{
     net,
-    requests::{form_builder::FormBuilder, Request, ResponseResult},
+    requests::{
+        form_builder::FormBuilder, send_cached, Request, ResponseResult,
+        UploadKind,
+    },
     types::{ChatId, InputFile, Message, ParseMode, ReplyMarkup},
     Bot,
 };
@@ -28,30 +31,37 @@ impl Request for SendPhoto {
     type Output = Message;
 
     async fn send(&self) -> ResponseResult<Message> {
-        net::request_multipart(
-            self.bot.client(),
-            self.bot.token(),
-            "sendPhoto",
-            FormBuilder::new()
-                .add("chat_id", &self.chat_id)
-                .await
-                .add("photo", &self.photo)
-                .await
-                .add("caption", &self.caption)
-                .await
-                .add("parse_mode", &self.parse_mode)
-                .await
-                .add("disable_notification", &self.disable_notification)
-                .await
-                .add("reply_to_message_id", &self.reply_to_message_id)
-                .await
-                .add("reply_markup", &self.reply_markup)
-                .await
-                .add("mentions", &self.mentions)
-                .await
-                .build(),
-        )
+        let kind = UploadKind::Photo;
+        send_cached(&self.bot, kind, &self.photo, |cached| async move {
+            let file = cached.as_ref().unwrap_or(&self.photo);
+            net::request_multipart(
+                self.bot.transport(),
+                self.bot.token(),
+                self.bot.api_url(),
+                "sendPhoto",
+                FormBuilder::new()
+                    .progress(self.bot.upload_progress())
+                    .add("chat_id", &self.chat_id)
+                    .await
+                    .add("photo", file)
+                    .await
+                    .add("caption", &self.caption)
+                    .await
+                    .add("parse_mode", &self.parse_mode)
+                    .await
+                    .add("disable_notification", &self.disable_notification)
+                    .await
+                    .add("reply_to_message_id", &self.reply_to_message_id)
+                    .await
+                    .add("reply_markup", &self.reply_markup)
+                    .await
+                    .add("mentions", &self.mentions)
+                    .await
+                    .build()?,
+            )
             .await
+        })
+        .await
     }
 }
 
@@ -1,6 +1,9 @@
 use crate::{
     net,
-    requests::{form_builder::FormBuilder, Request, ResponseResult},
+    requests::{
+        form_builder::FormBuilder, send_cached, Request, ResponseResult,
+        UploadKind,
+    },
     types::{ChatId, InputFile, Message, ReplyMarkup},
     Bot,
 };
@@ -26,23 +29,30 @@ impl Request for SendSticker {
     type Output = Message;
 
     async fn send(&self) -> ResponseResult<Message> {
-        net::request_multipart(
-            self.bot.client(),
-            self.bot.token(),
-            "sendSticker",
-            FormBuilder::new()
-                .add("chat_id", &self.chat_id)
-                .await
-                .add("sticker", &self.sticker)
-                .await
-                .add("disable_notification", &self.disable_notification)
-                .await
-                .add("reply_to_message_id", &self.reply_to_message_id)
-                .await
-                .add("reply_markup", &self.reply_markup)
-                .await
-                .build(),
-        )
+        let kind = UploadKind::Sticker;
+        send_cached(&self.bot, kind, &self.sticker, |cached| async move {
+            let file = cached.as_ref().unwrap_or(&self.sticker);
+            net::request_multipart(
+                self.bot.transport(),
+                self.bot.token(),
+                self.bot.api_url(),
+                "sendSticker",
+                FormBuilder::new()
+                    .progress(self.bot.upload_progress())
+                    .add("chat_id", &self.chat_id)
+                    .await
+                    .add("sticker", file)
+                    .await
+                    .add("disable_notification", &self.disable_notification)
+                    .await
+                    .add("reply_to_message_id", &self.reply_to_message_id)
+                    .await
+                    .add("reply_markup", &self.reply_markup)
+                    .await
+                    .build()?,
+            )
+            .await
+        })
         .await
     }
 }
@@ -1,6 +1,9 @@
 use crate::{
     net,
-    requests::{form_builder::FormBuilder, Request, ResponseResult},
+    requests::{
+        form_builder::FormBuilder, send_cached, Request, ResponseResult,
+        UploadKind,
+    },
     types::{ChatId, InputFile, Message, ReplyMarkup},
     Bot,
 };
@@ -30,29 +33,36 @@ impl Request for SendVideoNote {
     type Output = Message;
 
     async fn send(&self) -> ResponseResult<Message> {
-        net::request_multipart(
-            self.bot.client(),
-            self.bot.token(),
-            "sendVideoNote",
-            FormBuilder::new()
-                .add("chat_id", &self.chat_id)
-                .await
-                .add("video_note", &self.video_note)
-                .await
-                .add("duration", &self.duration)
-                .await
-                .add("length", &self.length)
-                .await
-                .add("thumb", &self.thumb)
-                .await
-                .add("disable_notification", &self.disable_notification)
-                .await
-                .add("reply_to_message_id", &self.reply_to_message_id)
-                .await
-                .add("reply_markup", &self.reply_markup)
-                .await
-                .build(),
-        )
+        let kind = UploadKind::VideoNote;
+        send_cached(&self.bot, kind, &self.video_note, |cached| async move {
+            let file = cached.as_ref().unwrap_or(&self.video_note);
+            net::request_multipart(
+                self.bot.transport(),
+                self.bot.token(),
+                self.bot.api_url(),
+                "sendVideoNote",
+                FormBuilder::new()
+                    .progress(self.bot.upload_progress())
+                    .add("chat_id", &self.chat_id)
+                    .await
+                    .add("video_note", file)
+                    .await
+                    .add("duration", &self.duration)
+                    .await
+                    .add("length", &self.length)
+                    .await
+                    .add("thumb", &self.thumb)
+                    .await
+                    .add("disable_notification", &self.disable_notification)
+                    .await
+                    .add("reply_to_message_id", &self.reply_to_message_id)
+                    .await
+                    .add("reply_markup", &self.reply_markup)
+                    .await
+                    .build()?,
+            )
+            .await
+        })
         .await
     }
 }
@@ -28,8 +28,9 @@ impl Request for SetChatAdministratorCustomTitle {
 
     async fn send(&self) -> ResponseResult<True> {
         net::request_json(
-            self.bot.client(),
+            self.bot.transport(),
             self.bot.token(),
+            self.bot.api_url(),
             "setChatAdministratorCustomTitle",
             &self,
         )
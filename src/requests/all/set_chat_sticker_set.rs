@@ -30,8 +30,9 @@ impl Request for SetChatStickerSet {
 
     async fn send(&self) -> ResponseResult<True> {
         net::request_json(
-            self.bot.client(),
+            self.bot.transport(),
             self.bot.token(),
+            self.bot.api_url(),
             "setChatStickerSet",
             &self,
         )
@@ -29,8 +29,9 @@ impl Request for SetChatTitle {
 
     async fn send(&self) -> ResponseResult<True> {
         net::request_json(
-            self.bot.client(),
+            self.bot.transport(),
             self.bot.token(),
+            self.bot.api_url(),
             "setChatTitle",
             &self,
         )
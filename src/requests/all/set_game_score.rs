@@ -38,8 +38,9 @@ impl Request for SetGameScore {
 
     async fn send(&self) -> ResponseResult<Message> {
         net::request_json(
-            self.bot.client(),
+            self.bot.transport(),
             self.bot.token(),
+            self.bot.api_url(),
             "setGameScore",
             &self,
         )
@@ -28,7 +28,7 @@ impl Request for SetMemberRoles {
     type Output = True;
 
     async fn send(&self) -> ResponseResult<True> {
-        net::request_json(self.bot.client(), self.bot.token(), "setMemberRoles", &self)
+        net::request_json(self.bot.transport(), self.bot.token(), self.bot.api_url(), "setMemberRoles", &self)
             .await
     }
 }
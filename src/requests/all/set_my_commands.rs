@@ -0,0 +1,66 @@
+use serde::Serialize;
+
+use crate::{
+    net,
+    requests::{Request, ResponseResult},
+    types::{BotCommand, BotCommandScope, True},
+    Bot,
+};
+use std::sync::Arc;
+
+/// Use this method to change the list of the bot's commands, shown in the
+/// command menu of clients.
+///
+/// [The official docs](https://core.telegram.org/bots/api#setmycommands).
+#[serde_with_macros::skip_serializing_none]
+#[derive(Debug, Clone, Serialize)]
+pub struct SetMyCommands {
+    #[serde(skip_serializing)]
+    bot: Arc<Bot>,
+    commands: Vec<BotCommand>,
+    scope: Option<BotCommandScope>,
+}
+
+#[async_trait::async_trait]
+impl Request for SetMyCommands {
+    type Output = True;
+
+    async fn send(&self) -> ResponseResult<True> {
+        net::request_json(
+            self.bot.transport(),
+            self.bot.token(),
+            self.bot.api_url(),
+            "setMyCommands",
+            &self,
+        )
+        .await
+    }
+}
+
+impl SetMyCommands {
+    pub(crate) fn new<C>(bot: Arc<Bot>, commands: C) -> Self
+    where
+        C: Into<Vec<BotCommand>>,
+    {
+        Self { bot, commands: commands.into(), scope: None }
+    }
+
+    /// A list of bot commands to be set, at most 100 commands can be
+    /// specified.
+    pub fn commands<C>(mut self, val: C) -> Self
+    where
+        C: Into<Vec<BotCommand>>,
+    {
+        self.commands = val.into();
+        self
+    }
+
+    /// Users and chats for which the commands are relevant. Defaults to
+    /// [`BotCommandScope::Default`].
+    ///
+    /// [`BotCommandScope::Default`]: crate::types::BotCommandScope::Default
+    pub fn scope(mut self, val: BotCommandScope) -> Self {
+        self.scope = Some(val);
+        self
+    }
+}
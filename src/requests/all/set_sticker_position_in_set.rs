@@ -27,8 +27,9 @@ impl Request for SetStickerPositionInSet {
 
     async fn send(&self) -> ResponseResult<True> {
         net::request_json(
-            self.bot.client(),
+            self.bot.transport(),
             self.bot.token(),
+            self.bot.api_url(),
             "setStickerPositionInSet",
             &self,
         )
@@ -41,8 +41,9 @@ impl Request for SetWebhook {
 
     async fn send(&self) -> ResponseResult<True> {
         net::request_json(
-            self.bot.client(),
+            self.bot.transport(),
             self.bot.token(),
+            self.bot.api_url(),
             "setWebhook",
             &self,
         )
@@ -34,8 +34,9 @@ impl Request for StopMessageLiveLocation {
 
     async fn send(&self) -> ResponseResult<Message> {
         net::request_json(
-            self.bot.client(),
+            self.bot.transport(),
             self.bot.token(),
+            self.bot.api_url(),
             "stopMessageLiveLocation",
             &self,
         )
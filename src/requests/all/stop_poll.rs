@@ -30,8 +30,9 @@ impl Request for StopPoll {
     /// [`Poll`]: crate::types::Poll
     async fn send(&self) -> ResponseResult<Poll> {
         net::request_json(
-            self.bot.client(),
+            self.bot.transport(),
             self.bot.token(),
+            self.bot.api_url(),
             "stopPoll",
             &self,
         )
@@ -30,8 +30,9 @@ impl Request for UploadStickerFile {
 
     async fn send(&self) -> ResponseResult<File> {
         net::request_json(
-            self.bot.client(),
+            self.bot.transport(),
             self.bot.token(),
+            self.bot.api_url(),
             "uploadStickerFile",
             &self,
         )
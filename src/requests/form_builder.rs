@@ -1,24 +1,37 @@
-use std::{borrow::Cow, path::PathBuf};
-
-use reqwest::multipart::Form;
+use std::{borrow::Cow, io, path::PathBuf};
 
 use crate::{
-    requests::utils::{file_from_memory_to_part, file_to_part},
+    net::{FormPart, PartData},
+    requests::utils::{
+        file_from_memory_to_part, file_to_part, reader_to_part,
+        ProgressCallback,
+    },
     types::{
-        ChatId, InlineKeyboardMarkup, InputFile, InputMedia, MaskPosition,
-        ParseMode, ReplyMarkup,
+        ChatId, FileReader, InlineKeyboardMarkup, InputFile, InputMedia,
+        MaskPosition, ParseMode, ReplyMarkup,
     },
+    RequestError,
 };
 
-/// This is a convenient struct that builds `reqwest::multipart::Form`
-/// from scratch.
+/// This is a convenient struct that builds parts of a `multipart/form-data`
+/// request from scratch.
+///
+/// If a file can't be prepared for uploading, the rest of the fields are
+/// skipped and [`FormBuilder::build`] returns the error.
 pub(crate) struct FormBuilder {
-    form: Form,
+    parts: Vec<FormPart>,
+    progress: Option<ProgressCallback>,
+    error: Option<io::Error>,
 }
 
 impl FormBuilder {
     pub(crate) fn new() -> Self {
-        Self { form: Form::new() }
+        Self { parts: Vec::new(), progress: None, error: None }
+    }
+
+    /// Reports the progress of uploading files to `progress`, if any.
+    pub(crate) fn progress(self, progress: Option<ProgressCallback>) -> Self {
+        Self { progress, ..self }
     }
 
     /// Add the supplied key-value pair to this `FormBuilder`.
@@ -27,30 +40,72 @@ impl FormBuilder {
         N: Into<Cow<'a, str>>,
         T: IntoFormValue,
     {
+        if self.error.is_some() {
+            return self;
+        }
+
         let name = name.into().into_owned();
         match value.into_form_value() {
-            Some(FormValue::Str(string)) => {
-                Self { form: self.form.text(name, string) }
-            }
+            Some(FormValue::Str(string)) => self.add_part(Ok(FormPart {
+                name,
+                file_name: None,
+                data: PartData::Text(string),
+            })),
             Some(FormValue::File(path)) => self.add_file(name, path).await,
             Some(FormValue::Memory { file_name, data }) => {
                 self.add_file_from_memory(name, file_name, data)
             }
+            Some(FormValue::Reader { file_name, reader }) => {
+                let part = reader_to_part(
+                    name,
+                    &reader,
+                    file_name,
+                    self.progress.clone(),
+                );
+                self.add_part(part)
+            }
             None => self,
         }
     }
 
-    // used in SendMediaGroup
+    /// Adds `media` as `name`, uploading its files and thumbnails as separate
+    /// parts, to which `media` refers by `attach://<part name>`.
+    pub async fn add_media<'a, N, M>(mut self, name: N, media: &M) -> Self
+    where
+        N: Into<Cow<'a, str>>,
+        M: MediaFiles + IntoFormValue + Clone,
+    {
+        let mut media = media.clone();
+        for (i, file) in media.files_mut().into_iter().enumerate() {
+            match file {
+                InputFile::File(_)
+                | InputFile::Memory { .. }
+                | InputFile::Reader { .. } => {
+                    let part_name = format!("file{}", i);
+                    self = self.add(part_name.as_str(), &*file).await;
+                    *file = InputFile::Url(format!("attach://{}", part_name));
+                }
+                InputFile::Url(_) | InputFile::FileId(_) => {}
+            }
+        }
+        self.add(name, &media).await
+    }
+
     pub async fn add_file<'a, N>(self, name: N, path_to_file: PathBuf) -> Self
     where
         N: Into<Cow<'a, str>>,
     {
-        Self {
-            form: self.form.part(
-                name.into().into_owned(),
-                file_to_part(path_to_file).await,
-            ),
+        if self.error.is_some() {
+            return self;
         }
+
+        let part = file_to_part(
+            name.into().into_owned(),
+            path_to_file,
+            self.progress.clone(),
+        )
+        .await;
+        self.add_part(part)
     }
 
     fn add_file_from_memory<'a, N>(
@@ -62,22 +117,39 @@ impl FormBuilder {
     where
         N: Into<Cow<'a, str>>,
     {
-        Self {
-            form: self.form.part(
-                name.into().into_owned(),
-                file_from_memory_to_part(data, file_name),
-            ),
+        let part = file_from_memory_to_part(
+            name.into().into_owned(),
+            data,
+            file_name,
+            self.progress.clone(),
+        );
+        self.add_part(Ok(part))
+    }
+
+    fn add_part(mut self, part: io::Result<FormPart>) -> Self {
+        match part {
+            Ok(part) => {
+                self.parts.push(part);
+                self
+            }
+            Err(error) => Self { error: Some(error), ..self },
         }
     }
 
-    pub fn build(self) -> Form {
-        self.form
+    /// Returns the parts or [`RequestError::Io`] if a file couldn't be
+    /// prepared for uploading.
+    pub fn build(self) -> Result<Vec<FormPart>, RequestError> {
+        match self.error {
+            Some(error) => Err(RequestError::Io(error)),
+            None => Ok(self.parts),
+        }
     }
 }
 
 pub(crate) enum FormValue {
     File(PathBuf),
     Memory { file_name: String, data: Cow<'static, [u8]> },
+    Reader { file_name: String, reader: FileReader },
     Str(String),
 }
 
@@ -118,8 +190,24 @@ where
     }
 }
 
-// TODO: fix InputMedia implementation of IntoFormValue (for now it doesn't
-// encode files :|)
+/// Media which can contain files to upload.
+pub(crate) trait MediaFiles {
+    fn files_mut(&mut self) -> Vec<&mut InputFile>;
+}
+
+impl MediaFiles for InputMedia {
+    fn files_mut(&mut self) -> Vec<&mut InputFile> {
+        InputMedia::files_mut(self)
+    }
+}
+
+impl MediaFiles for Vec<InputMedia> {
+    fn files_mut(&mut self) -> Vec<&mut InputFile> {
+        self.iter_mut().flat_map(InputMedia::files_mut).collect()
+    }
+}
+
+// Files of media should be attached by FormBuilder::add_media.
 impl IntoFormValue for Vec<InputMedia> {
     fn into_form_value(&self) -> Option<FormValue> {
         let json =
@@ -187,8 +275,215 @@ impl IntoFormValue for InputFile {
                 file_name: file_name.clone(),
                 data: data.clone(),
             }),
+            InputFile::Reader { file_name, reader } => {
+                Some(FormValue::Reader {
+                    file_name: file_name.clone(),
+                    reader: reader.clone(),
+                })
+            }
             InputFile::Url(url) => Some(FormValue::Str(url.clone())),
             InputFile::FileId(file_id) => Some(FormValue::Str(file_id.clone())),
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+
+    use serde_json::json;
+
+    use super::*;
+    use crate::{
+        requests::{Request, UploadProgress},
+        testing::{MockResponse, MockServer},
+        types::{
+            ChatOrInlineMessage, InputMediaDocument, InputMediaPhoto,
+            InputMediaVideo, Message, UpdateBuilder,
+        },
+    };
+
+    fn message() -> Message {
+        UpdateBuilder::message().text("hello").build_message()
+    }
+
+    #[tokio::test]
+    async fn missing_file() {
+        let result = FormBuilder::new()
+            .add("document", &InputFile::file("/no/such/file.txt"))
+            .await
+            .add("caption", &"skipped".to_owned())
+            .await
+            .build();
+
+        match result {
+            Err(RequestError::Io(error)) => {
+                assert_eq!(error.kind(), io::ErrorKind::NotFound)
+            }
+            other => panic!("Expected RequestError::Io, got {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn streams_with_progress_keep_length() {
+        let progress = ProgressCallback(Arc::new(|_: &UploadProgress| {}));
+        let parts = FormBuilder::new()
+            .progress(Some(progress))
+            .add("a", &InputFile::memory("a.txt", &b"abc"[..]))
+            .await
+            .add("b", &InputFile::reader("b.txt", &b"abc"[..]))
+            .await
+            .build()
+            .unwrap();
+
+        assert!(matches!(
+            parts[0].data,
+            PartData::Stream { length: Some(3), .. }
+        ));
+        assert!(matches!(parts[1].data, PartData::Stream { length: None, .. }));
+    }
+
+    #[tokio::test]
+    async fn reader_is_uploaded_once() {
+        let file = InputFile::reader("a.txt", &b"abc"[..]);
+
+        assert!(FormBuilder::new().add("a", &file).await.build().is_ok());
+        assert!(matches!(
+            FormBuilder::new().add("a", &file).await.build(),
+            Err(RequestError::Io(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn missing_file_is_not_sent() {
+        let server = MockServer::start().await;
+
+        let result = server
+            .bot()
+            .send_photo(10, InputFile::file("/no/such/photo.png"))
+            .send()
+            .await;
+
+        assert!(matches!(result, Err(RequestError::Io(_))));
+        assert!(server.calls().is_empty());
+    }
+
+    #[tokio::test]
+    async fn upload_progress() {
+        let server = MockServer::start().await;
+        server.respond_always("sendDocument", MockResponse::ok(message()));
+        let reports = Arc::new(Mutex::new(Vec::<UploadProgress>::new()));
+        let bot = {
+            let reports = Arc::clone(&reports);
+            server.bot().with_upload_progress(move |progress| {
+                reports.lock().unwrap().push(progress.clone())
+            })
+        };
+
+        let data = vec![7; 100 * 1024];
+        bot.send_document(10, InputFile::memory("a.bin", data.clone()))
+            .send()
+            .await
+            .unwrap();
+        bot.send_document(10, InputFile::reader("b.txt", &b"abc"[..]))
+            .send()
+            .await
+            .unwrap();
+
+        let calls = server.calls_to("sendDocument");
+        assert_eq!(calls[0].params.part("document").unwrap().data, data);
+        assert_eq!(calls[1].params.part("document").unwrap().data, b"abc");
+
+        let reports = reports.lock().unwrap();
+        let sent = |file_name| {
+            reports
+                .iter()
+                .filter(|progress| progress.file_name == file_name)
+                .map(|progress| (progress.sent, progress.total))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            sent("a.bin"),
+            [(64 * 1024, Some(100 * 1024)), (100 * 1024, Some(100 * 1024))]
+        );
+        assert_eq!(sent("b.txt"), [(3, None)]);
+    }
+
+    #[tokio::test]
+    async fn media_group_attaches_files() {
+        let server = MockServer::start().await;
+        server.respond("sendMediaGroup", MockResponse::ok(json!([])));
+        let path = std::env::temp_dir()
+            .join(format!("teloxide-media-group-{}.mp4", std::process::id()));
+        tokio::fs::write(&path, b"video").await.unwrap();
+
+        let media = vec![
+            InputMedia::Photo(InputMediaPhoto {
+                media: InputFile::memory("a.png", &b"photo"[..]),
+                caption: Some("a photo".to_owned()),
+                parse_mode: None,
+            }),
+            InputMedia::Video(InputMediaVideo {
+                media: InputFile::file(&path),
+                thumb: Some(InputFile::memory("thumb.jpg", &b"thumb"[..])),
+                caption: None,
+                parse_mode: None,
+                width: None,
+                height: None,
+                duration: None,
+                supports_streaming: None,
+            }),
+            InputMedia::Photo(InputMediaPhoto {
+                media: InputFile::file_id("AgADAgAD"),
+                caption: None,
+                parse_mode: None,
+            }),
+        ];
+        server.bot().send_media_group(10, media).send().await.unwrap();
+        tokio::fs::remove_file(&path).await.unwrap();
+
+        let params = &server.calls_to("sendMediaGroup")[0].params;
+        assert_eq!(
+            params.get("media"),
+            Some(json!([
+                {"type": "photo", "media": "attach://file0", "caption": "a photo"},
+                {"type": "video", "media": "attach://file1", "thumb": "attach://file2"},
+                {"type": "photo", "media": "AgADAgAD"},
+            ]))
+        );
+        assert_eq!(params.part("file0").unwrap().data, b"photo");
+        assert_eq!(params.part("file1").unwrap().data, b"video");
+        let thumb = params.part("file2").unwrap();
+        assert_eq!(thumb.file_name.as_deref(), Some("thumb.jpg"));
+        assert_eq!(thumb.data, b"thumb");
+    }
+
+    #[tokio::test]
+    async fn edit_message_media_attaches_file() {
+        let server = MockServer::start().await;
+        server.respond("editMessageMedia", MockResponse::ok(message()));
+
+        let media = InputMedia::Document(InputMediaDocument {
+            media: InputFile::memory("a.txt", &b"text"[..]),
+            thumb: None,
+            caption: None,
+            parse_mode: None,
+        });
+        server
+            .bot()
+            .edit_message_media(
+                ChatOrInlineMessage::Inline { inline_message_id: 42 },
+                media,
+            )
+            .send()
+            .await
+            .unwrap();
+
+        let params = &server.calls_to("editMessageMedia")[0].params;
+        assert_eq!(
+            params.get("media"),
+            Some(json!({"type": "document", "media": "attach://file0"}))
+        );
+        assert_eq!(params.part("file0").unwrap().data, b"text");
+    }
+}
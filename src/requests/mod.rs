@@ -2,9 +2,17 @@
 
 mod all;
 mod form_builder;
+mod upload_cache;
 mod utils;
 
 pub use all::*;
+pub(crate) use upload_cache::send_cached;
+pub use upload_cache::{
+    FileIdStorage, InMemFileIdStorage, UploadCache, UploadKey, UploadKind,
+    UploadSource,
+};
+pub(crate) use utils::ProgressCallback;
+pub use utils::UploadProgress;
 
 /// A type that is returned after making a request to Telegram.
 pub type ResponseResult<T> = Result<T, crate::RequestError>;
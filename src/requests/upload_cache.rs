@@ -0,0 +1,590 @@
+use std::{
+    collections::HashMap,
+    fmt::{self, Debug},
+    future::Future,
+    hash::Hasher,
+    io,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use fnv::FnvHasher;
+use futures::future::BoxFuture;
+use serde::{Deserialize, Serialize};
+use tokio::{io::AsyncReadExt, sync::Mutex};
+
+use crate::{
+    requests::ResponseResult,
+    types::{InputFile, Message},
+    ApiErrorKind, Bot, RequestError,
+};
+
+/// A key under which [`UploadCache`] stores the `file_id` of an uploaded
+/// file.
+///
+/// [`UploadCache`]: crate::requests::UploadCache
+#[derive(Clone, Debug, Eq, Hash, PartialEq, Serialize, Deserialize)]
+pub struct UploadKey {
+    /// How the file was sent. A `file_id` can be sent only as the same kind
+    /// of media, so the same file sent as a photo and as a document is
+    /// cached twice.
+    pub kind: UploadKind,
+
+    /// Which file was sent.
+    pub source: UploadSource,
+}
+
+/// A kind of media a file is uploaded as, see [`UploadKey`].
+///
+/// [`UploadKey`]: crate::requests::UploadKey
+#[derive(Copy, Clone, Debug, Eq, Hash, PartialEq, Serialize, Deserialize)]
+pub enum UploadKind {
+    Photo,
+    Document,
+    Sticker,
+    Video,
+    Audio,
+    Animation,
+    Voice,
+    VideoNote,
+}
+
+/// A file in [`UploadKey`].
+///
+/// [`UploadKey`]: crate::requests::UploadKey
+#[derive(Clone, Debug, Eq, Hash, PartialEq, Serialize, Deserialize)]
+pub enum UploadSource {
+    /// The path of an [`InputFile::File`].
+    ///
+    /// [`InputFile::File`]: crate::types::InputFile::File
+    Path(PathBuf),
+
+    /// The size and the 64-bit FNV-1a hash of the contents of a file,
+    /// formatted as `<size>-<hash in hex>`.
+    ///
+    /// The hash isn't collision-resistant, see [`UploadCache::by_content`].
+    ///
+    /// [`UploadCache::by_content`]: crate::requests::UploadCache::by_content
+    Content(String),
+}
+
+impl UploadSource {
+    /// Returns the source of a file with the contents `data`.
+    #[must_use]
+    pub fn content(data: &[u8]) -> Self {
+        let mut hasher = ContentHasher::default();
+        hasher.write(data);
+        hasher.finish()
+    }
+}
+
+#[derive(Default)]
+struct ContentHasher {
+    hasher: FnvHasher,
+    size: u64,
+}
+
+impl ContentHasher {
+    fn write(&mut self, data: &[u8]) {
+        self.hasher.write(data);
+        self.size += data.len() as u64;
+    }
+
+    fn finish(self) -> UploadSource {
+        UploadSource::Content(format!(
+            "{}-{:016x}",
+            self.size,
+            self.hasher.finish()
+        ))
+    }
+}
+
+/// A storage of `file_id`s of uploaded files.
+///
+/// You can implement this trait for a structure that communicates with a DB
+/// and be sure that after you restart your bot, the files won't be uploaded
+/// again.
+///
+/// A storage doesn't report errors: if it can't load a `file_id`, it should
+/// return `None`, and the file is uploaded as if it wasn't cached.
+///
+/// For a storage based on a simple hash map, see [`InMemFileIdStorage`].
+///
+/// [`InMemFileIdStorage`]: crate::requests::InMemFileIdStorage
+pub trait FileIdStorage: Send + Sync {
+    /// Returns the `file_id` stored under `key`, if any.
+    fn get_file_id(
+        self: Arc<Self>,
+        key: UploadKey,
+    ) -> BoxFuture<'static, Option<String>>;
+
+    /// Stores `file_id` under `key`, replacing the previous one, if any.
+    fn set_file_id(
+        self: Arc<Self>,
+        key: UploadKey,
+        file_id: String,
+    ) -> BoxFuture<'static, ()>;
+
+    /// Removes the `file_id` stored under `key`, if any.
+    ///
+    /// It's called when Telegram rejects the `file_id`.
+    fn remove_file_id(
+        self: Arc<Self>,
+        key: UploadKey,
+    ) -> BoxFuture<'static, ()>;
+}
+
+/// A storage based on a hash map. Stores all the `file_id`s directly in RAM.
+///
+/// ## Note
+/// All the `file_id`s will be lost after you restart your bot. If you need to
+/// store them somewhere on a drive, you need to implement a storage
+/// communicating with a DB.
+#[derive(Debug, Default)]
+pub struct InMemFileIdStorage {
+    map: Mutex<HashMap<UploadKey, String>>,
+}
+
+impl InMemFileIdStorage {
+    #[must_use]
+    pub fn new() -> Arc<Self> {
+        Arc::new(Self::default())
+    }
+}
+
+impl FileIdStorage for InMemFileIdStorage {
+    fn get_file_id(
+        self: Arc<Self>,
+        key: UploadKey,
+    ) -> BoxFuture<'static, Option<String>> {
+        Box::pin(async move { self.map.lock().await.get(&key).cloned() })
+    }
+
+    fn set_file_id(
+        self: Arc<Self>,
+        key: UploadKey,
+        file_id: String,
+    ) -> BoxFuture<'static, ()> {
+        Box::pin(async move {
+            self.map.lock().await.insert(key, file_id);
+        })
+    }
+
+    fn remove_file_id(
+        self: Arc<Self>,
+        key: UploadKey,
+    ) -> BoxFuture<'static, ()> {
+        Box::pin(async move {
+            self.map.lock().await.remove(&key);
+        })
+    }
+}
+
+/// A cache of `file_id`s of uploaded files.
+///
+/// When a bot has a cache (see [`Bot::with_upload_cache`]), the `file_id` of
+/// a file uploaded by [`SendPhoto`], [`SendDocument`], [`SendSticker`],
+/// [`SendVideo`], [`SendAudio`], [`SendAnimation`], [`SendVoice`] or
+/// [`SendVideoNote`] is taken from the sent message and stored. The next time
+/// the same file is sent, the request sends its `file_id` instead of
+/// uploading it again. If Telegram rejects the `file_id` as a wrong or an
+/// invalid one, it's forgotten and the file is uploaded. Other errors are
+/// returned as is.
+///
+/// [`InputFile::File`] is identified by its path, and with
+/// [`UploadCache::by_content`], by its contents, as well as
+/// [`InputFile::Memory`]. Other files aren't cached.
+///
+/// ## Examples
+///
+/// ```no_run
+/// use teloxide::{prelude::*, requests::UploadCache, types::InputFile};
+///
+/// # async fn send(chat_id: i64) -> ResponseResult<()> {
+/// let bot = Bot::from_env().with_upload_cache(UploadCache::in_memory());
+///
+/// // Uploads the sticker.
+/// bot.send_sticker(chat_id, InputFile::file("hello.webp")).send().await?;
+/// // Sends the `file_id` of the uploaded sticker.
+/// bot.send_sticker(chat_id, InputFile::file("hello.webp")).send().await?;
+/// # Ok(())
+/// # }
+/// ```
+///
+/// [`Bot::with_upload_cache`]: crate::Bot::with_upload_cache
+/// [`SendPhoto`]: crate::requests::SendPhoto
+/// [`SendDocument`]: crate::requests::SendDocument
+/// [`SendSticker`]: crate::requests::SendSticker
+/// [`SendVideo`]: crate::requests::SendVideo
+/// [`SendAudio`]: crate::requests::SendAudio
+/// [`SendAnimation`]: crate::requests::SendAnimation
+/// [`SendVoice`]: crate::requests::SendVoice
+/// [`SendVideoNote`]: crate::requests::SendVideoNote
+/// [`InputFile::File`]: crate::types::InputFile::File
+/// [`InputFile::Memory`]: crate::types::InputFile::Memory
+/// [`UploadCache::by_content`]: crate::requests::UploadCache::by_content
+#[derive(Clone)]
+pub struct UploadCache {
+    storage: Arc<dyn FileIdStorage>,
+    by_content: bool,
+}
+
+impl UploadCache {
+    /// Creates a cache which stores `file_id`s in `storage`.
+    pub fn new<S>(storage: Arc<S>) -> Self
+    where
+        S: FileIdStorage + 'static,
+    {
+        Self { storage, by_content: false }
+    }
+
+    /// Creates a cache which stores `file_id`s in [`InMemFileIdStorage`].
+    ///
+    /// [`InMemFileIdStorage`]: crate::requests::InMemFileIdStorage
+    #[must_use]
+    pub fn in_memory() -> Self {
+        Self::new(InMemFileIdStorage::new())
+    }
+
+    /// Identifies [`InputFile::File`] by its contents instead of its path
+    /// and caches [`InputFile::Memory`] as well.
+    ///
+    /// Then a file is uploaded again after it's changed, and copies of a file
+    /// are uploaded once, at the cost of reading the file before every
+    /// request.
+    ///
+    /// ## Note
+    /// Contents are identified by a hash which isn't collision-resistant, so
+    /// files with crafted contents can be taken for each other. Use this
+    /// mode only for trusted contents, not for files supplied by users.
+    ///
+    /// [`InputFile::File`]: crate::types::InputFile::File
+    /// [`InputFile::Memory`]: crate::types::InputFile::Memory
+    #[must_use]
+    pub fn by_content(mut self) -> Self {
+        self.by_content = true;
+        self
+    }
+
+    /// Returns the key under which the `file_id` of `file` sent as `kind` is
+    /// stored, or `None` if `file` isn't cached.
+    pub async fn key(
+        &self,
+        kind: UploadKind,
+        file: &InputFile,
+    ) -> io::Result<Option<UploadKey>> {
+        let source = match file {
+            InputFile::File(path) if self.by_content => {
+                file_source(path).await?
+            }
+            InputFile::File(path) => UploadSource::Path(path.clone()),
+            InputFile::Memory { data, .. } if self.by_content => {
+                UploadSource::content(data)
+            }
+            InputFile::Memory { .. }
+            | InputFile::Reader { .. }
+            | InputFile::Url(_)
+            | InputFile::FileId(_) => return Ok(None),
+        };
+        Ok(Some(UploadKey { kind, source }))
+    }
+}
+
+impl Debug for UploadCache {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("UploadCache")
+            .field("by_content", &self.by_content)
+            .finish()
+    }
+}
+
+async fn file_source(path: &Path) -> io::Result<UploadSource> {
+    let mut file = tokio::fs::File::open(path).await?;
+    let mut hasher = ContentHasher::default();
+    let mut buf = vec![0; 64 * 1024];
+    loop {
+        match file.read(&mut buf).await? {
+            0 => return Ok(hasher.finish()),
+            n => hasher.write(&buf[..n]),
+        }
+    }
+}
+
+/// Sends a message with `file` as `kind` by `send`, passing it the `file_id`
+/// of `file` if it's cached by the bot, or `None` if `file` should be
+/// uploaded.
+pub(crate) async fn send_cached<F, Fut>(
+    bot: &Bot,
+    kind: UploadKind,
+    file: &InputFile,
+    send: F,
+) -> ResponseResult<Message>
+where
+    F: Fn(Option<InputFile>) -> Fut,
+    Fut: Future<Output = ResponseResult<Message>>,
+{
+    let cache = match bot.upload_cache() {
+        Some(cache) => cache,
+        None => return send(None).await,
+    };
+    let key = match cache.key(kind, file).await.map_err(RequestError::Io)? {
+        Some(key) => key,
+        None => return send(None).await,
+    };
+    let storage = &cache.storage;
+
+    if let Some(file_id) = Arc::clone(storage).get_file_id(key.clone()).await {
+        match send(Some(InputFile::FileId(file_id))).await {
+            Err(RequestError::ApiError { kind, .. })
+                if rejects_file_id(&kind) =>
+            {
+                Arc::clone(storage).remove_file_id(key.clone()).await;
+            }
+            result => return result,
+        }
+    }
+
+    let message = send(None).await?;
+    if let Some(file_id) = uploaded_file_id(&message, kind) {
+        Arc::clone(storage).set_file_id(key, file_id.to_owned()).await;
+    }
+    Ok(message)
+}
+
+/// Returns `true` if Telegram has rejected a `file_id` with the error `kind`.
+fn rejects_file_id(kind: &ApiErrorKind) -> bool {
+    matches!(
+        kind,
+        ApiErrorKind::WrongFileID
+            | ApiErrorKind::FileIdInvalid
+            | ApiErrorKind::WrongFileIdOrUrl
+    )
+}
+
+/// Returns the `file_id` of the file sent as `kind` in `message`, unless the
+/// server has stored it as another kind of media.
+fn uploaded_file_id(message: &Message, kind: UploadKind) -> Option<&str> {
+    let file_id = match kind {
+        UploadKind::Photo => message
+            .photo()
+            .and_then(|sizes| sizes.last())
+            .map(|size| &size.file_id),
+        UploadKind::Document => {
+            message.document().map(|document| &document.file_id)
+        }
+        UploadKind::Sticker => {
+            message.sticker().map(|sticker| &sticker.file_id)
+        }
+        UploadKind::Video => message.video().map(|video| &video.file_id),
+        UploadKind::Audio => message.audio().map(|audio| &audio.file_id),
+        UploadKind::Animation => {
+            message.animation().map(|animation| &animation.file_id)
+        }
+        UploadKind::Voice => message.voice().map(|voice| &voice.file_id),
+        UploadKind::VideoNote => {
+            message.video_note().map(|video_note| &video_note.file_id)
+        }
+    };
+    file_id.map(String::as_str)
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::{
+        requests::Request,
+        testing::{MockResponse, MockServer},
+        types::{Document, UpdateBuilder},
+    };
+
+    fn document_message(file_id: &str) -> Message {
+        UpdateBuilder::message()
+            .document(Document {
+                file_id: file_id.to_owned(),
+                file_unique_id: "unique".to_owned(),
+                thumb: None,
+                file_name: None,
+                mime_type: None,
+                file_size: None,
+            })
+            .build_message()
+    }
+
+    #[test]
+    fn content_source() {
+        assert_eq!(
+            UploadSource::content(b"abc"),
+            UploadSource::Content("3-e71fa2190541574b".to_owned())
+        );
+    }
+
+    #[tokio::test]
+    async fn keys() {
+        let path = std::env::temp_dir()
+            .join(format!("teloxide-upload-cache-{}.txt", std::process::id()));
+        tokio::fs::write(&path, b"abc").await.unwrap();
+
+        let by_path = UploadCache::in_memory();
+        let by_content = UploadCache::in_memory().by_content();
+        let file = InputFile::file(&path);
+        let memory = InputFile::memory("a.txt", &b"abc"[..]);
+        let key = |source| UploadKey { kind: UploadKind::Photo, source };
+
+        assert_eq!(
+            by_path.key(UploadKind::Photo, &file).await.unwrap(),
+            Some(key(UploadSource::Path(path.clone())))
+        );
+        assert_eq!(
+            by_path.key(UploadKind::Photo, &memory).await.unwrap(),
+            None
+        );
+        assert_eq!(
+            by_content.key(UploadKind::Photo, &file).await.unwrap(),
+            Some(key(UploadSource::content(b"abc")))
+        );
+        assert_eq!(
+            by_content.key(UploadKind::Photo, &memory).await.unwrap(),
+            Some(key(UploadSource::content(b"abc")))
+        );
+        assert_eq!(
+            by_content
+                .key(UploadKind::Photo, &InputFile::url("u"))
+                .await
+                .unwrap(),
+            None
+        );
+
+        tokio::fs::remove_file(&path).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn sends_cached_file_id() {
+        let server = MockServer::start().await;
+        server.respond_always(
+            "sendDocument",
+            MockResponse::ok(document_message("BQADAgAD")),
+        );
+        let bot = server
+            .bot()
+            .with_upload_cache(UploadCache::in_memory().by_content());
+        let file = InputFile::memory("a.txt", &b"abc"[..]);
+
+        bot.send_document(10, file.clone()).send().await.unwrap();
+        bot.send_document(10, file.clone()).send().await.unwrap();
+        bot.send_document(10, InputFile::memory("b.txt", &b"abd"[..]))
+            .send()
+            .await
+            .unwrap();
+
+        let calls = server.calls_to("sendDocument");
+        assert_eq!(calls[0].params.part("document").unwrap().data, b"abc");
+        assert_eq!(calls[1].params.get("document"), Some(json!("BQADAgAD")));
+        assert_eq!(calls[2].params.part("document").unwrap().data, b"abd");
+    }
+
+    #[tokio::test]
+    async fn caches_by_kind() {
+        let server = MockServer::start().await;
+        server
+            .respond_always(
+                "sendDocument",
+                MockResponse::ok(document_message("doc")),
+            )
+            // The server has stored the video as a document.
+            .respond_always(
+                "sendVideo",
+                MockResponse::ok(document_message("doc")),
+            );
+        let storage = InMemFileIdStorage::new();
+        let bot = server.bot().with_upload_cache(
+            UploadCache::new(Arc::clone(&storage)).by_content(),
+        );
+        let file = InputFile::memory("a.mp4", &b"abc"[..]);
+
+        bot.send_document(10, file.clone()).send().await.unwrap();
+        bot.send_video(10, file.clone()).send().await.unwrap();
+        bot.send_video(10, file.clone()).send().await.unwrap();
+
+        let calls = server.calls_to("sendVideo");
+        assert_eq!(calls[0].params.part("video").unwrap().data, b"abc");
+        assert_eq!(calls[1].params.part("video").unwrap().data, b"abc");
+        let key =
+            |kind| UploadKey { kind, source: UploadSource::content(b"abc") };
+        assert_eq!(
+            Arc::clone(&storage)
+                .get_file_id(key(UploadKind::Document))
+                .await
+                .as_deref(),
+            Some("doc")
+        );
+        assert_eq!(storage.get_file_id(key(UploadKind::Video)).await, None);
+    }
+
+    #[tokio::test]
+    async fn uploads_again_if_file_id_is_rejected() {
+        let server = MockServer::start().await;
+        server
+            .respond(
+                "sendDocument",
+                MockResponse::error(
+                    400,
+                    "Bad Request: wrong file identifier/HTTP URL specified",
+                ),
+            )
+            .respond("sendDocument", MockResponse::ok(document_message("new")));
+        let storage = InMemFileIdStorage::new();
+        let key = UploadKey {
+            kind: UploadKind::Document,
+            source: UploadSource::content(b"abc"),
+        };
+        Arc::clone(&storage).set_file_id(key.clone(), "old".to_owned()).await;
+        let bot = server.bot().with_upload_cache(
+            UploadCache::new(Arc::clone(&storage)).by_content(),
+        );
+
+        bot.send_document(10, InputFile::memory("a.txt", &b"abc"[..]))
+            .send()
+            .await
+            .unwrap();
+
+        let calls = server.calls_to("sendDocument");
+        assert_eq!(calls[0].params.get("document"), Some(json!("old")));
+        assert_eq!(calls[1].params.part("document").unwrap().data, b"abc");
+        assert_eq!(storage.get_file_id(key).await.as_deref(), Some("new"));
+    }
+
+    #[tokio::test]
+    async fn returns_other_errors() {
+        let server = MockServer::start().await;
+        server.respond(
+            "sendDocument",
+            MockResponse::error(400, "Bad Request: chat not found"),
+        );
+        let storage = InMemFileIdStorage::new();
+        let key = UploadKey {
+            kind: UploadKind::Document,
+            source: UploadSource::content(b"abc"),
+        };
+        Arc::clone(&storage).set_file_id(key.clone(), "old".to_owned()).await;
+        let bot = server.bot().with_upload_cache(
+            UploadCache::new(Arc::clone(&storage)).by_content(),
+        );
+
+        let result = bot
+            .send_document(10, InputFile::memory("a.txt", &b"abc"[..]))
+            .send()
+            .await;
+
+        assert!(matches!(
+            result,
+            Err(RequestError::ApiError {
+                kind: ApiErrorKind::ChatNotFound,
+                ..
+            })
+        ));
+        assert_eq!(server.calls_to("sendDocument").len(), 1);
+        assert_eq!(storage.get_file_id(key).await.as_deref(), Some("old"));
+    }
+}
@@ -1,14 +1,59 @@
-use std::{borrow::Cow, path::PathBuf};
+use std::{
+    borrow::Cow,
+    fmt::{self, Debug},
+    io,
+    path::PathBuf,
+    sync::Arc,
+};
 
 use bytes::{Bytes, BytesMut};
-use reqwest::{multipart::Part, Body};
+use futures::{stream, Stream, TryStreamExt};
 use tokio_util::codec::{Decoder, FramedRead};
 
+use crate::{
+    net::{FormPart, PartData, PartStream},
+    types::FileReader,
+};
+
+/// The size of chunks in which files from memory are uploaded, if their
+/// progress is reported.
+const MEMORY_CHUNK_SIZE: usize = 64 * 1024;
+
+/// The progress of uploading a file, reported to a callback set by
+/// [`Bot::with_upload_progress`].
+///
+/// [`Bot::with_upload_progress`]: crate::Bot::with_upload_progress
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct UploadProgress {
+    /// The name of the file.
+    pub file_name: String,
+
+    /// The number of bytes passed to the connection so far.
+    pub sent: u64,
+
+    /// The size of the file, if known in advance.
+    pub total: Option<u64>,
+}
+
+/// A callback receiving [`UploadProgress`].
+///
+/// [`UploadProgress`]: crate::requests::UploadProgress
+#[derive(Clone)]
+pub(crate) struct ProgressCallback(
+    pub(crate) Arc<dyn Fn(&UploadProgress) + Send + Sync>,
+);
+
+impl Debug for ProgressCallback {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("ProgressCallback")
+    }
+}
+
 struct FileDecoder;
 
 impl Decoder for FileDecoder {
     type Item = Bytes;
-    type Error = std::io::Error;
+    type Error = io::Error;
 
     fn decode(
         &mut self,
@@ -21,23 +66,107 @@ impl Decoder for FileDecoder {
     }
 }
 
-pub async fn file_to_part(path_to_file: PathBuf) -> Part {
-    let file_name =
-        path_to_file.file_name().unwrap().to_string_lossy().into_owned();
+pub async fn file_to_part(
+    name: String,
+    path_to_file: PathBuf,
+    progress: Option<ProgressCallback>,
+) -> io::Result<FormPart> {
+    let file_name = path_to_file
+        .file_name()
+        .ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("{} is not a file", path_to_file.display()),
+            )
+        })?
+        .to_string_lossy()
+        .into_owned();
 
-    let file = FramedRead::new(
-        tokio::fs::File::open(path_to_file).await.unwrap(), /* TODO: this
-                                                             * can
-                                                             * cause panics */
-        FileDecoder,
-    );
+    let file = tokio::fs::File::open(&path_to_file).await?;
+    let total = file.metadata().await?.len();
+    let file = FramedRead::new(file, FileDecoder);
 
-    Part::stream(Body::wrap_stream(file)).file_name(file_name)
+    Ok(stream_to_part(name, file, file_name, Some(total), progress))
 }
 
 pub fn file_from_memory_to_part(
+    name: String,
     data: Cow<'static, [u8]>,
+    file_name: String,
+    progress: Option<ProgressCallback>,
+) -> FormPart {
+    let data = match data {
+        Cow::Borrowed(data) => Bytes::from_static(data),
+        Cow::Owned(data) => Bytes::from(data),
+    };
+    if progress.is_none() {
+        return FormPart {
+            name,
+            file_name: Some(file_name),
+            data: PartData::Bytes(data),
+        };
+    }
+
+    let total = data.len() as u64;
+    let chunks = (0..data.len())
+        .step_by(MEMORY_CHUNK_SIZE)
+        .map(move |start| {
+            let end = (start + MEMORY_CHUNK_SIZE).min(data.len());
+            Ok(data.slice(start..end))
+        })
+        .collect::<Vec<_>>();
+
+    stream_to_part(name, stream::iter(chunks), file_name, Some(total), progress)
+}
+
+pub fn reader_to_part(
+    name: String,
+    reader: &FileReader,
+    file_name: String,
+    progress: Option<ProgressCallback>,
+) -> io::Result<FormPart> {
+    let reader = reader.take().ok_or_else(|| {
+        io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("The reader of {} has already been uploaded", file_name),
+        )
+    })?;
+
+    Ok(stream_to_part(
+        name,
+        FramedRead::new(reader, FileDecoder),
+        file_name,
+        None,
+        progress,
+    ))
+}
+
+/// Makes a part of `stream`, whose exact size is `total` bytes, if known.
+fn stream_to_part<S>(
     name: String,
-) -> Part {
-    Part::bytes(data).file_name(name)
+    stream: S,
+    file_name: String,
+    total: Option<u64>,
+    progress: Option<ProgressCallback>,
+) -> FormPart
+where
+    S: Stream<Item = io::Result<Bytes>> + Send + Sync + 'static,
+{
+    let stream: PartStream = match progress {
+        Some(ProgressCallback(callback)) => {
+            let mut progress =
+                UploadProgress { file_name: file_name.clone(), sent: 0, total };
+            Box::pin(stream.inspect_ok(move |chunk| {
+                progress.sent += chunk.len() as u64;
+                callback(&progress);
+            }))
+        }
+        None => Box::pin(stream),
+    };
+
+    FormPart {
+        name,
+        file_name: Some(file_name),
+        data: PartData::Stream { stream, length: total },
+    }
 }
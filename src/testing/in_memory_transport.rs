@@ -0,0 +1,192 @@
+use std::sync::Arc;
+
+use serde_json::Value;
+
+use crate::{
+    net::{FormPart, HttpBody, HttpRequest, HttpResponse, Transport},
+    requests::ResponseResult,
+    Bot, RequestError,
+};
+
+use super::{
+    mock_server::{Inner, State},
+    Call, MockResponse, Params, Part,
+};
+
+/// A [`Transport`] which answers requests by scripted responses instead of
+/// sending them.
+///
+/// It's like [`MockServer`], but without a server: requests don't leave the
+/// process. Clones share the same responses and calls.
+///
+/// ```
+/// use teloxide::{
+///     prelude::*,
+///     testing::{InMemoryTransport, MockResponse},
+/// };
+///
+/// # #[tokio::main]
+/// # async fn main() {
+/// let transport = InMemoryTransport::new();
+/// transport.respond_always("leaveChat", MockResponse::ok(true));
+///
+/// let bot = transport.bot();
+/// bot.leave_chat(123).send().await.unwrap();
+///
+/// let calls = transport.calls_to("leaveChat");
+/// assert_eq!(calls[0].params.get("chat_id"), Some(123.into()));
+/// # }
+/// ```
+///
+/// [`Transport`]: crate::net::Transport
+/// [`MockServer`]: crate::testing::MockServer
+#[derive(Clone, Default)]
+pub struct InMemoryTransport {
+    state: Arc<State>,
+}
+
+impl InMemoryTransport {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a bot that sends all its requests to this transport.
+    pub fn bot(&self) -> Arc<Bot> {
+        Bot::new(super::mock_server::TOKEN).with_transport(self.clone())
+    }
+
+    /// Makes the next call of `method` return `response`.
+    ///
+    /// Responses scripted for the same method are returned in the order they
+    /// were scripted, each one only once. After they are exhausted,
+    /// the response set by [`InMemoryTransport::respond_always`] is used.
+    pub fn respond<M>(&self, method: M, response: MockResponse) -> &Self
+    where
+        M: Into<String>,
+    {
+        self.inner()
+            .scripted
+            .entry(method.into())
+            .or_default()
+            .push_back(response);
+        self
+    }
+
+    /// Makes all the calls of `method` return `response`, unless a response
+    /// was scripted by [`InMemoryTransport::respond`].
+    ///
+    /// Methods without any response return an error with the 404 code.
+    pub fn respond_always<M>(&self, method: M, response: MockResponse) -> &Self
+    where
+        M: Into<String>,
+    {
+        self.inner().fallback.insert(method.into(), response);
+        self
+    }
+
+    /// All the calls received so far, in the order of arrival.
+    pub fn calls(&self) -> Vec<Call> {
+        self.inner().calls.clone()
+    }
+
+    /// All the calls of `method` received so far, in the order of arrival.
+    pub fn calls_to(&self, method: &str) -> Vec<Call> {
+        self.inner()
+            .calls
+            .iter()
+            .filter(|call| call.method == method)
+            .cloned()
+            .collect()
+    }
+
+    fn inner(&self) -> std::sync::MutexGuard<'_, Inner> {
+        self.state.inner.lock().unwrap()
+    }
+}
+
+#[async_trait::async_trait]
+impl Transport for InMemoryTransport {
+    async fn send(&self, request: HttpRequest) -> ResponseResult<HttpResponse> {
+        let params = match request.body {
+            HttpBody::Json(json) => Params::Json(
+                serde_json::from_str(&json).unwrap_or(Value::String(json)),
+            ),
+            HttpBody::Multipart(parts) => {
+                let mut params = Vec::with_capacity(parts.len());
+                for FormPart { name, file_name, data } in parts {
+                    let data =
+                        data.into_bytes().await.map_err(RequestError::Io)?;
+                    params.push(Part { name, file_name, data: data.to_vec() });
+                }
+                Params::Multipart(params)
+            }
+        };
+
+        let (status, body) = self
+            .state
+            .call(Call { method: request.method_name, params })
+            .into_parts();
+        Ok(HttpResponse { status, body: body.to_string().into() })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::{
+        net::TransportMiddleware, requests::Request, types::InputFile,
+    };
+
+    struct Prefix(&'static str);
+
+    #[async_trait::async_trait]
+    impl TransportMiddleware for Prefix {
+        async fn handle(
+            &self,
+            mut request: HttpRequest,
+            next: &dyn Transport,
+        ) -> ResponseResult<HttpResponse> {
+            request.method_name = format!("{}{}", self.0, request.method_name);
+            next.send(request).await
+        }
+    }
+
+    #[tokio::test]
+    async fn records_calls() {
+        let transport = InMemoryTransport::new();
+        transport
+            .respond("leaveChat", MockResponse::error(400, "Bad Request"))
+            .respond_always("leaveChat", MockResponse::ok(true));
+        transport.respond_always("sendDocument", MockResponse::ok(json!({})));
+        let bot = transport.bot();
+
+        assert!(bot.leave_chat(1).send().await.is_err());
+        bot.leave_chat(2).send().await.unwrap();
+        bot.send_document(3, InputFile::memory("a.txt", &b"abc"[..]))
+            .send()
+            .await
+            .ok();
+
+        let calls = transport.calls();
+        assert_eq!(calls[1].params.get("chat_id"), Some(json!(2)));
+        let document = calls[2].params.part("document").unwrap();
+        assert_eq!(document.file_name.as_deref(), Some("a.txt"));
+        assert_eq!(document.data, b"abc");
+    }
+
+    #[tokio::test]
+    async fn middlewares_added_later_run_first() {
+        let transport = InMemoryTransport::new();
+        transport.respond_always("ableaveChat", MockResponse::ok(true));
+        let bot = transport
+            .bot()
+            .with_transport_middleware(Prefix("a"))
+            .with_transport_middleware(Prefix("b"));
+
+        bot.leave_chat(1).send().await.unwrap();
+        assert_eq!(transport.calls_to("ableaveChat").len(), 1);
+    }
+}
@@ -0,0 +1,595 @@
+use std::{
+    collections::{HashMap, VecDeque},
+    convert::Infallible,
+    net::{SocketAddr, TcpListener},
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use hyper::{
+    service::{make_service_fn, service_fn},
+    Body, Request, Response, Server, StatusCode,
+};
+use serde::Serialize;
+use serde_json::{json, Value};
+use tokio::sync::{oneshot, Notify};
+
+use crate::{types::ResponseParameters, Bot};
+
+use super::multipart;
+
+/// The token of bots created by [`MockServer::bot`] and
+/// [`InMemoryTransport::bot`].
+///
+/// [`InMemoryTransport::bot`]: super::InMemoryTransport::bot
+pub(super) const TOKEN: &str = "123456:mock-token";
+
+/// An in-process fake Bot API server.
+///
+/// The server is shut down when it is dropped.
+///
+/// See [the module-level documentation](crate::testing) for an example.
+pub struct MockServer {
+    addr: SocketAddr,
+    state: Arc<State>,
+    shutdown: Option<oneshot::Sender<()>>,
+}
+
+#[derive(Default)]
+pub(super) struct State {
+    pub(super) inner: Mutex<Inner>,
+    new_call: Notify,
+}
+
+#[derive(Default)]
+pub(super) struct Inner {
+    pub(super) calls: Vec<Call>,
+    pub(super) scripted: HashMap<String, VecDeque<MockResponse>>,
+    pub(super) fallback: HashMap<String, MockResponse>,
+    files: HashMap<String, Vec<u8>>,
+}
+
+impl State {
+    /// Records `call` and returns the response to it.
+    pub(super) fn call(&self, call: Call) -> MockResponse {
+        let response = {
+            let mut inner = self.inner.lock().unwrap();
+            let method = call.method.clone();
+            inner.calls.push(call);
+
+            let scripted =
+                inner.scripted.get_mut(&method).and_then(VecDeque::pop_front);
+            scripted
+                .or_else(|| inner.fallback.get(&method).cloned())
+                .unwrap_or_else(|| MockResponse::not_mocked(&method))
+        };
+        self.new_call.notify();
+        response
+    }
+}
+
+impl MockServer {
+    /// Starts a new server on a random local port.
+    ///
+    /// # Panics
+    /// If cannot bind a local port.
+    pub async fn start() -> Self {
+        let listener = TcpListener::bind("127.0.0.1:0")
+            .expect("Cannot bind a port for the mock server");
+        let addr = listener.local_addr().expect("Cannot get the local address");
+
+        let state = Arc::new(State::default());
+        let make_service = {
+            let state = Arc::clone(&state);
+            make_service_fn(move |_| {
+                let state = Arc::clone(&state);
+                async move {
+                    Ok::<_, Infallible>(service_fn(move |req| {
+                        handle(Arc::clone(&state), req)
+                    }))
+                }
+            })
+        };
+
+        let (shutdown, shutdown_rx) = oneshot::channel();
+        let server = Server::from_tcp(listener)
+            .expect("Cannot start the mock server")
+            .serve(make_service)
+            .with_graceful_shutdown(async {
+                shutdown_rx.await.ok();
+            });
+
+        tokio::spawn(async move {
+            if let Err(error) = server.await {
+                log::error!("The mock server failed: {}", error);
+            }
+        });
+
+        Self { addr, state, shutdown: Some(shutdown) }
+    }
+
+    /// The URL of this server, suitable as a Bot API URL.
+    pub fn url(&self) -> String {
+        format!("http://{}", self.addr)
+    }
+
+    /// Creates a bot that sends all its requests to this server.
+    pub fn bot(&self) -> Arc<Bot> {
+        Bot::new(TOKEN).with_api_url(self.url())
+    }
+
+    /// Makes the next call of `method` return `response`.
+    ///
+    /// Responses scripted for the same method are returned in the order they
+    /// were scripted, each one only once. After they are exhausted,
+    /// the response set by [`MockServer::respond_always`] is used.
+    pub fn respond<M>(&self, method: M, response: MockResponse) -> &Self
+    where
+        M: Into<String>,
+    {
+        self.inner()
+            .scripted
+            .entry(method.into())
+            .or_default()
+            .push_back(response);
+        self
+    }
+
+    /// Makes all the calls of `method` return `response`, unless a response
+    /// was scripted by [`MockServer::respond`].
+    ///
+    /// Methods without any response return an error with the 404 code.
+    pub fn respond_always<M>(&self, method: M, response: MockResponse) -> &Self
+    where
+        M: Into<String>,
+    {
+        self.inner().fallback.insert(method.into(), response);
+        self
+    }
+
+    /// Serves `data` as the file at `file_path`, for [`Bot::download_file`]
+    /// and similar methods.
+    ///
+    /// Requests of the file with the `Range: bytes=<start>-` header get the
+    /// rest of the file. Downloads aren't recorded as calls.
+    ///
+    /// [`Bot::download_file`]: crate::Bot::download_file
+    pub fn serve_file<P, D>(&self, file_path: P, data: D) -> &Self
+    where
+        P: Into<String>,
+        D: Into<Vec<u8>>,
+    {
+        self.inner().files.insert(file_path.into(), data.into());
+        self
+    }
+
+    /// All the calls received so far, in the order of arrival.
+    pub fn calls(&self) -> Vec<Call> {
+        self.inner().calls.clone()
+    }
+
+    /// All the calls of `method` received so far, in the order of arrival.
+    pub fn calls_to(&self, method: &str) -> Vec<Call> {
+        self.inner()
+            .calls
+            .iter()
+            .filter(|call| call.method == method)
+            .cloned()
+            .collect()
+    }
+
+    /// Waits until the server has received at least `n` calls and returns
+    /// them.
+    ///
+    /// # Panics
+    /// If `n` calls haven't been received within `timeout`.
+    pub async fn wait_for_calls(
+        &self,
+        n: usize,
+        timeout: Duration,
+    ) -> Vec<Call> {
+        let wait = async {
+            loop {
+                let notified = self.state.new_call.notified();
+                {
+                    let inner = self.inner();
+                    if inner.calls.len() >= n {
+                        return inner.calls.clone();
+                    }
+                }
+                notified.await;
+            }
+        };
+
+        match tokio::time::timeout(timeout, wait).await {
+            Ok(calls) => calls,
+            Err(_) => panic!(
+                "Expected {} calls within {:?}, got {:?}",
+                n,
+                timeout,
+                self.calls()
+            ),
+        }
+    }
+
+    fn inner(&self) -> std::sync::MutexGuard<'_, Inner> {
+        self.state.inner.lock().unwrap()
+    }
+}
+
+impl Drop for MockServer {
+    fn drop(&mut self) {
+        if let Some(shutdown) = self.shutdown.take() {
+            shutdown.send(()).ok();
+        }
+    }
+}
+
+/// A method call received by [`MockServer`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct Call {
+    /// A name of the method, e.g. `sendMessage`.
+    pub method: String,
+
+    /// Parameters of the call.
+    pub params: Params,
+}
+
+/// Parameters of a [`Call`].
+#[derive(Clone, Debug, PartialEq)]
+pub enum Params {
+    /// Parameters sent as `application/json`.
+    Json(Value),
+
+    /// Parameters sent as `multipart/form-data`.
+    Multipart(Vec<Part>),
+}
+
+impl Params {
+    /// Returns a parameter called `name`.
+    ///
+    /// Text fields of `multipart/form-data` are parsed as JSON, falling back
+    /// to a JSON string if they are not valid JSON. Files are returned as
+    /// their names.
+    pub fn get(&self, name: &str) -> Option<Value> {
+        match self {
+            Params::Json(value) => value.get(name).cloned(),
+            Params::Multipart(parts) => {
+                let part = parts.iter().find(|part| part.name == name)?;
+                Some(match (&part.file_name, part.text()) {
+                    (Some(file_name), _) => Value::String(file_name.clone()),
+                    (None, Some(text)) => serde_json::from_str(text)
+                        .unwrap_or_else(|_| Value::String(text.to_owned())),
+                    (None, None) => Value::Null,
+                })
+            }
+        }
+    }
+
+    /// Returns a `multipart/form-data` part called `name`.
+    pub fn part(&self, name: &str) -> Option<&Part> {
+        match self {
+            Params::Json(_) => None,
+            Params::Multipart(parts) => {
+                parts.iter().find(|part| part.name == name)
+            }
+        }
+    }
+}
+
+/// A single part of a `multipart/form-data` [`Call`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct Part {
+    /// A name of the field.
+    pub name: String,
+
+    /// A file name, if the part is a file.
+    pub file_name: Option<String>,
+
+    /// Raw contents of the part.
+    pub data: Vec<u8>,
+}
+
+impl Part {
+    /// Contents of the part, if they are valid UTF-8.
+    pub fn text(&self) -> Option<&str> {
+        std::str::from_utf8(&self.data).ok()
+    }
+}
+
+/// A scripted response of [`MockServer`].
+#[derive(Clone, Debug, PartialEq)]
+pub enum MockResponse {
+    /// A successful response with the given `result`.
+    Ok(Value),
+
+    /// An unsuccessful response.
+    Err {
+        error_code: u16,
+        description: String,
+        parameters: Option<ResponseParameters>,
+    },
+}
+
+impl MockResponse {
+    /// A successful response with `result` serialized to JSON.
+    ///
+    /// # Panics
+    /// If `result` cannot be serialized.
+    pub fn ok<T>(result: T) -> Self
+    where
+        T: Serialize,
+    {
+        Self::Ok(serde_json::to_value(result).expect("Cannot serialize result"))
+    }
+
+    /// An unsuccessful response with `error_code` and `description`.
+    ///
+    /// The description is what Telegram reports, so the familiar ones are
+    /// parsed into the corresponding [`ApiErrorKind`].
+    ///
+    /// [`ApiErrorKind`]: crate::ApiErrorKind
+    pub fn error<S>(error_code: u16, description: S) -> Self
+    where
+        S: Into<String>,
+    {
+        Self::Err {
+            error_code,
+            description: description.into(),
+            parameters: None,
+        }
+    }
+
+    /// A flood control error, asking to retry after `seconds`.
+    pub fn retry_after(seconds: i32) -> Self {
+        Self::Err {
+            error_code: 429,
+            description: format!("Too Many Requests: retry after {}", seconds),
+            parameters: Some(ResponseParameters::RetryAfter(seconds)),
+        }
+    }
+
+    /// An error reporting that the group has been migrated to the
+    /// supergroup `chat_id`.
+    pub fn migrate_to_chat_id(chat_id: i64) -> Self {
+        Self::Err {
+            error_code: 400,
+            description: "Bad Request: group chat was upgraded to a \
+                          supergroup chat"
+                .to_owned(),
+            parameters: Some(ResponseParameters::MigrateToChatId(chat_id)),
+        }
+    }
+
+    fn not_mocked(method: &str) -> Self {
+        Self::error(404, format!("Not Found: method {} is not mocked", method))
+    }
+
+    /// Returns the status and the body of the response.
+    pub(super) fn into_parts(self) -> (StatusCode, Value) {
+        match self {
+            MockResponse::Ok(result) => {
+                (StatusCode::OK, json!({ "ok": true, "result": result }))
+            }
+            MockResponse::Err { error_code, description, parameters } => {
+                let mut body = json!({
+                    "ok": false,
+                    "error_code": error_code,
+                    "description": description,
+                });
+                if let Some(parameters) = parameters {
+                    body["response_parameters"] = json!(parameters);
+                }
+                (
+                    StatusCode::from_u16(error_code)
+                        .unwrap_or(StatusCode::INTERNAL_SERVER_ERROR),
+                    body,
+                )
+            }
+        }
+    }
+
+    fn into_response(self) -> Response<Body> {
+        let (status, body) = self.into_parts();
+        Response::builder()
+            .status(status)
+            .header("Content-Type", "application/json")
+            .body(Body::from(body.to_string()))
+            .unwrap()
+    }
+}
+
+async fn handle(
+    state: Arc<State>,
+    req: Request<Body>,
+) -> Result<Response<Body>, hyper::Error> {
+    // Files are downloaded from `/file/bot<token>/<file path>`.
+    if let Some(path) = req.uri().path().strip_prefix("/file/") {
+        let file_path = path.find('/').map_or("", |i| &path[i + 1..]);
+        let file = state.inner.lock().unwrap().files.get(file_path).cloned();
+        return Ok(file_response(file, req.headers().get("Range")));
+    }
+
+    // The path looks like `/bot<token>/<method>`.
+    let method =
+        req.uri().path().rsplit('/').next().unwrap_or_default().to_owned();
+    let content_type = req
+        .headers()
+        .get("Content-Type")
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.parse::<mime::Mime>().ok());
+    let body = hyper::body::to_bytes(req.into_body()).await?;
+
+    let params = match content_type {
+        Some(ref mime) if mime.essence_str() == "multipart/form-data" => {
+            let boundary = mime
+                .get_param(mime::BOUNDARY)
+                .map(|b| b.as_str())
+                .unwrap_or("");
+            Params::Multipart(multipart::parse(&body, boundary))
+        }
+        _ if body.is_empty() => Params::Json(Value::Null),
+        _ => Params::Json(serde_json::from_slice(&body).unwrap_or_else(|_| {
+            Value::String(String::from_utf8_lossy(&body).into_owned())
+        })),
+    };
+
+    Ok(state.call(Call { method, params }).into_response())
+}
+
+fn file_response(
+    file: Option<Vec<u8>>,
+    range: Option<&hyper::header::HeaderValue>,
+) -> Response<Body> {
+    let file = match file {
+        Some(file) => file,
+        None => {
+            return Response::builder()
+                .status(StatusCode::NOT_FOUND)
+                .body(Body::empty())
+                .unwrap()
+        }
+    };
+
+    let start = range
+        .and_then(|range| range.to_str().ok())
+        .and_then(|range| range.strip_prefix("bytes="))
+        .and_then(|range| range.strip_suffix('-'))
+        .and_then(|start| start.parse::<usize>().ok());
+    match start {
+        None => Response::new(Body::from(file)),
+        Some(start) if start < file.len() => Response::builder()
+            .status(StatusCode::PARTIAL_CONTENT)
+            .header(
+                "Content-Range",
+                format!("bytes {}-{}/{}", start, file.len() - 1, file.len()),
+            )
+            .body(Body::from(file[start..].to_vec()))
+            .unwrap(),
+        Some(_) => Response::builder()
+            .status(StatusCode::RANGE_NOT_SATISFIABLE)
+            .header("Content-Range", format!("bytes */{}", file.len()))
+            .body(Body::empty())
+            .unwrap(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        dispatching::{Dispatcher, DispatcherHandlerRx},
+        error_handlers::IgnoringErrorHandlerSafe,
+        requests::Request,
+        types::{Message, UpdateBuilder},
+        RequestError,
+    };
+    use futures::StreamExt;
+
+    const TIMEOUT: Duration = Duration::from_secs(5);
+
+    fn message() -> Message {
+        UpdateBuilder::message()
+            .message_id(1)
+            .in_private(20, 10)
+            .text("hello")
+            .build_message()
+    }
+
+    #[tokio::test]
+    async fn records_json_calls() {
+        let server = MockServer::start().await;
+        server.respond("sendMessage", MockResponse::ok(message()));
+
+        let message =
+            server.bot().send_message(10, "hello").send().await.unwrap();
+
+        assert_eq!(message.id, 1);
+        let calls = server.calls_to("sendMessage");
+        assert_eq!(calls.len(), 1);
+        assert_eq!(calls[0].params.get("chat_id"), Some(json!(10)));
+        assert_eq!(calls[0].params.get("text"), Some(json!("hello")));
+    }
+
+    #[tokio::test]
+    async fn scripted_responses_come_in_order() {
+        let server = MockServer::start().await;
+        server
+            .respond("leaveChat", MockResponse::retry_after(3))
+            .respond("leaveChat", MockResponse::migrate_to_chat_id(42))
+            .respond_always("leaveChat", MockResponse::ok(true));
+        let bot = server.bot();
+
+        match bot.leave_chat(1).send().await {
+            Err(RequestError::RetryAfter(3)) => {}
+            other => panic!("Expected RetryAfter(3), got {:?}", other),
+        }
+        match bot.leave_chat(1).send().await {
+            Err(RequestError::MigrateToChatId(42)) => {}
+            other => panic!("Expected MigrateToChatId(42), got {:?}", other),
+        }
+        assert!(bot.leave_chat(1).send().await.is_ok());
+        assert!(bot.leave_chat(1).send().await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn not_mocked_methods_fail() {
+        let server = MockServer::start().await;
+
+        match server.bot().get_me().send().await {
+            Err(RequestError::ApiError { status_code, .. }) => {
+                assert_eq!(status_code, StatusCode::NOT_FOUND)
+            }
+            other => panic!("Expected ApiError, got {:?}", other),
+        }
+        assert_eq!(server.calls()[0].method, "getMe");
+    }
+
+    #[tokio::test]
+    async fn records_multipart_calls() {
+        let server = MockServer::start().await;
+        server.respond("sendPhoto", MockResponse::ok(message()));
+
+        server
+            .bot()
+            .send_photo(
+                10,
+                crate::types::InputFile::memory("a.png", &b"\x89PNG"[..]),
+            )
+            .caption("a photo")
+            .send()
+            .await
+            .unwrap();
+
+        let params = &server.calls_to("sendPhoto")[0].params;
+        assert_eq!(params.get("chat_id"), Some(json!(10)));
+        assert_eq!(params.get("caption"), Some(json!("a photo")));
+        let photo = params.part("photo").unwrap();
+        assert_eq!(photo.file_name.as_deref(), Some("a.png"));
+        assert_eq!(photo.data, b"\x89PNG");
+    }
+
+    #[tokio::test]
+    async fn injected_updates_reach_handlers() {
+        let server = MockServer::start().await;
+        server.respond_always("sendMessage", MockResponse::ok(message()));
+        let (injector, listener) = super::super::update_injector();
+
+        injector.inject_message(message());
+        injector.inject_message(message());
+        drop(injector);
+
+        Dispatcher::new(server.bot())
+            .messages_handler(|rx: DispatcherHandlerRx<Message>| {
+                rx.for_each(|cx| async move {
+                    cx.answer("pong").send().await.unwrap();
+                })
+            })
+            .dispatch_with_listener(listener, IgnoringErrorHandlerSafe::new())
+            .await;
+
+        let calls = server.wait_for_calls(2, TIMEOUT).await;
+        assert!(calls.iter().all(|call| call.method == "sendMessage"));
+        assert_eq!(calls[1].params.get("text"), Some(json!("pong")));
+    }
+}
@@ -0,0 +1,52 @@
+//! Utilities for testing bots without talking to a real Bot API server.
+//!
+//! Requires the `testing` feature, which is usually enabled only for tests:
+//! ```toml
+//! [dev-dependencies]
+//! teloxide = { version = "0.2", features = ["testing"] }
+//! ```
+//!
+//! [`MockServer`] is an in-process fake Bot API server. Bots created by
+//! [`MockServer::bot`] send all their requests to it, so you can script the
+//! responses of the API (including errors such as
+//! [`RequestError::RetryAfter`]) and then inspect which methods were called
+//! and with which parameters:
+//!
+//! ```
+//! use teloxide::{
+//!     prelude::*,
+//!     testing::{MockResponse, MockServer},
+//! };
+//!
+//! # #[tokio::main]
+//! # async fn main() {
+//! let server = MockServer::start().await;
+//! server.respond_always("leaveChat", MockResponse::ok(true));
+//!
+//! let bot = server.bot();
+//! bot.leave_chat(123).send().await.unwrap();
+//!
+//! let calls = server.calls_to("leaveChat");
+//! assert_eq!(calls[0].params.get("chat_id"), Some(123.into()));
+//! # }
+//! ```
+//!
+//! To test without a server, bots can send their requests to an
+//! [`InMemoryTransport`] instead, which is scripted and inspected the same
+//! way.
+//!
+//! Updates can be fed into a [`Dispatcher`] using [`update_injector`].
+//!
+//! [`RequestError::RetryAfter`]: crate::RequestError::RetryAfter
+//! [`Dispatcher`]: crate::dispatching::Dispatcher
+
+pub use self::{
+    in_memory_transport::InMemoryTransport,
+    mock_server::{Call, MockResponse, MockServer, Params, Part},
+    update_injector::{update_injector, UpdateInjector},
+};
+
+mod in_memory_transport;
+mod mock_server;
+mod multipart;
+mod update_injector;
@@ -0,0 +1,103 @@
+use super::Part;
+
+/// Parses a `multipart/form-data` body, delimited by `boundary`.
+///
+/// This is a deliberately simple parser that understands exactly what
+/// `reqwest::multipart::Form` produces.
+pub(crate) fn parse(body: &[u8], boundary: &str) -> Vec<Part> {
+    let delimiter = format!("--{}", boundary).into_bytes();
+    let mut parts = Vec::new();
+
+    let mut rest = match find(body, &delimiter) {
+        Some(start) => &body[start + delimiter.len()..],
+        None => return parts,
+    };
+
+    // The closing delimiter is followed by `--`.
+    while !rest.starts_with(b"--") {
+        let end = match find(rest, &delimiter) {
+            Some(end) => end,
+            None => break,
+        };
+
+        if let Some(part) = parse_part(&rest[..end]) {
+            parts.push(part);
+        }
+        rest = &rest[end + delimiter.len()..];
+    }
+
+    parts
+}
+
+fn parse_part(raw: &[u8]) -> Option<Part> {
+    let raw = raw.strip_prefix(b"\r\n").unwrap_or(raw);
+    let raw = raw.strip_suffix(b"\r\n").unwrap_or(raw);
+
+    let headers_end = find(raw, b"\r\n\r\n")?;
+    let headers = String::from_utf8_lossy(&raw[..headers_end]);
+    let data = raw[headers_end + 4..].to_vec();
+
+    let disposition = headers.lines().find(|line| {
+        line.to_ascii_lowercase().starts_with("content-disposition:")
+    })?;
+
+    Some(Part {
+        name: disposition_param(disposition, "name")?,
+        file_name: disposition_param(disposition, "filename"),
+        data,
+    })
+}
+
+/// Extracts `key="value"` from a `Content-Disposition` header.
+fn disposition_param(header: &str, key: &str) -> Option<String> {
+    header.split(';').skip(1).find_map(|param| {
+        let mut kv = param.trim().splitn(2, '=');
+        match (kv.next(), kv.next()) {
+            (Some(k), Some(v)) if k == key => {
+                Some(v.trim_matches('"').to_owned())
+            }
+            _ => None,
+        }
+    })
+}
+
+fn find(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    haystack.windows(needle.len()).position(|window| window == needle)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_fields_and_files() {
+        let body = b"--XYZ\r\n\
+            Content-Disposition: form-data; name=\"chat_id\"\r\n\
+            \r\n\
+            123\r\n\
+            --XYZ\r\n\
+            Content-Disposition: form-data; name=\"photo\"; filename=\"a.png\"\r\n\
+            Content-Type: image/png\r\n\
+            \r\n\
+            \x89PNG\r\n\
+            --XYZ--\r\n";
+
+        let parts = parse(body, "XYZ");
+
+        assert_eq!(
+            parts,
+            vec![
+                Part {
+                    name: "chat_id".to_owned(),
+                    file_name: None,
+                    data: b"123".to_vec(),
+                },
+                Part {
+                    name: "photo".to_owned(),
+                    file_name: Some("a.png".to_owned()),
+                    data: b"\x89PNG".to_vec(),
+                },
+            ]
+        );
+    }
+}
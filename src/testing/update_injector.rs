@@ -0,0 +1,68 @@
+use std::{
+    convert::Infallible,
+    sync::atomic::{AtomicI64, Ordering},
+};
+
+use futures::StreamExt;
+use tokio::sync::mpsc;
+
+use crate::{
+    dispatching::update_listeners::UpdateListener,
+    types::{Message, Update, UpdateKind},
+};
+
+/// Feeds synthetic updates into an update listener.
+///
+/// See [`update_injector`].
+#[derive(Debug)]
+pub struct UpdateInjector {
+    tx: mpsc::UnboundedSender<Update>,
+    next_id: AtomicI64,
+}
+
+impl UpdateInjector {
+    /// Sends `update` to the corresponding update listener as is.
+    ///
+    /// # Panics
+    /// If the update listener has been dropped.
+    pub fn inject(&self, update: Update) {
+        self.tx.send(update).expect("The update listener has been dropped");
+    }
+
+    /// Wraps `kind` into an [`Update`] with the next sequential identifier
+    /// and sends it to the corresponding update listener.
+    ///
+    /// # Panics
+    /// If the update listener has been dropped.
+    ///
+    /// [`Update`]: crate::types::Update
+    pub fn inject_kind(&self, kind: UpdateKind) {
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+        self.inject(Update { id, kind });
+    }
+
+    /// Sends `message` as [`UpdateKind::Message`].
+    ///
+    /// # Panics
+    /// If the update listener has been dropped.
+    ///
+    /// [`UpdateKind::Message`]: crate::types::UpdateKind::Message
+    pub fn inject_message(&self, message: Message) {
+        self.inject_kind(UpdateKind::Message(message));
+    }
+}
+
+/// Returns an update listener yielding updates sent through the returned
+/// [`UpdateInjector`].
+///
+/// The listener ends once the injector is dropped, so
+/// [`Dispatcher::dispatch_with_listener`] returns after all the injected
+/// updates have been dispatched.
+///
+/// [`Dispatcher::dispatch_with_listener`]: crate::dispatching::Dispatcher::dispatch_with_listener
+pub fn update_injector() -> (UpdateInjector, impl UpdateListener<Infallible>) {
+    let (tx, rx) = mpsc::unbounded_channel();
+    let injector = UpdateInjector { tx, next_id: AtomicI64::new(1) };
+
+    (injector, rx.map(Ok))
+}
@@ -10,4 +10,9 @@ pub enum AllowedUpdate {
     InlineQuery,
     ChosenInlineResult,
     CallbackQuery,
+    ShippingQuery,
+    PreCheckoutQuery,
+    Poll,
+    PollAnswer,
+    MiniProgramData,
 }
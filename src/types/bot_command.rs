@@ -0,0 +1,86 @@
+use serde::{Deserialize, Serialize};
+
+use crate::types::ChatId;
+
+/// A command of a bot, shown in the command menu of clients.
+///
+/// [The official docs](https://core.telegram.org/bots/api#botcommand).
+#[derive(Clone, Debug, Eq, Hash, PartialEq, Serialize, Deserialize)]
+pub struct BotCommand {
+    /// Text of the command, 1-32 characters. Can contain only lowercase
+    /// English letters, digits and underscores.
+    pub command: String,
+
+    /// Description of the command, 3-256 characters.
+    pub description: String,
+}
+
+impl BotCommand {
+    pub fn new<C, D>(command: C, description: D) -> Self
+    where
+        C: Into<String>,
+        D: Into<String>,
+    {
+        Self { command: command.into(), description: description.into() }
+    }
+
+    pub fn command<S>(mut self, val: S) -> Self
+    where
+        S: Into<String>,
+    {
+        self.command = val.into();
+        self
+    }
+
+    pub fn description<S>(mut self, val: S) -> Self
+    where
+        S: Into<String>,
+    {
+        self.description = val.into();
+        self
+    }
+}
+
+/// Users and chats to which a list of [`BotCommand`]s applies.
+///
+/// A more specific scope takes precedence: commands of a channel override
+/// commands of its guild, which override the default ones.
+///
+/// [`BotCommand`]: crate::types::BotCommand
+#[derive(Clone, Debug, Eq, Hash, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum BotCommandScope {
+    /// All chats, unless a more specific scope applies.
+    Default,
+
+    /// All channels of a guild.
+    Guild { guild_id: i64 },
+
+    /// A single channel (or any other chat).
+    Channel { chat_id: ChatId },
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn serialize_scope() {
+        assert_eq!(
+            serde_json::to_string(&BotCommandScope::Default).unwrap(),
+            r#"{"type":"default"}"#
+        );
+        assert_eq!(
+            serde_json::to_string(&BotCommandScope::Guild { guild_id: 7 })
+                .unwrap(),
+            r#"{"type":"guild","guild_id":7}"#
+        );
+        assert_eq!(
+            serde_json::to_string(&BotCommandScope::Channel {
+                chat_id: ChatId::Id(42)
+            })
+            .unwrap(),
+            r#"{"type":"channel","chat_id":42}"#
+        );
+    }
+}
@@ -1,5 +1,5 @@
-use crate::types::{CallbackGame, LoginUrl};
-use serde::{Deserialize, Serialize};
+use crate::types::{CallbackGame, LoginUrl, MiniProgram};
+use serde::{de::Error, Deserialize, Deserializer, Serialize, Serializer};
 
 /// This object represents one button of an inline keyboard.
 ///
@@ -13,28 +13,26 @@ pub struct InlineKeyboardButton {
     pub kind: InlineKeyboardButtonKind,
 }
 
-#[derive(Clone, Debug, Eq, Hash, PartialEq, Serialize, Deserialize)]
-#[serde(rename_all = "snake_case")]
+// Serialize + Deserialize are implemented by hand
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
 pub enum InlineKeyboardButtonKind {
     /// HTTP or tg:// url to be opened when button is pressed.
-    #[serde(rename(serialize = "url"))]
     Url(String),
 
-    #[serde(rename = "app_id")]
-    MiniProgram(String),
+    /// A mini-program to be launched when button is pressed. Its fields are
+    /// sent alongside the button's text.
+    MiniProgram(MiniProgram),
 
     /// An HTTP URL used to automatically authorize the user. Can be used as a
     /// replacement for the [Telegram Login Widget]().
     ///
     /// [Telegram Login Widget]: https://core.telegram.org/widgets/login
-    #[serde(rename(serialize = "login_url"))]
     LoginUrl(LoginUrl),
 
     /// Data to be sent in a [`CallbackQuery`] to the bot when button is
     /// pressed, 1-64 bytes.
     ///
     /// [`CallbackQuery`]: crate::types::CallbackQuery
-    #[serde(rename(serialize = "callback_data"))]
     CallbackData(String),
 
     /// If set, pressing the button will prompt the user to select one of their
@@ -50,7 +48,6 @@ pub enum InlineKeyboardButtonKind {
     ///
     /// [inline mode]: https://core.telegram.org/bots/inline
     /// [switch_pm…]: https://core.telegram.org/bots/api#answerinlinequery
-    #[serde(rename(serialize = "switch_inline_query"))]
     SwitchInlineQuery(String),
 
     /// If set, pressing the button will insert the bot‘s username and the
@@ -60,7 +57,6 @@ pub enum InlineKeyboardButtonKind {
     ///
     ///This offers a quick way for the user to open your bot in inline mode in
     /// the same chat – good for selecting something from multiple options.
-    #[serde(rename(serialize = "switch_inline_query_current_chat"))]
     SwitchInlineQueryCurrentChat(String),
 
     /// Description of the game that will be launched when the user presses the
@@ -69,7 +65,6 @@ pub enum InlineKeyboardButtonKind {
     /// ## Note
     /// This type of button **must** always be the first button in the first
     /// row.
-    #[serde(rename(serialize = "callback_game"))]
     CallbackGame(CallbackGame),
 
     /// Specify True, to send a [Pay button].
@@ -79,7 +74,6 @@ pub enum InlineKeyboardButtonKind {
     /// row.
     ///
     /// [Pay button]: https://core.telegram.org/bots/api#payments
-    #[serde(rename(serialize = "pay"))]
     Pay(bool),
 }
 
@@ -99,9 +93,21 @@ impl InlineKeyboardButton {
         InlineKeyboardButton { text, kind: InlineKeyboardButtonKind::Url(url) }
     }
 
-
-    pub fn mini_program(text: String, app_id: String) -> InlineKeyboardButton {
-        InlineKeyboardButton { text, kind: InlineKeyboardButtonKind::MiniProgram(app_id) }
+    /// Creates a button launching a mini-program, given either by its
+    /// `app_id` or as a whole [`MiniProgram`].
+    ///
+    /// [`MiniProgram`]: crate::types::MiniProgram
+    pub fn mini_program<P>(
+        text: String,
+        mini_program: P,
+    ) -> InlineKeyboardButton
+    where
+        P: Into<MiniProgram>,
+    {
+        InlineKeyboardButton {
+            text,
+            kind: InlineKeyboardButtonKind::MiniProgram(mini_program.into()),
+        }
     }
 
     pub fn callback(
@@ -138,3 +144,143 @@ impl InlineKeyboardButton {
         }
     }
 }
+
+/// Helper struct for (de)serializing [`InlineKeyboardButtonKind`]
+///
+/// [`InlineKeyboardButtonKind`]: InlineKeyboardButtonKind
+#[serde_with_macros::skip_serializing_none]
+#[derive(Default, Serialize, Deserialize)]
+struct RawKind {
+    url: Option<String>,
+    #[serde(flatten)]
+    mini_program: Option<MiniProgram>,
+    login_url: Option<LoginUrl>,
+    callback_data: Option<String>,
+    switch_inline_query: Option<String>,
+    switch_inline_query_current_chat: Option<String>,
+    callback_game: Option<CallbackGame>,
+    pay: Option<bool>,
+}
+
+impl<'de> Deserialize<'de> for InlineKeyboardButtonKind {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let raw = RawKind::deserialize(deserializer)?;
+        match raw {
+            RawKind { url: Some(url), .. } => Ok(Self::Url(url)),
+            RawKind { mini_program: Some(program), .. } => {
+                Ok(Self::MiniProgram(program))
+            }
+            RawKind { login_url: Some(login_url), .. } => {
+                Ok(Self::LoginUrl(login_url))
+            }
+            RawKind { callback_data: Some(data), .. } => {
+                Ok(Self::CallbackData(data))
+            }
+            RawKind { switch_inline_query: Some(query), .. } => {
+                Ok(Self::SwitchInlineQuery(query))
+            }
+            RawKind {
+                switch_inline_query_current_chat: Some(query), ..
+            } => Ok(Self::SwitchInlineQueryCurrentChat(query)),
+            RawKind { callback_game: Some(game), .. } => {
+                Ok(Self::CallbackGame(game))
+            }
+            RawKind { pay: Some(pay), .. } => Ok(Self::Pay(pay)),
+            _ => Err(D::Error::custom(
+                "An inline keyboard button requires one of `url`, `app_id`, \
+                 `login_url`, `callback_data`, `switch_inline_query`, \
+                 `switch_inline_query_current_chat`, `callback_game` and \
+                 `pay` fields",
+            )),
+        }
+    }
+}
+
+impl Serialize for InlineKeyboardButtonKind {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let raw = match self {
+            Self::Url(url) => {
+                RawKind { url: Some(url.clone()), ..<_>::default() }
+            }
+            Self::MiniProgram(program) => RawKind {
+                mini_program: Some(program.clone()),
+                ..<_>::default()
+            },
+            Self::LoginUrl(login_url) => {
+                RawKind { login_url: Some(login_url.clone()), ..<_>::default() }
+            }
+            Self::CallbackData(data) => {
+                RawKind { callback_data: Some(data.clone()), ..<_>::default() }
+            }
+            Self::SwitchInlineQuery(query) => RawKind {
+                switch_inline_query: Some(query.clone()),
+                ..<_>::default()
+            },
+            Self::SwitchInlineQueryCurrentChat(query) => RawKind {
+                switch_inline_query_current_chat: Some(query.clone()),
+                ..<_>::default()
+            },
+            Self::CallbackGame(game) => {
+                RawKind { callback_game: Some(*game), ..<_>::default() }
+            }
+            Self::Pay(pay) => RawKind { pay: Some(*pay), ..<_>::default() },
+        };
+        raw.serialize(serializer)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::MiniProgramDisplayMode;
+
+    #[test]
+    fn serialize_callback() {
+        let button = InlineKeyboardButton::callback(
+            "Text".to_owned(),
+            "data".to_owned(),
+        );
+        let expected = r#"{"text":"Text","callback_data":"data"}"#;
+        let actual = serde_json::to_string(&button).unwrap();
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn serialize_mini_program() {
+        let button = InlineKeyboardButton::mini_program(
+            "Open".to_owned(),
+            MiniProgram::new("123")
+                .path("pages/index")
+                .display_mode(MiniProgramDisplayMode::FullScreen),
+        );
+        let expected = r#"{"text":"Open","app_id":"123","path":"pages/index","display_mode":"full_screen"}"#;
+        let actual = serde_json::to_string(&button).unwrap();
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn deserialize_mini_program_app_id_only() {
+        let json = r#"{"text":"Open","app_id":"123"}"#;
+        let expected =
+            InlineKeyboardButton::mini_program("Open".to_owned(), "123");
+        let actual: InlineKeyboardButton = serde_json::from_str(json).unwrap();
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn deserialize_url() {
+        let json = r#"{"text":"Text","url":"http://url.com"}"#;
+        let expected = InlineKeyboardButton::url(
+            "Text".to_owned(),
+            "http://url.com".to_owned(),
+        );
+        let actual: InlineKeyboardButton = serde_json::from_str(json).unwrap();
+        assert_eq!(expected, actual);
+    }
+}
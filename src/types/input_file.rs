@@ -1,6 +1,14 @@
 use serde::{Deserialize, Serialize};
+use tokio::io::AsyncRead;
 
-use std::{borrow::Cow, path::PathBuf};
+use std::{
+    borrow::Cow,
+    fmt::{self, Debug},
+    hash::{Hash, Hasher},
+    path::PathBuf,
+    pin::Pin,
+    sync::{Arc, Mutex},
+};
 
 /// This object represents the contents of a file to be uploaded.
 ///
@@ -8,11 +16,67 @@ use std::{borrow::Cow, path::PathBuf};
 #[derive(Clone, Debug, Eq, Hash, PartialEq, Deserialize)]
 pub enum InputFile {
     File(PathBuf),
-    Memory { file_name: String, data: Cow<'static, [u8]> },
+    Memory {
+        file_name: String,
+        data: Cow<'static, [u8]>,
+    },
+    /// The contents are read from an asynchronous reader while uploading.
+    #[serde(skip_deserializing)]
+    Reader {
+        file_name: String,
+        reader: FileReader,
+    },
     Url(String),
     FileId(String),
 }
 
+/// An asynchronous reader of the contents of [`InputFile::Reader`].
+///
+/// A reader can be uploaded only once: clones share the same reader, and
+/// uploading it again fails with an I/O error.
+///
+/// [`InputFile::Reader`]: crate::types::InputFile::Reader
+#[derive(Clone)]
+pub struct FileReader(Arc<Mutex<Option<BoxedReader>>>);
+
+pub(crate) type BoxedReader = Pin<Box<dyn AsyncRead + Send + Sync>>;
+
+impl FileReader {
+    /// Wraps `reader`.
+    pub fn new<R>(reader: R) -> Self
+    where
+        R: AsyncRead + Send + Sync + 'static,
+    {
+        Self(Arc::new(Mutex::new(Some(Box::pin(reader)))))
+    }
+
+    /// Takes the reader, if it hasn't been taken yet.
+    pub(crate) fn take(&self) -> Option<BoxedReader> {
+        self.0.lock().unwrap_or_else(|err| err.into_inner()).take()
+    }
+}
+
+impl Debug for FileReader {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("FileReader")
+    }
+}
+
+/// Readers are equal if they share the same reader.
+impl PartialEq for FileReader {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+impl Eq for FileReader {}
+
+impl Hash for FileReader {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        (Arc::as_ptr(&self.0) as *const ()).hash(state)
+    }
+}
+
 impl InputFile {
     pub fn file<P>(path: P) -> Self
     where
@@ -29,6 +93,17 @@ impl InputFile {
         Self::Memory { file_name: file_name.into(), data: data.into() }
     }
 
+    pub fn reader<S, R>(file_name: S, reader: R) -> Self
+    where
+        S: Into<String>,
+        R: AsyncRead + Send + Sync + 'static,
+    {
+        Self::Reader {
+            file_name: file_name.into(),
+            reader: FileReader::new(reader),
+        }
+    }
+
     pub fn url<T>(url: T) -> Self
     where
         T: Into<String>,
@@ -91,13 +166,15 @@ impl Serialize for InputFile {
                     ),
                 )
             }
-            InputFile::Memory { data, .. } => {
+            InputFile::Memory { file_name, .. } => {
+                // NOTE: file should be actually attached with
+                // multipart/form-data
+                serializer.serialize_str(&format!("attach://{}", file_name))
+            }
+            InputFile::Reader { file_name, .. } => {
                 // NOTE: file should be actually attached with
                 // multipart/form-data
-                serializer.serialize_str(&format!(
-                    "attach://{}",
-                    String::from_utf8_lossy(data)
-                ))
+                serializer.serialize_str(&format!("attach://{}", file_name))
             }
             InputFile::Url(url) => serializer.serialize_str(url),
             InputFile::FileId(id) => serializer.serialize_str(id),
@@ -193,6 +193,24 @@ impl InputMedia {
     }
 }
 
+impl InputMedia {
+    /// Returns the file and the thumbnail, if any.
+    pub(crate) fn files_mut(&mut self) -> Vec<&mut InputFile> {
+        let (media, thumb) = match self {
+            InputMedia::Photo(InputMediaPhoto { media, .. }) => (media, None),
+            InputMedia::Video(InputMediaVideo { media, thumb, .. })
+            | InputMedia::Animation(InputMediaAnimation {
+                media, thumb, ..
+            })
+            | InputMedia::Audio(InputMediaAudio { media, thumb, .. })
+            | InputMedia::Document(InputMediaDocument {
+                media, thumb, ..
+            }) => (media, thumb.as_mut()),
+        };
+        std::iter::once(media).chain(thumb).collect()
+    }
+}
+
 impl From<InputMedia> for InputFile {
     fn from(media: InputMedia) -> InputFile {
         match media {
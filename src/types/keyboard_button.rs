@@ -1,6 +1,6 @@
 use serde::{de::Error, Deserialize, Deserializer, Serialize, Serializer};
 
-use crate::types::{KeyboardButtonPollType, True};
+use crate::types::{KeyboardButtonPollType, MiniProgram, True};
 
 /// This object represents one button of the reply keyboard.
 ///
@@ -20,6 +20,11 @@ pub struct KeyboardButton {
     ///   when the button is pressed. Available in private chats only
     /// - If `Some(Location)`, the user's current location will be sent when
     ///   the button is pressed. Available in private chats only
+    /// - If `Some(MiniProgram(_))`, the mini-program will be launched when the
+    ///   button is pressed. The data it returns comes as
+    ///   [`UpdateKind::MiniProgramData`]
+    ///
+    /// [`UpdateKind::MiniProgramData`]: crate::types::UpdateKind::MiniProgramData
     #[serde(flatten)]
     pub request: Option<ButtonRequest>,
 }
@@ -49,6 +54,7 @@ pub enum ButtonRequest {
     Location,
     Contact,
     KeyboardButtonPollType(KeyboardButtonPollType),
+    MiniProgram(MiniProgram),
 }
 
 /// Helper struct for (de)serializing [`ButtonRequest`](ButtonRequest)
@@ -70,6 +76,14 @@ struct RawRequest {
     /// chats only.
     #[serde(rename = "request_poll")]
     poll: Option<KeyboardButtonPollType>,
+
+    /// If specified, the mini-program will be launched when the button is
+    /// pressed. Its fields are inlined into the button, like in
+    /// [`InlineKeyboardButton`].
+    ///
+    /// [`InlineKeyboardButton`]: crate::types::InlineKeyboardButton
+    #[serde(flatten)]
+    mini_program: Option<MiniProgram>,
 }
 
 impl<'de> Deserialize<'de> for ButtonRequest {
@@ -83,6 +97,7 @@ impl<'de> Deserialize<'de> for ButtonRequest {
                 contact: Some(_),
                 location: Some(_),
                 poll: Some(_),
+                ..
             } => Err(D::Error::custom(
                 "`request_contact` and `request_location` fields are mutually \
                  exclusive, but both were provided",
@@ -92,9 +107,12 @@ impl<'de> Deserialize<'de> for ButtonRequest {
             RawRequest { poll: Some(poll_type), .. } => {
                 Ok(Self::KeyboardButtonPollType(poll_type))
             }
+            RawRequest { mini_program: Some(program), .. } => {
+                Ok(Self::MiniProgram(program))
+            }
             _ => Err(D::Error::custom(
-                "Either one of `request_contact` and `request_location` \
-                 fields is required",
+                "Either one of `request_contact`, `request_location`, \
+                 `request_poll` and `app_id` fields is required",
             )),
         }
     }
@@ -106,18 +124,32 @@ impl Serialize for ButtonRequest {
         S: Serializer,
     {
         match self {
-            Self::Contact => {
-                RawRequest { contact: Some(True), location: None, poll: None }
-                    .serialize(serializer)
+            Self::Contact => RawRequest {
+                contact: Some(True),
+                location: None,
+                poll: None,
+                mini_program: None,
             }
-            Self::Location => {
-                RawRequest { contact: None, location: Some(True), poll: None }
-                    .serialize(serializer)
+            .serialize(serializer),
+            Self::Location => RawRequest {
+                contact: None,
+                location: Some(True),
+                poll: None,
+                mini_program: None,
             }
+            .serialize(serializer),
             Self::KeyboardButtonPollType(poll_type) => RawRequest {
                 contact: None,
                 location: None,
                 poll: Some(poll_type.clone()),
+                mini_program: None,
+            }
+            .serialize(serializer),
+            Self::MiniProgram(program) => RawRequest {
+                contact: None,
+                location: None,
+                poll: None,
+                mini_program: Some(program.clone()),
             }
             .serialize(serializer),
         }
@@ -147,6 +179,25 @@ mod tests {
         assert_eq!(expected, actual);
     }
 
+    #[test]
+    fn serialize_mini_program() {
+        let button = KeyboardButton::new("Open")
+            .request(ButtonRequest::MiniProgram(MiniProgram::new("123")));
+        let expected = r#"{"text":"Open","app_id":"123"}"#;
+        let actual = serde_json::to_string(&button).unwrap();
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn deserialize_mini_program() {
+        let json = r#"{"text":"Open","app_id":"123","path":"p"}"#;
+        let expected = KeyboardButton::new("Open").request(
+            ButtonRequest::MiniProgram(MiniProgram::new("123").path("p")),
+        );
+        let actual: KeyboardButton = serde_json::from_str(json).unwrap();
+        assert_eq!(expected, actual);
+    }
+
     #[test]
     fn deserialize_no_request() {
         let json = r#"{"text":""}"#;
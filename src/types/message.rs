@@ -2,8 +2,10 @@
 
 use serde::{Deserialize, Serialize};
 
+use crate::utils::fanbook::{self, Mention};
+
 use crate::types::{chat::{ChatKind, PublicChatKind}, Animation, Audio, Chat, ChatPublic, Contact, Document, Game, InlineKeyboardMarkup, Invoice, Location,
-                   MessageEntity, PassportData, PhotoSize, Poll, PublicChatChannel, PublicChatSupergroup, Sticker, SuccessfulPayment, True, User, Venue,
+                   MessageEntity, MessageEntityKind, PassportData, PhotoSize, Poll, PublicChatChannel, PublicChatSupergroup, Sticker, SuccessfulPayment, True, User, Venue,
                    Video, VideoNote, Voice, ChatPrivate, TopicShare, CircleShareEntity, RichText, CircleLike, CircleComment, CirclePost};
 
 /// This object represents a message.
@@ -385,7 +387,7 @@ pub struct MediaAudio {
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct MediaContact {
     /// Message is a shared contact, information about the contact.
-    contact: Contact,
+    pub contact: Contact,
 }
 
 #[serde_with_macros::skip_serializing_none]
@@ -1075,13 +1077,101 @@ impl Message {
             _ => None,
         }
     }
+
+    /// Returns the entities of the text and the caption of the message
+    /// along with their text.
+    ///
+    /// Entities which don't fit the text are skipped.
+    pub fn parse_entities(
+        &self,
+    ) -> impl Iterator<Item = (&MessageEntity, &str)> {
+        let text = self.text().zip(self.entities());
+        let caption = self.caption().zip(self.caption_entities());
+
+        text.into_iter().chain(caption).flat_map(|(text, entities)| {
+            entities
+                .iter()
+                .filter_map(move |entity| Some((entity, entity.text_in(text)?)))
+        })
+    }
+
+    /// Returns the users mentioned by [text mentions], i.e. users without
+    /// usernames.
+    ///
+    /// [text mentions]: crate::types::MessageEntityKind::TextMention
+    pub fn mentioned_users(&self) -> Vec<&User> {
+        self.parse_entities()
+            .filter_map(|(entity, _)| match &entity.kind {
+                MessageEntityKind::TextMention { user } => Some(user),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Returns everything mentioned in the message, both by entities and by
+    /// the [Fanbook markup] of its text or caption.
+    ///
+    /// The mentions are in the order they appear in the text or the caption.
+    ///
+    /// [Fanbook markup]: crate::utils::fanbook
+    pub fn mentions(&self) -> Vec<Mention> {
+        let entities = self.parse_entities().filter_map(|(entity, text)| {
+            let mention = match &entity.kind {
+                MessageEntityKind::TextMention { user } => {
+                    Mention::User(user.id)
+                }
+                MessageEntityKind::Mention => {
+                    let username = text.trim_start_matches('@');
+                    Mention::Username(username.to_owned())
+                }
+                _ => return None,
+            };
+            Some((entity.offset, mention))
+        });
+        // Entity offsets are counted in UTF-16 code units, so the offsets of
+        // the markup are converted too.
+        let markup = self.text().or_else(|| self.caption()).map(|text| {
+            fanbook::mentions_with_offsets(text).into_iter().map(
+                move |(offset, mention)| {
+                    (text[..offset].encode_utf16().count(), mention)
+                },
+            )
+        });
+
+        let mut mentions: Vec<_> =
+            entities.chain(markup.into_iter().flatten()).collect();
+        mentions.sort_by_key(|&(offset, _)| offset);
+        mentions.into_iter().map(|(_, mention)| mention).collect()
+    }
+
+    /// Returns the hashtags of the message, including `#`.
+    pub fn hashtags(&self) -> Vec<&str> {
+        self.parse_entities()
+            .filter(|(entity, _)| entity.kind == MessageEntityKind::Hashtag)
+            .map(|(_, text)| text)
+            .collect()
+    }
+
+    /// Returns the URLs of the message, both written as is and hidden
+    /// behind [text links].
+    ///
+    /// [text links]: crate::types::MessageEntityKind::TextLink
+    pub fn urls(&self) -> Vec<&str> {
+        self.parse_entities()
+            .filter_map(|(entity, text)| match &entity.kind {
+                MessageEntityKind::Url => Some(text),
+                MessageEntityKind::TextLink { url } => Some(url.as_str()),
+                _ => None,
+            })
+            .collect()
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use serde_json::from_str;
 
-    use crate::types::*;
+    use crate::{types::*, utils::fanbook::Mention};
 
     #[test]
     fn de_media_forwarded() {
@@ -1281,4 +1371,93 @@ mod tests {
         let message = from_str::<Message>(json);
         assert!(message.is_ok());
     }
+
+    #[test]
+    fn parse_entities() {
+        let json = r#"{
+          "message_id": 199792,
+          "chat": {
+           "id": 250918540,
+           "first_name": "Андрей",
+           "type": "private"
+          },
+          "date": 1568290622,
+          "text": "😂 Привет, Ann! #тест https://ya.ru link",
+          "entities": [
+           {"type": "text_mention", "offset": 11, "length": 3,
+            "user": {"id": 42, "is_bot": false, "first_name": "Ann"}},
+           {"type": "hashtag", "offset": 16, "length": 5},
+           {"type": "url", "offset": 22, "length": 13},
+           {"type": "text_link", "offset": 36, "length": 4,
+            "url": "https://example.com/"},
+           {"type": "bold", "offset": 40, "length": 10}
+          ]
+         }"#;
+        let message = from_str::<Message>(json).unwrap();
+
+        let texts: Vec<_> =
+            message.parse_entities().map(|(_, text)| text).collect();
+        assert_eq!(texts, ["Ann", "#тест", "https://ya.ru", "link"]);
+        assert_eq!(
+            message.mentioned_users().iter().map(|u| u.id).collect::<Vec<_>>(),
+            [42]
+        );
+        assert_eq!(message.hashtags(), ["#тест"]);
+        assert_eq!(message.mentions(), [Mention::User(42)]);
+        assert_eq!(message.urls(), ["https://ya.ru", "https://example.com/"]);
+        assert_eq!(
+            message.entities().unwrap()[1].text_from(&message).as_deref(),
+            Some("#тест")
+        );
+    }
+
+    #[test]
+    fn parse_caption_entities() {
+        let json = r#"{
+          "message_id": 199793,
+          "chat": {
+           "id": 250918540,
+           "first_name": "Андрей",
+           "type": "private"
+          },
+          "date": 1568290622,
+          "photo": [
+           {
+            "file_id": "AgADAgAD36sxG-PX0UvQSXIn9rccdw-ACA4ABAEAAwIAA20AAybcBAABFgQ",
+            "file_unique_id":"",
+            "file_size": 18188,
+            "width": 320,
+            "height": 239
+           }
+          ],
+          "caption": "🌅 #sunset",
+          "caption_entities": [{"type": "hashtag", "offset": 3, "length": 7}]
+         }"#;
+        let message = from_str::<Message>(json).unwrap();
+
+        assert_eq!(message.hashtags(), ["#sunset"]);
+    }
+
+    #[test]
+    fn mentions() {
+        let json = r#"{
+          "message_id": 199794,
+          "chat": {
+           "id": 250918540,
+           "first_name": "Андрей",
+           "type": "private"
+          },
+          "date": 1568290622,
+          "text": "Привет, привет! ${@!42} @aka_dude ${@&7} `${#1}` ${#8}",
+          "entities": [{"type": "mention", "offset": 24, "length": 9}]
+         }"#;
+        let message = from_str::<Message>(json).unwrap();
+
+        assert_eq!(message.mentions(), [
+            Mention::User(42),
+            Mention::Username("aka_dude".to_owned()),
+            Mention::Role(7),
+            Mention::Channel(8),
+        ]);
+    }
 }
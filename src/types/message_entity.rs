@@ -1,3 +1,5 @@
+use std::ops::Range;
+
 use serde::{Deserialize, Serialize};
 
 use crate::types::{Message, User};
@@ -41,9 +43,45 @@ pub enum MessageEntityKind {
 }
 
 impl MessageEntity {
+    /// Returns the text of the entity in the text or the caption of the
+    /// `message`.
     pub fn text_from(&self, message: &Message) -> Option<String> {
-        let text = message.text();
-        Some(String::from(&text?[self.offset..self.offset + self.length]))
+        let text = message.text().or_else(|| message.caption())?;
+        self.text_in(text).map(ToOwned::to_owned)
+    }
+
+    /// Returns the text of the entity in `text`.
+    ///
+    /// Returns `None` if the entity doesn't fit `text`.
+    pub fn text_in<'a>(&self, text: &'a str) -> Option<&'a str> {
+        self.range_in(text).map(|range| &text[range])
+    }
+
+    /// Converts the offset and the length of the entity, which are counted
+    /// in UTF-16 code units, to a range of bytes in `text`.
+    ///
+    /// Returns `None` if the entity doesn't fit `text` or its bounds split a
+    /// character.
+    pub fn range_in(&self, text: &str) -> Option<Range<usize>> {
+        let start = byte_offset(text, self.offset)?;
+        let end = start + byte_offset(&text[start..], self.length)?;
+        Some(start..end)
+    }
+}
+
+/// Converts an offset in UTF-16 code units into an offset in bytes.
+fn byte_offset(text: &str, utf16_offset: usize) -> Option<usize> {
+    let mut utf16 = 0;
+    for (bytes, c) in text.char_indices() {
+        if utf16 >= utf16_offset {
+            return if utf16 == utf16_offset { Some(bytes) } else { None };
+        }
+        utf16 += c.len_utf16();
+    }
+    if utf16 == utf16_offset {
+        Some(text.len())
+    } else {
+        None
     }
 }
 
@@ -100,6 +138,26 @@ mod tests {
         assert_eq!(actual, expected);
     }
 
+    #[test]
+    fn text_in_utf16() {
+        let text = "👋 Привет, 世界! #rust";
+        let entity = |offset, length| MessageEntity {
+            kind: MessageEntityKind::Hashtag,
+            offset,
+            length,
+        };
+
+        assert_eq!(entity(0, 2).text_in(text), Some("👋"));
+        assert_eq!(entity(3, 6).text_in(text), Some("Привет"));
+        assert_eq!(entity(11, 2).text_in(text), Some("世界"));
+        assert_eq!(entity(15, 5).text_in(text), Some("#rust"));
+        assert_eq!(entity(15, 5).range_in(text), Some(27..32));
+        // Splits the emoji.
+        assert_eq!(entity(1, 2).text_in(text), None);
+        // Out of the text.
+        assert_eq!(entity(15, 6).text_in(text), None);
+    }
+
     fn message() -> Message {
         Message {
             id: 0,
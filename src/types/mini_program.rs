@@ -0,0 +1,133 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+/// A mini-program to be launched when a button is pressed.
+///
+/// # Examples
+/// ```
+/// use teloxide::types::{MiniProgram, MiniProgramDisplayMode};
+///
+/// let program = MiniProgram::new("123456")
+///     .path("pages/order/index")
+///     .query_param("order_id", "42")
+///     .display_mode(MiniProgramDisplayMode::HalfScreen);
+/// ```
+#[serde_with_macros::skip_serializing_none]
+#[derive(Clone, Debug, Eq, Hash, PartialEq, Serialize, Deserialize)]
+pub struct MiniProgram {
+    /// Identifier of the mini-program.
+    pub app_id: String,
+
+    /// A page of the mini-program to be opened instead of the default one.
+    pub path: Option<String>,
+
+    /// Query parameters passed to the opened page.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub query: BTreeMap<String, String>,
+
+    /// How the mini-program is displayed. Chosen by the client if not
+    /// specified.
+    pub display_mode: Option<MiniProgramDisplayMode>,
+}
+
+impl MiniProgram {
+    /// Creates a mini-program with the identifier `app_id`, which opens its
+    /// default page.
+    pub fn new<S>(app_id: S) -> Self
+    where
+        S: Into<String>,
+    {
+        Self {
+            app_id: app_id.into(),
+            path: None,
+            query: BTreeMap::new(),
+            display_mode: None,
+        }
+    }
+
+    /// Sets the identifier of the mini-program.
+    pub fn app_id<S>(mut self, val: S) -> Self
+    where
+        S: Into<String>,
+    {
+        self.app_id = val.into();
+        self
+    }
+
+    /// Opens the page `val` instead of the default one.
+    pub fn path<S>(mut self, val: S) -> Self
+    where
+        S: Into<String>,
+    {
+        self.path = Some(val.into());
+        self
+    }
+
+    /// Adds the query parameter `key`, replacing the previous value if any.
+    pub fn query_param<K, V>(mut self, key: K, value: V) -> Self
+    where
+        K: Into<String>,
+        V: Into<String>,
+    {
+        self.query.insert(key.into(), value.into());
+        self
+    }
+
+    /// Sets how the mini-program is displayed.
+    pub fn display_mode(mut self, val: MiniProgramDisplayMode) -> Self {
+        self.display_mode = Some(val);
+        self
+    }
+}
+
+impl From<String> for MiniProgram {
+    fn from(app_id: String) -> Self {
+        Self::new(app_id)
+    }
+}
+
+impl From<&str> for MiniProgram {
+    fn from(app_id: &str) -> Self {
+        Self::new(app_id)
+    }
+}
+
+/// How a [`MiniProgram`] is displayed.
+///
+/// [`MiniProgram`]: crate::types::MiniProgram
+#[derive(Copy, Clone, Debug, Eq, Hash, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MiniProgramDisplayMode {
+    /// The mini-program covers the whole screen.
+    FullScreen,
+
+    /// The mini-program covers the lower half of the screen, leaving the chat
+    /// visible.
+    HalfScreen,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn serialize_app_id_only() {
+        let expected = r#"{"app_id":"123"}"#;
+        let actual = serde_json::to_string(&MiniProgram::new("123")).unwrap();
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn serialize_full() {
+        let program = MiniProgram::new("123")
+            .path("pages/index")
+            .query_param("b", "2")
+            .query_param("a", "1")
+            .display_mode(MiniProgramDisplayMode::HalfScreen);
+
+        let expected = r#"{"app_id":"123","path":"pages/index","query":{"a":"1","b":"2"},"display_mode":"half_screen"}"#;
+        let actual = serde_json::to_string(&program).unwrap();
+        assert_eq!(expected, actual);
+    }
+}
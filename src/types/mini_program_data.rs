@@ -0,0 +1,53 @@
+use serde::{Deserialize, Serialize};
+
+use crate::types::{Chat, Message, User};
+
+/// Data returned to the bot by a [`MiniProgram`] launched from one of its
+/// buttons.
+///
+/// [`MiniProgram`]: crate::types::MiniProgram
+#[serde_with_macros::skip_serializing_none]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct MiniProgramData {
+    /// Identifier of the mini-program which returned the data.
+    pub app_id: String,
+
+    /// A user who launched the mini-program.
+    pub from: User,
+
+    /// A chat the mini-program was launched from, if any.
+    pub chat: Option<Chat>,
+
+    /// A message with the inline button which launched the mini-program. Not
+    /// present if it was launched from a reply keyboard.
+    pub message: Option<Message>,
+
+    /// Text of the button which launched the mini-program.
+    pub button_text: Option<String>,
+
+    /// The data. Its format is up to the mini-program.
+    pub data: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn deserialize() {
+        let json = r#"{
+            "app_id": "123",
+            "from": {"id": 1, "is_bot": false, "first_name": "Ann"},
+            "button_text": "Order",
+            "data": "{\"order_id\":42}"
+        }"#;
+
+        let data: MiniProgramData = serde_json::from_str(json).unwrap();
+
+        assert_eq!(data.app_id, "123");
+        assert_eq!(data.from.id, 1);
+        assert_eq!(data.chat, None);
+        assert_eq!(data.button_text.as_deref(), Some("Order"));
+        assert_eq!(data.data, r#"{"order_id":42}"#);
+    }
+}
@@ -3,6 +3,7 @@
 pub use allowed_update::*;
 pub use animation::*;
 pub use audio::*;
+pub use bot_command::*;
 pub use callback_game::*;
 pub use callback_query::*;
 pub use chat::*;
@@ -81,6 +82,7 @@ pub use successful_payment::*;
 pub use unit_false::*;
 pub use unit_true::*;
 pub use update::*;
+pub use update_builder::*;
 pub use user::*;
 pub use user_profile_photos::*;
 pub use venue::*;
@@ -92,6 +94,7 @@ pub use webhook_info::*;
 mod allowed_update;
 mod animation;
 mod audio;
+mod bot_command;
 mod callback_game;
 mod callback_query;
 mod chat;
@@ -143,6 +146,7 @@ mod successful_payment;
 mod unit_false;
 mod unit_true;
 mod update;
+mod update_builder;
 mod user;
 mod user_profile_photos;
 mod venue;
@@ -205,4 +209,10 @@ mod guild_credit;
 pub use guild_credit::*;
 
 mod guild_role;
-pub use guild_role::*;
\ No newline at end of file
+pub use guild_role::*;
+
+mod mini_program;
+pub use mini_program::*;
+
+mod mini_program_data;
+pub use mini_program_data::*;
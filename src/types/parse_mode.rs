@@ -97,6 +97,11 @@ use serde::{Deserialize, Serialize};
 ///   entity.
 /// - Programming language can't be specified for standalone `code` tags.
 ///
+/// ## Fanbook style
+/// To use this mode, pass [`Fanbook`] in the `parse_mode` field. See
+/// [`utils::fanbook`] for the syntax and the utils to build and parse
+/// messages in this style.
+///
 /// ## Markdown style
 /// This is a legacy mode, retained for backward compatibility. To use this
 /// mode, pass [`Markdown`] in the `parse_mode` field.
@@ -125,6 +130,8 @@ use serde::{Deserialize, Serialize};
 /// [`MarkdownV2`]: ParseMode::MarkdownV2
 /// [`HTML`]: ParseMode::HTML
 /// [`Markdown`]: ParseMode::Markdown
+/// [`Fanbook`]: ParseMode::Fanbook
+/// [`utils::fanbook`]: crate::utils::fanbook
 #[derive(Copy, Clone, Debug, Eq, Hash, PartialEq, Serialize, Deserialize)]
 pub enum ParseMode {
     MarkdownV2,
@@ -144,6 +151,7 @@ impl TryFrom<&str> for ParseMode {
             "html" => Ok(ParseMode::HTML),
             "markdown" => Ok(ParseMode::Markdown),
             "markdownv2" => Ok(ParseMode::MarkdownV2),
+            "fanbook" => Ok(ParseMode::Fanbook),
             _ => Err(()),
         }
     }
@@ -186,4 +194,14 @@ mod tests {
 
         assert_eq!(expected_json, actual_json)
     }
+
+    #[test]
+    fn fanbook_from_str() {
+        assert_eq!("fanbook".parse(), Ok(ParseMode::Fanbook));
+        assert_eq!(ParseMode::try_from("Fanbook"), Ok(ParseMode::Fanbook));
+        assert_eq!(
+            serde_json::to_string(&ParseMode::Fanbook).unwrap(),
+            r#""Fanbook""#
+        );
+    }
 }
@@ -2,12 +2,10 @@
 
 use serde::{Deserialize, Serialize};
 
-use crate::types::{CallbackQuery, Chat, ChosenInlineResult, InlineQuery, Message, Poll, PollAnswer, PreCheckoutQuery, ShippingQuery, User, ChatKind,
-                   ChatPublic, PublicChatKind, PublicChatChannel, MessageKind, MessageCommon, ForwardKind, ForwardOrigin, MediaKind, MediaText,
-                   MessageEntity, PublicChatGroup, MediaPhoto, PhotoSize, ChatPrivate, VideoNote, MediaVideoNote, Voice, MediaVoice, MessageNewChatMembers,
-                   MessageChatMembersOnline, MessageChatMembersOffline, MessageLeftChatMember, MediaSticker, Sticker, MessagePinned, MessageReaction,
-                   Reaction, MediaTopicSahre, TopicShare, MediaCircleShareEntity, CircleShareEntity, CirclePost, CircleLike, MediaCircleLike, MediaRichText,
-                   RichText, MediaCircleComment, MediaCirclePost, CircleComment};
+use crate::types::{
+    CallbackQuery, Chat, ChosenInlineResult, InlineQuery, Message,
+    MiniProgramData, Poll, PollAnswer, PreCheckoutQuery, ShippingQuery, User,
+};
 use serde_json::Value;
 
 /// This [object] represents an incoming update.
@@ -95,6 +93,12 @@ pub enum UpdateKind {
     /// A user changed their answer in a non-anonymous poll. Bots receive new
     /// votes only in polls that were sent by the bot itself.
     PollAnswer(PollAnswer),
+
+    /// A user returned data from a [`MiniProgram`] launched from one of the
+    /// bot's buttons.
+    ///
+    /// [`MiniProgram`]: crate::types::MiniProgram
+    MiniProgramData(MiniProgramData),
 }
 
 impl Update {
@@ -108,6 +112,7 @@ impl Update {
             UpdateKind::ShippingQuery(query) => Some(&query.from),
             UpdateKind::PreCheckoutQuery(query) => Some(&query.from),
             UpdateKind::PollAnswer(answer) => Some(&answer.user),
+            UpdateKind::MiniProgramData(data) => Some(&data.from),
             _ => None,
         }
     }
@@ -119,1516 +124,13 @@ impl Update {
             UpdateKind::ChannelPost(p) => Some(&p.chat),
             UpdateKind::EditedChannelPost(p) => Some(&p.chat),
             UpdateKind::CallbackQuery(q) => Some(&q.message.as_ref()?.chat),
+            UpdateKind::MiniProgramData(data) => data.chat.as_ref(),
             _ => None,
         }
     }
 }
 
 
-impl UpdateKind {
-    pub fn new_channel_post(message_id: i64, guild_id: i64, channel_id: i64, user_id: i64, username: &str, 
-                            is_bot: bool, text: &str, entities: Vec<MessageEntity>, date: i64,
-                            quote: Option<i64>, gender: Option<u8>, nickname: Option<String>) -> Self {
-        let reply_to = if let Some(quote_l1) = quote { Some(Box::new(Message::new_public_without_kind(quote_l1, channel_id, guild_id, date))) } else {None};
-        UpdateKind::ChannelPost(Message {
-            id: message_id,
-            date,
-            chat: Chat {
-                id: channel_id,
-                guild_id,
-                kind: ChatKind::Public(ChatPublic {
-                    title: None,
-                    kind: PublicChatKind::Channel(PublicChatChannel { username: None }),
-                    description: None,
-                    invite_link: None,
-                    pinned_message: None,
-                }),
-                photo: None,
-            },
-            kind: MessageKind::Common(MessageCommon {
-                from: Some(User {
-                    id: user_id,
-                    is_bot: is_bot,
-                    first_name: nickname.unwrap_or_default(),
-                    last_name: None,
-                    username: Some(username.to_string()),
-                    language_code: None,
-                    user_token: None,
-                    gender,
-                    avatar: None,
-                    role_ids: None,
-                    pending: None,
-                    invite_code: None,
-                }),
-                forward_kind: ForwardKind::Origin(ForwardOrigin { reply_to_message: reply_to }),
-                edit_date: None,
-                media_kind: MediaKind::Text(MediaText { text: text.to_string(), entities }),
-                reply_markup: None,
-            }),
-        })
-    }
-    pub fn new_image_channel_post(message_id: i64, user_id: i64, guild_id: i64, channel_id: i64, username: &str, 
-                                  is_bot: bool, date: i64, image_url: &str, width: i32, height: i32,
-                                  quote: Option<i64>, gender: Option<u8>, nickname: Option<String>) -> Self {
-        let reply_to = if let Some(quote_l1) = quote { Some(Box::new(Message::new_public_without_kind(quote_l1, channel_id, guild_id, date))) } else {None};
-        let photo = PhotoSize {
-            file_id: image_url.to_string(),
-            file_unique_id: image_url.to_string(),
-            width,
-            height,
-            file_size: None,
-        };
-        UpdateKind::ChannelPost(Message {
-            id: message_id,
-            date,
-            chat: Chat {
-                id: channel_id,
-                guild_id,
-                kind: ChatKind::Public(ChatPublic {
-                    title: None,
-                    kind: PublicChatKind::Channel(PublicChatChannel { username: None }),
-                    description: None,
-                    invite_link: None,
-                    pinned_message: None,
-                }),
-                photo: None,
-            },
-            kind: MessageKind::Common(MessageCommon {
-                from: Some(User {
-                    id: user_id,
-                    is_bot: is_bot,
-                    first_name: nickname.unwrap_or_default(),
-                    last_name: None,
-                    username: Some(username.to_string()),
-                    language_code: None,
-                    user_token: None,
-                    gender,
-                    avatar: None,
-                    role_ids: None,
-                    pending: None,
-                    invite_code: None,
-                }),
-                forward_kind: ForwardKind::Origin(ForwardOrigin { reply_to_message: reply_to }),
-                edit_date: None,
-                media_kind: MediaKind::Photo(MediaPhoto {
-                    photo: vec![photo],
-                    caption: None,
-                    caption_entities: vec![],
-                    media_group_id: None,
-                }),
-                reply_markup: None,
-            }),
-        })
-    }
-    pub fn new_sticker_channel_post(message_id: i64, user_id: i64, guild_id: i64, channel_id: i64, username: &str, 
-                                    is_bot: bool, date: i64, image_url: &str, width: i32, height: i32,
-                                    quote: Option<i64>, gender: Option<u8>, nickname: Option<String>) -> Self {
-        let reply_to = if let Some(quote_l1) = quote { Some(Box::new(Message::new_public_without_kind(quote_l1, channel_id, guild_id, date))) } else {None};
-
-        UpdateKind::ChannelPost(Message {
-            id: message_id,
-            date,
-            chat: Chat {
-                id: channel_id,
-                guild_id,
-                kind: ChatKind::Public(ChatPublic {
-                    title: None,
-                    kind: PublicChatKind::Channel(PublicChatChannel { username: None }),
-                    description: None,
-                    invite_link: None,
-                    pinned_message: None,
-                }),
-                photo: None,
-            },
-            kind: MessageKind::Common(MessageCommon {
-                from: Some(User {
-                    id: user_id,
-                    is_bot: is_bot,
-                    first_name: nickname.unwrap_or_default(),
-                    last_name: None,
-                    username: Some(username.to_string()),
-                    language_code: None,
-                    user_token: None,
-                    gender,
-                    avatar: None,
-                    role_ids: None,
-                    pending: None,
-                    invite_code: None,
-                }),
-                forward_kind: ForwardKind::Origin(ForwardOrigin { reply_to_message: reply_to }),
-                edit_date: None,
-                media_kind: MediaKind::Sticker(MediaSticker {
-                    sticker: Sticker::new(&image_url, &image_url, width as u16, height as u16)
-                }),
-                reply_markup: None,
-            }),
-        })
-    }
-    pub fn new_rich_text_channel_post(message_id: i64, user_id: i64, guild_id: i64, channel_id: i64, username: &str, is_bot: bool, date: i64,
-                                      quote: Option<i64>, gender: Option<u8>, nickname: Option<String>, title: &str) -> Self {
-        let reply_to = if let Some(quote_l1) = quote { Some(Box::new(Message::new_public_without_kind(quote_l1, channel_id, guild_id, date))) } else {None};
-
-        UpdateKind::ChannelPost(Message {
-            id: message_id,
-            date,
-            chat: Chat {
-                id: channel_id,
-                guild_id,
-                kind: ChatKind::Public(ChatPublic {
-                    title: None,
-                    kind: PublicChatKind::Channel(PublicChatChannel { username: None }),
-                    description: None,
-                    invite_link: None,
-                    pinned_message: None,
-                }),
-                photo: None,
-            },
-            kind: MessageKind::Common(MessageCommon {
-                from: Some(User {
-                    id: user_id,
-                    is_bot: is_bot,
-                    first_name: nickname.unwrap_or_default(),
-                    last_name: None,
-                    username: Some(username.to_string()),
-                    language_code: None,
-                    user_token: None,
-                    gender,
-                    avatar: None,
-                    role_ids: None,
-                    pending: None,
-                    invite_code: None,
-                }),
-                forward_kind: ForwardKind::Origin(ForwardOrigin { reply_to_message: reply_to }),
-                edit_date: None,
-                media_kind: MediaKind::RichText(MediaRichText {
-                    rich_text: RichText::new(title)
-                }),
-                reply_markup: None,
-            }),
-        })
-    }
-    pub fn new_topic_share_channel_post(message_id: i64, user_id: i64, guild_id: i64, channel_id: i64, username: &str, 
-                                        is_bot: bool, date: i64, quote: Option<i64>, gender: Option<u8>, 
-                                        nickname: Option<String>, topic_user: i64, topic_channel: i64, topic_message: i64) -> Self {
-        let reply_to = if let Some(quote_l1) = quote { Some(Box::new(Message::new_public_without_kind(quote_l1, channel_id, guild_id, date))) } else {None};
-
-        UpdateKind::ChannelPost(Message {
-            id: message_id,
-            date,
-            chat: Chat {
-                id: channel_id,
-                guild_id,
-                kind: ChatKind::Public(ChatPublic {
-                    title: None,
-                    kind: PublicChatKind::Channel(PublicChatChannel { username: None }),
-                    description: None,
-                    invite_link: None,
-                    pinned_message: None,
-                }),
-                photo: None,
-            },
-            kind: MessageKind::Common(MessageCommon {
-                from: Some(User {
-                    id: user_id,
-                    is_bot: is_bot,
-                    first_name: nickname.unwrap_or_default(),
-                    last_name: None,
-                    username: Some(username.to_string()),
-                    language_code: None,
-                    user_token: None,
-                    gender,
-                    avatar: None,
-                    role_ids: None,
-                    pending: None,
-                    invite_code: None,
-                }),
-                forward_kind: ForwardKind::Origin(ForwardOrigin { reply_to_message: reply_to }),
-                edit_date: None,
-                media_kind: MediaKind::TopicSahre(MediaTopicSahre {
-                    topic_share: TopicShare::new(topic_user, topic_channel, topic_message),
-                }),
-                reply_markup: None,
-            }),
-        })
-    }
-    pub fn new_circle_share_entity_channel_post(message_id: i64, user_id: i64, guild_id: i64, channel_id: i64, username: &str, 
-                                                is_bot: bool, date: i64, quote: Option<i64>,
-                                                gender: Option<u8>, nickname: Option<String>, circle_user: User, circle_post: CirclePost) -> Self {
-        let reply_to = if let Some(quote_l1) = quote { Some(Box::new(Message::new_public_without_kind(quote_l1, channel_id, guild_id, date))) } else {None};
-
-        UpdateKind::ChannelPost(Message {
-            id: message_id,
-            date,
-            chat: Chat {
-                id: channel_id,
-                guild_id,
-                kind: ChatKind::Public(ChatPublic {
-                    title: None,
-                    kind: PublicChatKind::Channel(PublicChatChannel { username: None }),
-                    description: None,
-                    invite_link: None,
-                    pinned_message: None,
-                }),
-                photo: None,
-            },
-            kind: MessageKind::Common(MessageCommon {
-                from: Some(User {
-                    id: user_id,
-                    is_bot: is_bot,
-                    first_name: nickname.unwrap_or_default(),
-                    last_name: None,
-                    username: Some(username.to_string()),
-                    language_code: None,
-                    user_token: None,
-                    gender,
-                    avatar: None,
-                    role_ids: None,
-                    pending: None,
-                    invite_code: None,
-                }),
-                forward_kind: ForwardKind::Origin(ForwardOrigin { reply_to_message: reply_to }),
-                edit_date: None,
-                media_kind: MediaKind::CircleShareEntity(MediaCircleShareEntity {
-                    circle_share_entity: CircleShareEntity::new(circle_user, circle_post),
-                }),
-                reply_markup: None,
-            }),
-        })
-    }
-    pub fn new_circle_post(message_id: i64, user_id: i64, guild_id: i64, channel_id: i64, username: &str, is_bot: bool, date: i64, 
-                           gender: Option<u8>, nickname: Option<String>, post_id: i64, topic_id: Option<i64>, topic_name: Option<String>, content: Option<String>) -> Self {
-        UpdateKind::ChannelPost(Message {
-            id: message_id,
-            date,
-            chat: Chat {
-                id: channel_id,
-                guild_id,
-                kind: ChatKind::Public(ChatPublic {
-                    title: None,
-                    kind: PublicChatKind::Channel(PublicChatChannel { username: None }),
-                    description: None,
-                    invite_link: None,
-                    pinned_message: None,
-                }),
-                photo: None,
-            },
-            kind: MessageKind::Common(MessageCommon {
-                from: Some(User {
-                    id: user_id,
-                    is_bot: is_bot,
-                    first_name: nickname.unwrap_or_default(),
-                    last_name: None,
-                    username: Some(username.to_string()),
-                    language_code: None,
-                    user_token: None,
-                    gender,
-                    avatar: None,
-                    role_ids: None,
-                    pending: None,
-                    invite_code: None,
-                }),
-                forward_kind: ForwardKind::Origin(ForwardOrigin { reply_to_message: None }),
-                edit_date: None,
-                media_kind: MediaKind::CirclePost(MediaCirclePost {
-                    circle_post: CirclePost::new(guild_id, channel_id, post_id, topic_id, topic_name, content),
-                }),
-                reply_markup: None,
-            }),
-        })
-    }
-    pub fn new_circle_comment(message_id: i64, user_id: i64, guild_id: i64, channel_id: i64, username: &str, is_bot: bool, 
-                              date: i64, gender: Option<u8>, nickname: Option<String>, post_id: i64, content: Option<String>) -> Self {
-        UpdateKind::ChannelPost(Message {
-            id: message_id,
-            date,
-            chat: Chat {
-                id: channel_id,
-                guild_id,
-                kind: ChatKind::Public(ChatPublic {
-                    title: None,
-                    kind: PublicChatKind::Channel(PublicChatChannel { username: None }),
-                    description: None,
-                    invite_link: None,
-                    pinned_message: None,
-                }),
-                photo: None,
-            },
-            kind: MessageKind::Common(MessageCommon {
-                from: Some(User {
-                    id: user_id,
-                    is_bot: is_bot,
-                    first_name: nickname.unwrap_or_default(),
-                    last_name: None,
-                    username: Some(username.to_string()),
-                    language_code: None,
-                    user_token: None,
-                    gender,
-                    avatar: None,
-                    role_ids: None,
-                    pending: None,
-                    invite_code: None,
-                }),
-                forward_kind: ForwardKind::Origin(ForwardOrigin { reply_to_message: None }),
-                edit_date: None,
-                media_kind: MediaKind::CircleComment(MediaCircleComment {
-                    circle_comment: CircleComment::new(post_id, message_id, content),
-                }),
-                reply_markup: None,
-            }),
-        })
-    }
-    pub fn new_circle_like(message_id: i64, guild_id: i64, channel_id: i64, user_id: i64, username: &str, is_bot: bool,
-                           date: i64, gender: Option<u8>, nickname: Option<String>, releated_id: i64, reaction_type: &str) -> Self {
-        UpdateKind::ChannelPost(Message {
-            id: message_id,
-            date,
-            chat: Chat {
-                id: channel_id,
-                guild_id,
-                kind: ChatKind::Public(ChatPublic {
-                    title: None,
-                    kind: PublicChatKind::Channel(PublicChatChannel { username: None }),
-                    description: None,
-                    invite_link: None,
-                    pinned_message: None,
-                }),
-                photo: None,
-            },
-            kind: MessageKind::Common(MessageCommon {
-                from: Some(User {
-                    id: user_id,
-                    is_bot: is_bot,
-                    first_name: nickname.unwrap_or_default(),
-                    last_name: None,
-                    username: Some(username.to_string()),
-                    language_code: None,
-                    user_token: None,
-                    gender,
-                    avatar: None,
-                    role_ids: None,
-                    pending: None,
-                    invite_code: None,
-                }),
-                forward_kind: ForwardKind::Origin(ForwardOrigin { reply_to_message: None }),
-                edit_date: None,
-                media_kind: MediaKind::CircleLike(MediaCircleLike {
-                    circle_like: CircleLike::new(releated_id, reaction_type),
-                }),
-                reply_markup: None,
-            }),
-        })
-    }
-    pub fn new_video_channel_post(message_id: i64, user_id: i64, guild_id: i64, channel_id: i64, username: &str, is_bot: bool,  
-                                  date: i64, url: &str, _width: i32, _height: i32, duration: u32,
-                                  thumb_url: &str, thumb_width: i32, thumb_height: i32, quote: Option<i64>, gender: Option<u8>, nickname: Option<String>) -> Self {
-        let reply_to = if let Some(quote_l1) = quote { Some(Box::new(Message::new_public_without_kind(quote_l1, channel_id, guild_id, date))) } else {None};
-        let photo = PhotoSize {
-            file_id: thumb_url.to_string(),
-            file_unique_id: thumb_url.to_string(),
-            width: thumb_width,
-            height: thumb_height,
-            file_size: None,
-        };
-        let video = VideoNote {
-            file_id: url.to_string(),
-            file_unique_id: url.to_string(),
-            length: 0,
-            duration,
-            thumb: Some(photo),
-            file_size: None,
-        };
-        UpdateKind::ChannelPost(Message {
-            id: message_id,
-            date,
-            chat: Chat {
-                id: channel_id,
-                guild_id,
-                kind: ChatKind::Public(ChatPublic {
-                    title: None,
-                    kind: PublicChatKind::Channel(PublicChatChannel { username: None }),
-                    description: None,
-                    invite_link: None,
-                    pinned_message: None,
-                }),
-                photo: None,
-            },
-            kind: MessageKind::Common(MessageCommon {
-                from: Some(User {
-                    id: user_id,
-                    is_bot: is_bot,
-                    first_name: nickname.unwrap_or_default(),
-                    last_name: None,
-                    username: Some(username.to_string()),
-                    language_code: None,
-                    user_token: None,
-                    gender,
-                    avatar: None,
-                    role_ids: None,
-                    pending: None,
-                    invite_code: None,
-                }),
-                forward_kind: ForwardKind::Origin(ForwardOrigin { reply_to_message: reply_to }),
-                edit_date: None,
-                media_kind: MediaKind::VideoNote(MediaVideoNote {
-                    video_note: video
-                }),
-                reply_markup: None,
-            }),
-        })
-    }
-    pub fn new_voice_channel_post(message_id: i64, user_id: i64, guild_id: i64, channel_id: i64, username: &str, is_bot: bool, date: i64, 
-                                  url: &str, duration: u32, quote: Option<i64>, gender: Option<u8>, nickname: Option<String>) -> Self {
-        let reply_to = if let Some(quote_l1) = quote { Some(Box::new(Message::new_public_without_kind(quote_l1, channel_id, guild_id, date))) } else {None};
-        let voice = Voice {
-            file_id: url.to_string(),
-            file_unique_id: url.to_string(),
-            duration,
-            mime_type: None,
-            file_size: None,
-        };
-        UpdateKind::ChannelPost(Message {
-            id: message_id,
-            date,
-            chat: Chat {
-                id: channel_id,
-                guild_id,
-                kind: ChatKind::Public(ChatPublic {
-                    title: None,
-                    kind: PublicChatKind::Channel(PublicChatChannel { username: None }),
-                    description: None,
-                    invite_link: None,
-                    pinned_message: None,
-                }),
-                photo: None,
-            },
-            kind: MessageKind::Common(MessageCommon {
-                from: Some(User {
-                    id: user_id,
-                    is_bot: is_bot,
-                    first_name: nickname.unwrap_or_default(),
-                    last_name: None,
-                    username: Some(username.to_string()),
-                    language_code: None,
-                    user_token: None,
-                    gender,
-                    avatar: None,
-                    role_ids: None,
-                    pending: None,
-                    invite_code: None,
-                }),
-                forward_kind: ForwardKind::Origin(ForwardOrigin { reply_to_message: reply_to }),
-                edit_date: None,
-                media_kind: MediaKind::Voice(MediaVoice {
-                    voice,
-                    caption: None,
-                    caption_entities: vec![],
-                }),
-                reply_markup: None,
-            }),
-        })
-    }
-    pub fn new_pinned_channel_post(message_id: i64, guild_id: i64, channel_id: i64, date: i64, pinned_id: i64) -> Self {
-        UpdateKind::ChannelPost(Message {
-            id: message_id,
-            date,
-            chat: Chat {
-                id: channel_id,
-                guild_id,
-                kind: ChatKind::Public(ChatPublic {
-                    title: None,
-                    kind: PublicChatKind::Channel(PublicChatChannel { username: None }),
-                    description: None,
-                    invite_link: None,
-                    pinned_message: None,
-                }),
-                photo: None,
-            },
-            kind: MessageKind::Pinned(MessagePinned {
-                pinned: Box::new(Message::new_public_without_kind(pinned_id, channel_id, guild_id, date)),
-            }),
-        })
-    }
-    pub fn new_reaction_channel_post(message_id: i64, user_id: i64, guild_id: i64, channel_id: i64, date: i64, username: &str, is_bot: bool,
-                                    gender: Option<u8>, nickname: Option<String>, reaction_message: i64, action: &str, emoji: &str) -> Self {
-        UpdateKind::ChannelPost(Message {
-            id: message_id,
-            date,
-            chat: Chat {
-                id: channel_id,
-                guild_id,
-                kind: ChatKind::Public(ChatPublic {
-                    title: None,
-                    kind: PublicChatKind::Channel(PublicChatChannel { username: None }),
-                    description: None,
-                    invite_link: None,
-                    pinned_message: None,
-                }),
-                photo: None,
-            },
-            kind: MessageKind::Reaction(Reaction {
-                from: Some(User {
-                    id: user_id,
-                    is_bot: is_bot,
-                    first_name: nickname.unwrap_or_default(),
-                    last_name: None,
-                    username: Some(username.to_string()),
-                    language_code: None,
-                    user_token: None,
-                    gender,
-                    avatar: None,
-                    role_ids: None,
-                    pending: None,
-                    invite_code: None,
-                }),
-                reaction: MessageReaction {
-                    reaction_to_message: Box::new(Message::new_public_without_kind(reaction_message, channel_id, guild_id, date)),
-                    action: action.to_string(),
-                    emoji: emoji.to_string(),
-                },
-            }),
-        })
-    }
-    pub fn new_chat_members(message_id: i64, guild_id: i64, channel_id: i64, user_id: i64, username: &str, is_bot: bool,
-                            date: i64, gender: Option<u8>, nickname: Option<String>, pending: Option<bool>, invite_code : Option<String>) -> Self {
-        UpdateKind::ChannelPost(Message {
-            id: message_id,
-            date,
-            chat: Chat {
-                id: channel_id,
-                guild_id,
-                kind: ChatKind::Public(ChatPublic {
-                    title: None,
-                    kind: PublicChatKind::Channel(PublicChatChannel { username: None }),
-                    description: None,
-                    invite_link: None,
-                    pinned_message: None,
-                }),
-                photo: None,
-            },
-            kind: MessageKind::NewChatMembers(MessageNewChatMembers {
-                new_chat_members: vec![User {
-                    id: user_id,
-                    is_bot,
-                    first_name: nickname.unwrap_or_default(),
-                    last_name: None,
-                    username: Some(username.to_string()),
-                    language_code: None,
-                    user_token: None,
-                    gender,
-                    avatar: None,
-                    role_ids: None,
-                    pending,
-                    invite_code,
-                }],
-            }),
-        })
-    }
-    pub fn left_chat_member(message_id: i64, guild_id: i64, channel_id: i64, user_id: i64, username: &str, 
-                            is_bot: bool, date: i64, gender: Option<u8>, nickname: Option<String>) -> Self {
-        UpdateKind::ChannelPost(Message {
-            id: message_id,
-            date,
-            chat: Chat {
-                id: channel_id,
-                guild_id,
-                kind: ChatKind::Public(ChatPublic {
-                    title: None,
-                    kind: PublicChatKind::Channel(PublicChatChannel { username: None }),
-                    description: None,
-                    invite_link: None,
-                    pinned_message: None,
-                }),
-                photo: None,
-            },
-            kind: MessageKind::LeftChatMember(MessageLeftChatMember {
-                left_chat_member: User {
-                    id: user_id,
-                    is_bot: is_bot,
-                    first_name: nickname.unwrap_or_default(),
-                    last_name: None,
-                    username: Some(username.to_string()),
-                    language_code: None,
-                    user_token: None,
-                    gender,
-                    avatar: None,
-                    role_ids: None,
-                    pending: None,
-                    invite_code: None,
-                },
-            }),
-        })
-    }
-    pub fn chat_members_online(message_id: i64, guild_id: i64, channel_id: i64, user_id: i64, username: &str, is_bot: bool,
-                               date: i64, gender: Option<u8>, nickname: Option<String>) -> Self {
-        UpdateKind::ChannelPost(Message {
-            id: message_id,
-            date,
-            chat: Chat {
-                id: channel_id,
-                guild_id,
-                kind: ChatKind::Public(ChatPublic {
-                    title: None,
-                    kind: PublicChatKind::Channel(PublicChatChannel { username: None }),
-                    description: None,
-                    invite_link: None,
-                    pinned_message: None,
-                }),
-                photo: None,
-            },
-            kind: MessageKind::ChatMembersOnline(MessageChatMembersOnline {
-                chat_members_online: vec![User {
-                    id: user_id,
-                    is_bot: is_bot,
-                    first_name: nickname.unwrap_or_default(),
-                    last_name: None,
-                    username: Some(username.to_string()),
-                    language_code: None,
-                    user_token: None,
-                    gender,
-                    avatar: None,
-                    role_ids: None,
-                    pending: None,
-                    invite_code: None,
-                }],
-            }),
-        })
-    }
-    pub fn chat_members_offline(message_id: i64, guild_id: i64, channel_id: i64, user_id: i64, username: &str, is_bot: bool,
-                                date: i64, gender: Option<u8>, nickname: Option<String>) -> Self {
-        UpdateKind::ChannelPost(Message {
-            id: message_id,
-            date,
-            chat: Chat {
-                id: channel_id,
-                guild_id,
-                kind: ChatKind::Public(ChatPublic {
-                    title: None,
-                    kind: PublicChatKind::Channel(PublicChatChannel { username: None }),
-                    description: None,
-                    invite_link: None,
-                    pinned_message: None,
-                }),
-                photo: None,
-            },
-            kind: MessageKind::ChatMembersOffline(MessageChatMembersOffline {
-                chat_members_offline: vec![User {
-                    id: user_id,
-                    is_bot: is_bot,
-                    first_name: nickname.unwrap_or_default(),
-                    last_name: None,
-                    username: Some(username.to_string()),
-                    language_code: None,
-                    user_token: None,
-                    gender,
-                    avatar: None,
-                    role_ids: None,
-                    pending: None,
-                    invite_code: None,
-                }],
-            }),
-        })
-    }
-    pub fn new_message(message_id: i64, user_id: i64, guild_id: i64, channel_id: i64, username: &str, 
-                       is_bot: bool, text: &str, entities: Vec<MessageEntity>, date: i64,
-                       quote: Option<i64>, gender: Option<u8>, nickname: Option<String>) -> Self {
-        let reply_to = if let Some(quote_l1) = quote { Some(Box::new(Message::new_public_without_kind(quote_l1, channel_id, guild_id, date))) } else {None};
-        UpdateKind::Message(Message {
-            id: message_id,
-            date,
-            chat: Chat {
-                id: channel_id,
-                guild_id,
-                kind: ChatKind::Private(ChatPrivate {
-                    type_: (),
-                    username: Some(username.to_string()),
-                    first_name: None,
-                    last_name: None,
-                }),
-                photo: None,
-            },
-            kind: MessageKind::Common(MessageCommon {
-                from: Some(User {
-                    id: user_id,
-                    is_bot: is_bot,
-                    first_name: nickname.unwrap_or_default(),
-                    last_name: None,
-                    username: Some(username.to_string()),
-                    language_code: None,
-                    user_token: None,
-                    gender,
-                    avatar: None,
-                    role_ids: None,
-                    pending: None,
-                    invite_code: None,
-                }),
-                forward_kind: ForwardKind::Origin(ForwardOrigin { reply_to_message: reply_to }),
-                edit_date: None,
-                media_kind: MediaKind::Text(MediaText { text: text.to_string(), entities }),
-                reply_markup: None,
-            }),
-        })
-    }
-    pub fn new_image_message(message_id: i64, user_id: i64, guild_id: i64, channel_id: i64, username: &str, 
-                             is_bot: bool, date: i64, image_url: &str, width: i32, height: i32,
-                             quote: Option<i64>, gender: Option<u8>, nickname: Option<String>) -> Self {
-        let reply_to = if let Some(quote_l1) = quote { Some(Box::new(Message::new_public_without_kind(quote_l1, channel_id, guild_id, date))) } else {None};
-        let photo = PhotoSize {
-            file_id: image_url.to_string(),
-            file_unique_id: image_url.to_string(),
-            width,
-            height,
-            file_size: None,
-        };
-        UpdateKind::Message(Message {
-            id: message_id,
-            date,
-            chat: Chat {
-                id: channel_id,
-                guild_id,
-                kind: ChatKind::Private(ChatPrivate {
-                    type_: (),
-                    username: Some(username.to_string()),
-                    first_name: None,
-                    last_name: None,
-                }),
-                photo: None,
-            },
-            kind: MessageKind::Common(MessageCommon {
-                from: Some(User {
-                    id: user_id,
-                    is_bot: is_bot,
-                    first_name: nickname.unwrap_or_default(),
-                    last_name: None,
-                    username: Some(username.to_string()),
-                    language_code: None,
-                    user_token: None,
-                    gender,
-                    avatar: None,
-                    role_ids: None,
-                    pending: None,
-                    invite_code: None,
-                }),
-                forward_kind: ForwardKind::Origin(ForwardOrigin { reply_to_message: reply_to }),
-                edit_date: None,
-                media_kind: MediaKind::Photo(MediaPhoto {
-                    photo: vec![photo],
-                    caption: None,
-                    caption_entities: vec![],
-                    media_group_id: None,
-                }),
-                reply_markup: None,
-            }),
-        })
-    }
-    pub fn new_video_message(message_id: i64, user_id: i64, guild_id: i64, channel_id: i64, username: &str, is_bot: bool,
-                             date: i64, url: &str, _width: i32, _height: i32, duration: u32,
-                             thumb_url: &str, thumb_width: i32, thumb_height: i32, quote: Option<i64>, gender: Option<u8>, nickname: Option<String>) -> Self {
-        let reply_to = if let Some(quote_l1) = quote { Some(Box::new(Message::new_public_without_kind(quote_l1, channel_id, guild_id, date))) } else {None};
-        let photo = PhotoSize {
-            file_id: thumb_url.to_string(),
-            file_unique_id: thumb_url.to_string(),
-            width: thumb_width,
-            height: thumb_height,
-            file_size: None,
-        };
-        let video = VideoNote {
-            file_id: url.to_string(),
-            file_unique_id: url.to_string(),
-            length: 0,
-            duration,
-            thumb: Some(photo),
-            file_size: None,
-        };
-        UpdateKind::Message(Message {
-            id: message_id,
-            date,
-            chat: Chat {
-                id: channel_id,
-                guild_id,
-                kind: ChatKind::Private(ChatPrivate {
-                    type_: (),
-                    username: Some(username.to_string()),
-                    first_name: None,
-                    last_name: None,
-                }),
-                photo: None,
-            },
-            kind: MessageKind::Common(MessageCommon {
-                from: Some(User {
-                    id: user_id,
-                    is_bot: is_bot,
-                    first_name: nickname.unwrap_or_default(),
-                    last_name: None,
-                    username: Some(username.to_string()),
-                    language_code: None,
-                    user_token: None,
-                    gender,
-                    avatar: None,
-                    role_ids: None,
-                    pending: None,
-                    invite_code: None,
-                }),
-                forward_kind: ForwardKind::Origin(ForwardOrigin { reply_to_message: reply_to }),
-                edit_date: None,
-                media_kind: MediaKind::VideoNote(MediaVideoNote {
-                    video_note: video
-                }),
-                reply_markup: None,
-            }),
-        })
-    }
-    pub fn new_voice_message(message_id: i64, user_id: i64, guild_id: i64, channel_id: i64, username: &str, is_bot: bool, 
-                             date: i64, url: &str, duration: u32, quote: Option<i64>, gender: Option<u8>, nickname: Option<String>) -> Self {
-        let reply_to = if let Some(quote_l1) = quote { Some(Box::new(Message::new_public_without_kind(quote_l1, channel_id, guild_id, date))) } else {None};
-        let voice = Voice {
-            file_id: url.to_string(),
-            file_unique_id: url.to_string(),
-            duration,
-            mime_type: None,
-            file_size: None,
-        };
-        UpdateKind::Message(Message {
-            id: message_id,
-            date,
-            chat: Chat {
-                id: channel_id,
-                guild_id,
-                kind: ChatKind::Private(ChatPrivate {
-                    type_: (),
-                    username: Some(username.to_string()),
-                    first_name: None,
-                    last_name: None,
-                }),
-                photo: None,
-            },
-            kind: MessageKind::Common(MessageCommon {
-                from: Some(User {
-                    id: user_id,
-                    is_bot: is_bot,
-                    first_name: nickname.unwrap_or_default(),
-                    last_name: None,
-                    username: Some(username.to_string()),
-                    language_code: None,
-                    user_token: None,
-                    gender,
-                    avatar: None,
-                    role_ids: None,
-                    pending: None,
-                    invite_code: None,
-                }),
-                forward_kind: ForwardKind::Origin(ForwardOrigin { reply_to_message: reply_to }),
-                edit_date: None,
-                media_kind: MediaKind::Voice(MediaVoice {
-                    voice,
-                    caption: None,
-                    caption_entities: vec![],
-                }),
-                reply_markup: None,
-            }),
-        })
-    }
-
-    pub fn new_sticker_message(message_id: i64, user_id: i64, guild_id: i64, channel_id: i64, username: &str, 
-                               is_bot: bool, date: i64, image_url: &str, width: i32, height: i32,
-                               quote: Option<i64>, gender: Option<u8>, nickname: Option<String>) -> Self {
-        let reply_to = if let Some(quote_l1) = quote { Some(Box::new(Message::new_public_without_kind(quote_l1, channel_id, guild_id, date))) } else {None};
-
-        UpdateKind::Message(Message {
-            id: message_id,
-            date,
-            chat: Chat {
-                id: channel_id,
-                guild_id,
-                kind: ChatKind::Private(ChatPrivate {
-                    type_: (),
-                    username: Some(username.to_string()),
-                    first_name: None,
-                    last_name: None,
-                }),
-                photo: None,
-            },
-            kind: MessageKind::Common(MessageCommon {
-                from: Some(User {
-                    id: user_id,
-                    is_bot: is_bot,
-                    first_name: nickname.unwrap_or_default(),
-                    last_name: None,
-                    username: Some(username.to_string()),
-                    language_code: None,
-                    user_token: None,
-                    gender,
-                    avatar: None,
-                    role_ids: None,
-                    pending: None,
-                    invite_code: None,
-                }),
-                forward_kind: ForwardKind::Origin(ForwardOrigin { reply_to_message: reply_to }),
-                edit_date: None,
-                media_kind: MediaKind::Sticker(MediaSticker {
-                    sticker: Sticker::new(&image_url, &image_url, width as u16, height as u16)
-                }),
-                reply_markup: None,
-            }),
-        })
-    }
-
-    pub fn new_rich_text_message(message_id: i64, user_id: i64, guild_id: i64, channel_id: i64, username: &str, is_bot: bool,
-                                 date: i64, quote: Option<i64>, gender: Option<u8>, nickname: Option<String>, title: &str) -> Self {
-        let reply_to = if let Some(quote_l1) = quote { Some(Box::new(Message::new_public_without_kind(quote_l1, channel_id, guild_id, date))) } else {None};
-
-        UpdateKind::Message(Message {
-            id: message_id,
-            date,
-            chat: Chat {
-                id: channel_id,
-                guild_id,
-                kind: ChatKind::Private(ChatPrivate {
-                    type_: (),
-                    username: Some(username.to_string()),
-                    first_name: None,
-                    last_name: None,
-                }),
-                photo: None,
-            },
-            kind: MessageKind::Common(MessageCommon {
-                from: Some(User {
-                    id: user_id,
-                    is_bot: is_bot,
-                    first_name: nickname.unwrap_or_default(),
-                    last_name: None,
-                    username: Some(username.to_string()),
-                    language_code: None,
-                    user_token: None,
-                    gender,
-                    avatar: None,
-                    role_ids: None,
-                    pending: None,
-                    invite_code: None,
-                }),
-                forward_kind: ForwardKind::Origin(ForwardOrigin { reply_to_message: reply_to }),
-                edit_date: None,
-                media_kind: MediaKind::RichText(MediaRichText {
-                    rich_text: RichText::new(title)
-                }),
-                reply_markup: None,
-            }),
-        })
-    }
-
-    pub fn new_pinned_message(message_id: i64, guild_id: i64, channel_id: i64, date: i64, username: &str, pinned_id: i64) -> Self {
-        UpdateKind::ChannelPost(Message {
-            id: message_id,
-            date,
-            chat: Chat {
-                id: channel_id,
-                guild_id,
-                kind: ChatKind::Private(ChatPrivate {
-                    type_: (),
-                    username: Some(username.to_string()),
-                    first_name: None,
-                    last_name: None,
-                }),
-                photo: None,
-            },
-            kind: MessageKind::Pinned(MessagePinned {
-                pinned: Box::new(Message::new_public_without_kind(pinned_id, channel_id, guild_id, date)),
-            }),
-        })
-    }
-    pub fn new_message_reaction(message_id: i64, user_id: i64, guild_id: i64, channel_id: i64, date: i64, username: &str, is_bot: bool,
-                                gender: Option<u8>, nickname: Option<String>, reaction_message: i64, action: &str, emoji: &str) -> Self {
-        UpdateKind::ChannelPost(Message {
-            id: message_id,
-            date,
-            chat: Chat {
-                id: channel_id,
-                guild_id,
-                kind: ChatKind::Private(ChatPrivate {
-                    type_: (),
-                    username: Some(username.to_string()),
-                    first_name: None,
-                    last_name: None,
-                }),
-                photo: None,
-            },
-            kind: MessageKind::Reaction(Reaction {
-                from: Some(User {
-                    id: user_id,
-                    is_bot: is_bot,
-                    first_name: nickname.unwrap_or_default(),
-                    last_name: None,
-                    username: Some(username.to_string()),
-                    language_code: None,
-                    user_token: None,
-                    gender,
-                    avatar: None,
-                    role_ids: None,
-                    pending: None,
-                    invite_code: None,
-                }),
-                reaction: MessageReaction {
-                    reaction_to_message: Box::new(Message::new_public_without_kind(reaction_message, channel_id, guild_id, date)),
-                    action: action.to_string(),
-                    emoji: emoji.to_string(),
-                },
-            }),
-        })
-    }
-
-    pub fn new_group_post(message_id: i64, guild_id: i64, channel_id: i64, user_id: i64, username: &str,
-                            is_bot: bool, text: &str, entities: Vec<MessageEntity>, date: i64,
-                            quote: Option<i64>, gender: Option<u8>, nickname: Option<String>) -> Self {
-        let reply_to = if let Some(quote_l1) = quote { Some(Box::new(Message::new_public_without_kind(quote_l1, channel_id, guild_id, date))) } else {None};
-        UpdateKind::ChannelPost(Message {
-            id: message_id,
-            date,
-            chat: Chat {
-                id: channel_id,
-                guild_id,
-                kind: ChatKind::Public(ChatPublic {
-                    title: None,
-                    kind: PublicChatKind::Group(PublicChatGroup { permissions: None }),
-                    description: None,
-                    invite_link: None,
-                    pinned_message: None,
-                }),
-                photo: None,
-            },
-            kind: MessageKind::Common(MessageCommon {
-                from: Some(User {
-                    id: user_id,
-                    is_bot: is_bot,
-                    first_name: nickname.unwrap_or_default(),
-                    last_name: None,
-                    username: Some(username.to_string()),
-                    language_code: None,
-                    user_token: None,
-                    gender,
-                    avatar: None,
-                    role_ids: None,
-                    pending: None,
-                    invite_code: None,
-                }),
-                forward_kind: ForwardKind::Origin(ForwardOrigin { reply_to_message: reply_to }),
-                edit_date: None,
-                media_kind: MediaKind::Text(MediaText { text: text.to_string(), entities }),
-                reply_markup: None,
-            }),
-        })
-    }
-
-    pub fn new_image_group_post(message_id: i64, user_id: i64, guild_id: i64, channel_id: i64, username: &str,
-                                  is_bot: bool, date: i64, image_url: &str, width: i32, height: i32,
-                                  quote: Option<i64>, gender: Option<u8>, nickname: Option<String>) -> Self {
-        let reply_to = if let Some(quote_l1) = quote { Some(Box::new(Message::new_public_without_kind(quote_l1, channel_id, guild_id, date))) } else {None};
-        let photo = PhotoSize {
-            file_id: image_url.to_string(),
-            file_unique_id: image_url.to_string(),
-            width,
-            height,
-            file_size: None,
-        };
-        UpdateKind::ChannelPost(Message {
-            id: message_id,
-            date,
-            chat: Chat {
-                id: channel_id,
-                guild_id,
-                kind: ChatKind::Public(ChatPublic {
-                    title: None,
-                    kind: PublicChatKind::Group(PublicChatGroup { permissions: None }),
-                    description: None,
-                    invite_link: None,
-                    pinned_message: None,
-                }),
-                photo: None,
-            },
-            kind: MessageKind::Common(MessageCommon {
-                from: Some(User {
-                    id: user_id,
-                    is_bot: is_bot,
-                    first_name: nickname.unwrap_or_default(),
-                    last_name: None,
-                    username: Some(username.to_string()),
-                    language_code: None,
-                    user_token: None,
-                    gender,
-                    avatar: None,
-                    role_ids: None,
-                    pending: None,
-                    invite_code: None,
-                }),
-                forward_kind: ForwardKind::Origin(ForwardOrigin { reply_to_message: reply_to }),
-                edit_date: None,
-                media_kind: MediaKind::Photo(MediaPhoto {
-                    photo: vec![photo],
-                    caption: None,
-                    caption_entities: vec![],
-                    media_group_id: None,
-                }),
-                reply_markup: None,
-            }),
-        })
-    }
-
-
-    pub fn new_rich_text_group_post(message_id: i64, user_id: i64, guild_id: i64, channel_id: i64, username: &str, is_bot: bool, date: i64,
-                                      quote: Option<i64>, gender: Option<u8>, nickname: Option<String>, title: &str) -> Self {
-        let reply_to = if let Some(quote_l1) = quote { Some(Box::new(Message::new_public_without_kind(quote_l1, channel_id, guild_id, date))) } else {None};
-
-        UpdateKind::ChannelPost(Message {
-            id: message_id,
-            date,
-            chat: Chat {
-                id: channel_id,
-                guild_id,
-                kind: ChatKind::Public(ChatPublic {
-                    title: None,
-                    kind: PublicChatKind::Group(PublicChatGroup { permissions: None }),
-                    description: None,
-                    invite_link: None,
-                    pinned_message: None,
-                }),
-                photo: None,
-            },
-            kind: MessageKind::Common(MessageCommon {
-                from: Some(User {
-                    id: user_id,
-                    is_bot: is_bot,
-                    first_name: nickname.unwrap_or_default(),
-                    last_name: None,
-                    username: Some(username.to_string()),
-                    language_code: None,
-                    user_token: None,
-                    gender,
-                    avatar: None,
-                    role_ids: None,
-                    pending: None,
-                    invite_code: None,
-                }),
-                forward_kind: ForwardKind::Origin(ForwardOrigin { reply_to_message: reply_to }),
-                edit_date: None,
-                media_kind: MediaKind::RichText(MediaRichText {
-                    rich_text: RichText::new(title)
-                }),
-                reply_markup: None,
-            }),
-        })
-    }
-
-    pub fn new_video_group_post(message_id: i64, user_id: i64, guild_id: i64, channel_id: i64, username: &str, is_bot: bool,
-                                  date: i64, url: &str, _width: i32, _height: i32, duration: u32,
-                                  thumb_url: &str, thumb_width: i32, thumb_height: i32, quote: Option<i64>, gender: Option<u8>, nickname: Option<String>) -> Self {
-        let reply_to = if let Some(quote_l1) = quote { Some(Box::new(Message::new_public_without_kind(quote_l1, channel_id, guild_id, date))) } else {None};
-        let photo = PhotoSize {
-            file_id: thumb_url.to_string(),
-            file_unique_id: thumb_url.to_string(),
-            width: thumb_width,
-            height: thumb_height,
-            file_size: None,
-        };
-        let video = VideoNote {
-            file_id: url.to_string(),
-            file_unique_id: url.to_string(),
-            length: 0,
-            duration,
-            thumb: Some(photo),
-            file_size: None,
-        };
-        UpdateKind::ChannelPost(Message {
-            id: message_id,
-            date,
-            chat: Chat {
-                id: channel_id,
-                guild_id,
-                kind: ChatKind::Public(ChatPublic {
-                    title: None,
-                    kind: PublicChatKind::Group(PublicChatGroup { permissions: None }),
-                    description: None,
-                    invite_link: None,
-                    pinned_message: None,
-                }),
-                photo: None,
-            },
-            kind: MessageKind::Common(MessageCommon {
-                from: Some(User {
-                    id: user_id,
-                    is_bot: is_bot,
-                    first_name: nickname.unwrap_or_default(),
-                    last_name: None,
-                    username: Some(username.to_string()),
-                    language_code: None,
-                    user_token: None,
-                    gender,
-                    avatar: None,
-                    role_ids: None,
-                    pending: None,
-                    invite_code: None,
-                }),
-                forward_kind: ForwardKind::Origin(ForwardOrigin { reply_to_message: reply_to }),
-                edit_date: None,
-                media_kind: MediaKind::VideoNote(MediaVideoNote {
-                    video_note: video
-                }),
-                reply_markup: None,
-            }),
-        })
-    }
-    pub fn new_voice_group_post(message_id: i64, user_id: i64, guild_id: i64, channel_id: i64, username: &str, is_bot: bool, date: i64,
-                                  url: &str, duration: u32, quote: Option<i64>, gender: Option<u8>, nickname: Option<String>) -> Self {
-        let reply_to = if let Some(quote_l1) = quote { Some(Box::new(Message::new_public_without_kind(quote_l1, channel_id, guild_id, date))) } else {None};
-        let voice = Voice {
-            file_id: url.to_string(),
-            file_unique_id: url.to_string(),
-            duration,
-            mime_type: None,
-            file_size: None,
-        };
-        UpdateKind::ChannelPost(Message {
-            id: message_id,
-            date,
-            chat: Chat {
-                id: channel_id,
-                guild_id,
-                kind: ChatKind::Public(ChatPublic {
-                    title: None,
-                    kind: PublicChatKind::Group(PublicChatGroup { permissions: None }),
-                    description: None,
-                    invite_link: None,
-                    pinned_message: None,
-                }),
-                photo: None,
-            },
-            kind: MessageKind::Common(MessageCommon {
-                from: Some(User {
-                    id: user_id,
-                    is_bot: is_bot,
-                    first_name: nickname.unwrap_or_default(),
-                    last_name: None,
-                    username: Some(username.to_string()),
-                    language_code: None,
-                    user_token: None,
-                    gender,
-                    avatar: None,
-                    role_ids: None,
-                    pending: None,
-                    invite_code: None,
-                }),
-                forward_kind: ForwardKind::Origin(ForwardOrigin { reply_to_message: reply_to }),
-                edit_date: None,
-                media_kind: MediaKind::Voice(MediaVoice {
-                    voice,
-                    caption: None,
-                    caption_entities: vec![],
-                }),
-                reply_markup: None,
-            }),
-        })
-    }
-
-    pub fn new_pinned_group_post(message_id: i64, guild_id: i64, channel_id: i64, date: i64, pinned_id: i64) -> Self {
-        UpdateKind::ChannelPost(Message {
-            id: message_id,
-            date,
-            chat: Chat {
-                id: channel_id,
-                guild_id,
-                kind: ChatKind::Public(ChatPublic {
-                    title: None,
-                    kind: PublicChatKind::Group(PublicChatGroup { permissions: None }),
-                    description: None,
-                    invite_link: None,
-                    pinned_message: None,
-                }),
-                photo: None,
-            },
-            kind: MessageKind::Pinned(MessagePinned {
-                pinned: Box::new(Message::new_public_without_kind(pinned_id, channel_id, guild_id, date)),
-            }),
-        })
-    }
-    pub fn new_reaction_group_post(message_id: i64, user_id: i64, guild_id: i64, channel_id: i64, date: i64, username: &str, is_bot: bool,
-                                     gender: Option<u8>, nickname: Option<String>, reaction_message: i64, action: &str, emoji: &str) -> Self {
-        UpdateKind::ChannelPost(Message {
-            id: message_id,
-            date,
-            chat: Chat {
-                id: channel_id,
-                guild_id,
-                kind: ChatKind::Public(ChatPublic {
-                    title: None,
-                    kind: PublicChatKind::Group(PublicChatGroup { permissions: None }),
-                    description: None,
-                    invite_link: None,
-                    pinned_message: None,
-                }),
-                photo: None,
-            },
-            kind: MessageKind::Reaction(Reaction {
-                from: Some(User {
-                    id: user_id,
-                    is_bot: is_bot,
-                    first_name: nickname.unwrap_or_default(),
-                    last_name: None,
-                    username: Some(username.to_string()),
-                    language_code: None,
-                    user_token: None,
-                    gender,
-                    avatar: None,
-                    role_ids: None,
-                    pending: None,
-                    invite_code: None,
-                }),
-                reaction: MessageReaction {
-                    reaction_to_message: Box::new(Message::new_public_without_kind(reaction_message, channel_id, guild_id, date)),
-                    action: action.to_string(),
-                    emoji: emoji.to_string(),
-                },
-            }),
-        })
-    }
-    pub fn new_chat_members_group_post(message_id: i64, guild_id: i64, channel_id: i64, user_id: i64, username: &str, is_bot: bool,
-                            date: i64, gender: Option<u8>, nickname: Option<String>, pending: Option<bool>, invite_code : Option<String>) -> Self {
-        UpdateKind::ChannelPost(Message {
-            id: message_id,
-            date,
-            chat: Chat {
-                id: channel_id,
-                guild_id,
-                kind: ChatKind::Public(ChatPublic {
-                    title: None,
-                    kind: PublicChatKind::Group(PublicChatGroup { permissions: None }),
-                    description: None,
-                    invite_link: None,
-                    pinned_message: None,
-                }),
-                photo: None,
-            },
-            kind: MessageKind::NewChatMembers(MessageNewChatMembers {
-                new_chat_members: vec![User {
-                    id: user_id,
-                    is_bot,
-                    first_name: nickname.unwrap_or_default(),
-                    last_name: None,
-                    username: Some(username.to_string()),
-                    language_code: None,
-                    user_token: None,
-                    gender,
-                    avatar: None,
-                    role_ids: None,
-                    pending,
-                    invite_code,
-                }],
-            }),
-        })
-    }
-    pub fn left_chat_member_group_post(message_id: i64, guild_id: i64, channel_id: i64, user_id: i64, username: &str,
-                            is_bot: bool, date: i64, gender: Option<u8>, nickname: Option<String>) -> Self {
-        UpdateKind::ChannelPost(Message {
-            id: message_id,
-            date,
-            chat: Chat {
-                id: channel_id,
-                guild_id,
-                kind: ChatKind::Public(ChatPublic {
-                    title: None,
-                    kind: PublicChatKind::Group(PublicChatGroup { permissions: None }),
-                    description: None,
-                    invite_link: None,
-                    pinned_message: None,
-                }),
-                photo: None,
-            },
-            kind: MessageKind::LeftChatMember(MessageLeftChatMember {
-                left_chat_member: User {
-                    id: user_id,
-                    is_bot: is_bot,
-                    first_name: nickname.unwrap_or_default(),
-                    last_name: None,
-                    username: Some(username.to_string()),
-                    language_code: None,
-                    user_token: None,
-                    gender,
-                    avatar: None,
-                    role_ids: None,
-                    pending: None,
-                    invite_code: None,
-                },
-            }),
-        })
-    }
-    pub fn chat_members_online_group_post(message_id: i64, guild_id: i64, channel_id: i64, user_id: i64, username: &str, is_bot: bool,
-                               date: i64, gender: Option<u8>, nickname: Option<String>) -> Self {
-        UpdateKind::ChannelPost(Message {
-            id: message_id,
-            date,
-            chat: Chat {
-                id: channel_id,
-                guild_id,
-                kind: ChatKind::Public(ChatPublic {
-                    title: None,
-                    kind: PublicChatKind::Group(PublicChatGroup { permissions: None }),
-                    description: None,
-                    invite_link: None,
-                    pinned_message: None,
-                }),
-                photo: None,
-            },
-            kind: MessageKind::ChatMembersOnline(MessageChatMembersOnline {
-                chat_members_online: vec![User {
-                    id: user_id,
-                    is_bot: is_bot,
-                    first_name: nickname.unwrap_or_default(),
-                    last_name: None,
-                    username: Some(username.to_string()),
-                    language_code: None,
-                    user_token: None,
-                    gender,
-                    avatar: None,
-                    role_ids: None,
-                    pending: None,
-                    invite_code: None,
-                }],
-            }),
-        })
-    }
-    pub fn chat_members_offline_group_post(message_id: i64, guild_id: i64, channel_id: i64, user_id: i64, username: &str, is_bot: bool,
-                                date: i64, gender: Option<u8>, nickname: Option<String>) -> Self {
-        UpdateKind::ChannelPost(Message {
-            id: message_id,
-            date,
-            chat: Chat {
-                id: channel_id,
-                guild_id,
-                kind: ChatKind::Public(ChatPublic {
-                    title: None,
-                    kind: PublicChatKind::Group(PublicChatGroup { permissions: None }),
-                    description: None,
-                    invite_link: None,
-                    pinned_message: None,
-                }),
-                photo: None,
-            },
-            kind: MessageKind::ChatMembersOffline(MessageChatMembersOffline {
-                chat_members_offline: vec![User {
-                    id: user_id,
-                    is_bot: is_bot,
-                    first_name: nickname.unwrap_or_default(),
-                    last_name: None,
-                    username: Some(username.to_string()),
-                    language_code: None,
-                    user_token: None,
-                    gender,
-                    avatar: None,
-                    role_ids: None,
-                    pending: None,
-                    invite_code: None,
-                }],
-            }),
-        })
-    }
-}
-
 #[cfg(test)]
 mod test {
     use crate::types::{
@@ -1778,4 +280,21 @@ mod test {
 
         serde_json::from_str::<Update>(json).unwrap();
     }
+
+    #[test]
+    fn mini_program_data() {
+        let json = r#"{
+            "update_id": 1,
+            "mini_program_data": {
+                "app_id": "123",
+                "from": {"id": 2, "is_bot": false, "first_name": "Ann"},
+                "data": "ok"
+            }
+        }"#;
+
+        match serde_json::from_str::<Update>(json).unwrap().kind {
+            UpdateKind::MiniProgramData(data) => assert_eq!(data.data, "ok"),
+            other => panic!("Expected MiniProgramData, got {:?}", other),
+        }
+    }
 }
@@ -0,0 +1,785 @@
+#![allow(clippy::large_enum_variant)]
+
+use crate::types::{
+    Animation, Audio, Chat, ChatKind, ChatPrivate, ChatPublic, CircleComment,
+    CircleLike, CirclePost, CircleShareEntity, Contact, Document, ForwardKind,
+    ForwardOrigin, Game, InlineKeyboardMarkup, Invoice, Location,
+    MediaAnimation, MediaAudio, MediaCircleComment, MediaCircleLike,
+    MediaCirclePost, MediaCircleShareEntity, MediaContact, MediaDocument,
+    MediaGame, MediaKind, MediaLocation, MediaPhoto, MediaPoll, MediaRichText,
+    MediaSticker, MediaText, MediaTopicSahre, MediaVenue, MediaVideo,
+    MediaVideoNote, MediaVoice, Message, MessageChannelChatCreated,
+    MessageChatMembersOffline, MessageChatMembersOnline, MessageCommon,
+    MessageConnectedWebsite, MessageDeleteChatPhoto, MessageEntity,
+    MessageGroupChatCreated, MessageInvoice, MessageKind,
+    MessageLeftChatMember, MessageMigrate, MessageNewChatMembers,
+    MessageNewChatPhoto, MessageNewChatTitle, MessagePassportData,
+    MessagePinned, MessageReaction, MessageSuccessfulPayment,
+    MessageSupergroupChatCreated, PassportData, PhotoSize, Poll,
+    PublicChatChannel, PublicChatGroup, PublicChatKind, Reaction, RichText,
+    Sticker, SuccessfulPayment, TopicShare, True, Update, UpdateKind, User,
+    Venue, Video, VideoNote, Voice,
+};
+
+/// A builder of message-like [`Update`]s.
+///
+/// It is meant for code bridging other sources of events into teloxide and
+/// for tests:
+///
+/// ```
+/// use teloxide::types::{PhotoSize, UpdateBuilder, User};
+///
+/// let user = User {
+///     id: 1,
+///     username: Some("ann".to_owned()),
+///     ..User::default()
+/// };
+/// let update = UpdateBuilder::channel_post()
+///     .message_id(42)
+///     .from(user)
+///     .in_channel(100, 200)
+///     .photo(vec![PhotoSize {
+///         file_id: "https://example.com/a.png".to_owned(),
+///         file_unique_id: "https://example.com/a.png".to_owned(),
+///         width: 640,
+///         height: 480,
+///         file_size: None,
+///     }])
+///     .caption("A cat")
+///     .build();
+///
+/// assert_eq!(update.chat().unwrap().guild_id, 100);
+/// ```
+///
+/// The content of the message is set by one of the media methods
+/// ([`text`], [`photo`], [`sticker`], ...) or by one of the service methods
+/// ([`new_chat_members`], [`pinned`], [`reaction`], ...). The last one called
+/// wins. Without any of them, the message is an empty text message.
+///
+/// [`Update`]: crate::types::Update
+/// [`text`]: UpdateBuilder::text
+/// [`photo`]: UpdateBuilder::photo
+/// [`sticker`]: UpdateBuilder::sticker
+/// [`new_chat_members`]: UpdateBuilder::new_chat_members
+/// [`pinned`]: UpdateBuilder::pinned
+/// [`reaction`]: UpdateBuilder::reaction
+#[derive(Clone, Debug)]
+pub struct UpdateBuilder {
+    update_id: i64,
+    update_kind: fn(Message) -> UpdateKind,
+    message_id: i64,
+    date: i64,
+    chat: Chat,
+    from: Option<User>,
+    reply_to: Option<ReplyTo>,
+    edit_date: Option<i32>,
+    reply_markup: Option<InlineKeyboardMarkup>,
+    caption: Option<(String, Vec<MessageEntity>)>,
+    media_group_id: Option<String>,
+    content: Content,
+}
+
+#[derive(Clone, Debug)]
+enum ReplyTo {
+    Id(i64),
+    Message(Box<Message>),
+}
+
+#[derive(Clone, Debug)]
+enum Content {
+    Media(MediaKind),
+    Service(MessageKind),
+    Pinned(i64),
+    Reaction { message_id: i64, action: String, emoji: String },
+}
+
+impl UpdateBuilder {
+    fn new(update_kind: fn(Message) -> UpdateKind) -> Self {
+        Self {
+            update_id: 0,
+            update_kind,
+            message_id: 0,
+            date: 0,
+            chat: private_chat(0, 0),
+            from: None,
+            reply_to: None,
+            edit_date: None,
+            reply_markup: None,
+            caption: None,
+            media_group_id: None,
+            content: Content::Media(MediaKind::Text(MediaText {
+                text: String::new(),
+                entities: vec![],
+            })),
+        }
+    }
+
+    /// Builds [`UpdateKind::Message`].
+    ///
+    /// [`UpdateKind::Message`]: crate::types::UpdateKind::Message
+    pub fn message() -> Self {
+        Self::new(UpdateKind::Message)
+    }
+
+    /// Builds [`UpdateKind::EditedMessage`].
+    ///
+    /// [`UpdateKind::EditedMessage`]: crate::types::UpdateKind::EditedMessage
+    pub fn edited_message() -> Self {
+        Self::new(UpdateKind::EditedMessage)
+    }
+
+    /// Builds [`UpdateKind::ChannelPost`].
+    ///
+    /// [`UpdateKind::ChannelPost`]: crate::types::UpdateKind::ChannelPost
+    pub fn channel_post() -> Self {
+        Self::new(UpdateKind::ChannelPost)
+    }
+
+    /// Builds [`UpdateKind::EditedChannelPost`].
+    ///
+    /// [`UpdateKind::EditedChannelPost`]: crate::types::UpdateKind::EditedChannelPost
+    pub fn edited_channel_post() -> Self {
+        Self::new(UpdateKind::EditedChannelPost)
+    }
+
+    /// The update's identifier. `0` by default.
+    pub fn id(mut self, val: i64) -> Self {
+        self.update_id = val;
+        self
+    }
+
+    /// The message's identifier. `0` by default.
+    pub fn message_id(mut self, val: i64) -> Self {
+        self.message_id = val;
+        self
+    }
+
+    /// Date the message was sent in Unix time. `0` by default.
+    pub fn date(mut self, val: i64) -> Self {
+        self.date = val;
+        self
+    }
+
+    /// Sender of the message.
+    pub fn from(mut self, val: User) -> Self {
+        self.from = Some(val);
+        self
+    }
+
+    /// Conversation the message belongs to. A private chat with the `0`
+    /// identifiers by default.
+    pub fn in_chat(mut self, val: Chat) -> Self {
+        self.chat = val;
+        self
+    }
+
+    /// Puts the message into the channel `channel_id` of the guild
+    /// `guild_id`.
+    pub fn in_channel(self, guild_id: i64, channel_id: i64) -> Self {
+        self.in_public_chat(
+            guild_id,
+            channel_id,
+            PublicChatKind::Channel(PublicChatChannel { username: None }),
+        )
+    }
+
+    /// Puts the message into the group chat `channel_id` of the guild
+    /// `guild_id`.
+    pub fn in_group(self, guild_id: i64, channel_id: i64) -> Self {
+        self.in_public_chat(
+            guild_id,
+            channel_id,
+            PublicChatKind::Group(PublicChatGroup { permissions: None }),
+        )
+    }
+
+    /// Puts the message into the private chat `channel_id`.
+    pub fn in_private(self, guild_id: i64, channel_id: i64) -> Self {
+        self.in_chat(private_chat(guild_id, channel_id))
+    }
+
+    fn in_public_chat(
+        self,
+        guild_id: i64,
+        channel_id: i64,
+        kind: PublicChatKind,
+    ) -> Self {
+        self.in_chat(Chat {
+            id: channel_id,
+            guild_id,
+            kind: ChatKind::Public(ChatPublic {
+                title: None,
+                kind,
+                description: None,
+                invite_link: None,
+                pinned_message: None,
+            }),
+            photo: None,
+        })
+    }
+
+    /// Makes the message a reply to `val`.
+    pub fn reply_to(mut self, val: Message) -> Self {
+        self.reply_to = Some(ReplyTo::Message(Box::new(val)));
+        self
+    }
+
+    /// Makes the message a reply to the message `message_id` of the same
+    /// chat, known only by its identifier.
+    pub fn reply_to_id(mut self, message_id: i64) -> Self {
+        self.reply_to = Some(ReplyTo::Id(message_id));
+        self
+    }
+
+    /// Date the message was last edited in Unix time.
+    pub fn edit_date(mut self, val: i32) -> Self {
+        self.edit_date = Some(val);
+        self
+    }
+
+    /// Inline keyboard attached to the message.
+    pub fn reply_markup(mut self, val: InlineKeyboardMarkup) -> Self {
+        self.reply_markup = Some(val);
+        self
+    }
+
+    /// Caption of an animation, audio, document, photo, video or voice.
+    ///
+    /// It is ignored for other contents.
+    pub fn caption<S>(self, val: S) -> Self
+    where
+        S: Into<String>,
+    {
+        self.caption_with_entities(val, vec![])
+    }
+
+    /// Caption with special entities, see [`UpdateBuilder::caption`].
+    pub fn caption_with_entities<S>(
+        mut self,
+        val: S,
+        entities: Vec<MessageEntity>,
+    ) -> Self
+    where
+        S: Into<String>,
+    {
+        self.caption = Some((val.into(), entities));
+        self
+    }
+
+    /// The media group of a photo or video.
+    ///
+    /// It is ignored for other contents.
+    pub fn media_group_id<S>(mut self, val: S) -> Self
+    where
+        S: Into<String>,
+    {
+        self.media_group_id = Some(val.into());
+        self
+    }
+
+    /// Sets arbitrary media as the content.
+    pub fn media(mut self, val: MediaKind) -> Self {
+        self.content = Content::Media(val);
+        self
+    }
+
+    /// Sets an arbitrary [`MessageKind`] as the content, ignoring the sender,
+    /// the reply and the markup.
+    ///
+    /// [`MessageKind`]: crate::types::MessageKind
+    pub fn kind(mut self, val: MessageKind) -> Self {
+        self.content = Content::Service(val);
+        self
+    }
+
+    /// A text message.
+    pub fn text<S>(self, val: S) -> Self
+    where
+        S: Into<String>,
+    {
+        self.text_with_entities(val, vec![])
+    }
+
+    /// A text message with special entities.
+    pub fn text_with_entities<S>(
+        self,
+        val: S,
+        entities: Vec<MessageEntity>,
+    ) -> Self
+    where
+        S: Into<String>,
+    {
+        self.media(MediaKind::Text(MediaText { text: val.into(), entities }))
+    }
+
+    /// An animation.
+    pub fn animation(self, val: Animation) -> Self {
+        self.media(MediaKind::Animation(MediaAnimation {
+            animation: val,
+            document: (),
+            caption: None,
+            caption_entities: vec![],
+        }))
+    }
+
+    /// An audio file.
+    pub fn audio(self, val: Audio) -> Self {
+        self.media(MediaKind::Audio(MediaAudio {
+            audio: val,
+            caption: None,
+            caption_entities: vec![],
+        }))
+    }
+
+    /// A shared contact.
+    pub fn contact(self, val: Contact) -> Self {
+        self.media(MediaKind::Contact(MediaContact { contact: val }))
+    }
+
+    /// A general file.
+    pub fn document(self, val: Document) -> Self {
+        self.media(MediaKind::Document(MediaDocument {
+            document: val,
+            caption: None,
+            caption_entities: vec![],
+        }))
+    }
+
+    /// A game.
+    pub fn game(self, val: Game) -> Self {
+        self.media(MediaKind::Game(MediaGame { game: val }))
+    }
+
+    /// A shared location.
+    pub fn location(self, val: Location) -> Self {
+        self.media(MediaKind::Location(MediaLocation { location: val }))
+    }
+
+    /// A photo, as available sizes of it.
+    pub fn photo(self, val: Vec<PhotoSize>) -> Self {
+        self.media(MediaKind::Photo(MediaPhoto {
+            photo: val,
+            caption: None,
+            caption_entities: vec![],
+            media_group_id: None,
+        }))
+    }
+
+    /// A native poll.
+    pub fn poll(self, val: Poll) -> Self {
+        self.media(MediaKind::Poll(MediaPoll { poll: val }))
+    }
+
+    /// A sticker.
+    pub fn sticker(self, val: Sticker) -> Self {
+        self.media(MediaKind::Sticker(MediaSticker { sticker: val }))
+    }
+
+    /// A video.
+    pub fn video(self, val: Video) -> Self {
+        self.media(MediaKind::Video(MediaVideo {
+            video: val,
+            caption: None,
+            caption_entities: vec![],
+            media_group_id: None,
+        }))
+    }
+
+    /// A video note.
+    pub fn video_note(self, val: VideoNote) -> Self {
+        self.media(MediaKind::VideoNote(MediaVideoNote { video_note: val }))
+    }
+
+    /// A voice message.
+    pub fn voice(self, val: Voice) -> Self {
+        self.media(MediaKind::Voice(MediaVoice {
+            voice: val,
+            caption: None,
+            caption_entities: vec![],
+        }))
+    }
+
+    /// A venue.
+    pub fn venue(self, val: Venue) -> Self {
+        self.media(MediaKind::Venue(MediaVenue { venue: val }))
+    }
+
+    /// A shared topic.
+    pub fn topic_share(self, val: TopicShare) -> Self {
+        self.media(MediaKind::TopicSahre(MediaTopicSahre { topic_share: val }))
+    }
+
+    /// A shared circle post.
+    pub fn circle_share_entity(self, val: CircleShareEntity) -> Self {
+        self.media(MediaKind::CircleShareEntity(MediaCircleShareEntity {
+            circle_share_entity: val,
+        }))
+    }
+
+    /// A new circle post.
+    pub fn circle_post(self, val: CirclePost) -> Self {
+        self.media(MediaKind::CirclePost(MediaCirclePost { circle_post: val }))
+    }
+
+    /// A new comment under a circle post.
+    pub fn circle_comment(self, val: CircleComment) -> Self {
+        self.media(MediaKind::CircleComment(MediaCircleComment {
+            circle_comment: val,
+        }))
+    }
+
+    /// A like of a circle post or comment.
+    pub fn circle_like(self, val: CircleLike) -> Self {
+        self.media(MediaKind::CircleLike(MediaCircleLike { circle_like: val }))
+    }
+
+    /// A rich text message.
+    pub fn rich_text(self, val: RichText) -> Self {
+        self.media(MediaKind::RichText(MediaRichText { rich_text: val }))
+    }
+
+    /// New members were added to the chat.
+    pub fn new_chat_members(self, val: Vec<User>) -> Self {
+        self.kind(MessageKind::NewChatMembers(MessageNewChatMembers {
+            new_chat_members: val,
+        }))
+    }
+
+    /// A member was removed from the chat.
+    pub fn left_chat_member(self, val: User) -> Self {
+        self.kind(MessageKind::LeftChatMember(MessageLeftChatMember {
+            left_chat_member: val,
+        }))
+    }
+
+    /// The chat title was changed.
+    pub fn new_chat_title<S>(self, val: S) -> Self
+    where
+        S: Into<String>,
+    {
+        self.kind(MessageKind::NewChatTitle(MessageNewChatTitle {
+            new_chat_title: val.into(),
+        }))
+    }
+
+    /// The chat photo was changed.
+    pub fn new_chat_photo(self, val: Vec<PhotoSize>) -> Self {
+        self.kind(MessageKind::NewChatPhoto(MessageNewChatPhoto {
+            new_chat_photo: val,
+        }))
+    }
+
+    /// The chat photo was deleted.
+    pub fn delete_chat_photo(self) -> Self {
+        self.kind(MessageKind::DeleteChatPhoto(MessageDeleteChatPhoto {
+            delete_chat_photo: True,
+        }))
+    }
+
+    /// The group has been created.
+    pub fn group_chat_created(self) -> Self {
+        self.kind(MessageKind::GroupChatCreated(MessageGroupChatCreated {
+            group_chat_created: True,
+        }))
+    }
+
+    /// The supergroup has been created.
+    pub fn supergroup_chat_created(self) -> Self {
+        self.kind(MessageKind::SupergroupChatCreated(
+            MessageSupergroupChatCreated { supergroup_chat_created: True },
+        ))
+    }
+
+    /// The channel has been created.
+    pub fn channel_chat_created(self) -> Self {
+        self.kind(MessageKind::ChannelChatCreated(MessageChannelChatCreated {
+            channel_chat_created: True,
+        }))
+    }
+
+    /// The group has been migrated to a supergroup.
+    pub fn migrate(
+        self,
+        migrate_from_chat_id: i64,
+        migrate_to_chat_id: i64,
+    ) -> Self {
+        self.kind(MessageKind::Migrate(MessageMigrate {
+            migrate_to_chat_id,
+            migrate_from_chat_id,
+        }))
+    }
+
+    /// The message `message_id` of the same chat was pinned.
+    pub fn pinned(mut self, message_id: i64) -> Self {
+        self.content = Content::Pinned(message_id);
+        self
+    }
+
+    /// An invoice for a payment.
+    pub fn invoice(self, val: Invoice) -> Self {
+        self.kind(MessageKind::Invoice(MessageInvoice { invoice: val }))
+    }
+
+    /// A successful payment.
+    pub fn successful_payment(self, val: SuccessfulPayment) -> Self {
+        self.kind(MessageKind::SuccessfulPayment(MessageSuccessfulPayment {
+            successful_payment: val,
+        }))
+    }
+
+    /// The user logged in on the domain `val`.
+    pub fn connected_website<S>(self, val: S) -> Self
+    where
+        S: Into<String>,
+    {
+        self.kind(MessageKind::ConnectedWebsite(MessageConnectedWebsite {
+            connected_website: val.into(),
+        }))
+    }
+
+    /// Telegram Passport data.
+    pub fn passport_data(self, val: PassportData) -> Self {
+        self.kind(MessageKind::PassportData(MessagePassportData {
+            passport_data: val,
+        }))
+    }
+
+    /// Members of the chat went online.
+    pub fn chat_members_online(self, val: Vec<User>) -> Self {
+        self.kind(MessageKind::ChatMembersOnline(MessageChatMembersOnline {
+            chat_members_online: val,
+        }))
+    }
+
+    /// Members of the chat went offline.
+    pub fn chat_members_offline(self, val: Vec<User>) -> Self {
+        self.kind(MessageKind::ChatMembersOffline(MessageChatMembersOffline {
+            chat_members_offline: val,
+        }))
+    }
+
+    /// The sender reacted with `emoji` to the message `message_id` of the
+    /// same chat. `action` is either `add` or `del`.
+    pub fn reaction<A, E>(
+        mut self,
+        message_id: i64,
+        action: A,
+        emoji: E,
+    ) -> Self
+    where
+        A: Into<String>,
+        E: Into<String>,
+    {
+        self.content = Content::Reaction {
+            message_id,
+            action: action.into(),
+            emoji: emoji.into(),
+        };
+        self
+    }
+
+    /// Builds the message alone.
+    pub fn build_message(self) -> Message {
+        let (chat, date) = (self.chat, self.date);
+        let stub = |message_id| Box::new(stub_message(message_id, &chat, date));
+
+        let kind = match self.content {
+            Content::Media(media_kind) => MessageKind::Common(MessageCommon {
+                from: self.from,
+                forward_kind: ForwardKind::Origin(ForwardOrigin {
+                    reply_to_message: self.reply_to.map(|reply_to| {
+                        match reply_to {
+                            ReplyTo::Id(id) => stub(id),
+                            ReplyTo::Message(message) => message,
+                        }
+                    }),
+                }),
+                edit_date: self.edit_date,
+                media_kind: with_extras(
+                    media_kind,
+                    self.caption,
+                    self.media_group_id,
+                ),
+                reply_markup: self.reply_markup,
+            }),
+            Content::Service(kind) => kind,
+            Content::Pinned(message_id) => {
+                MessageKind::Pinned(MessagePinned { pinned: stub(message_id) })
+            }
+            Content::Reaction { message_id, action, emoji } => {
+                MessageKind::Reaction(Reaction {
+                    from: self.from,
+                    reaction: MessageReaction {
+                        reaction_to_message: stub(message_id),
+                        action,
+                        emoji,
+                    },
+                })
+            }
+        };
+
+        Message { id: self.message_id, date, chat, kind }
+    }
+
+    /// Builds the update kind alone.
+    pub fn build_kind(self) -> UpdateKind {
+        let update_kind = self.update_kind;
+        update_kind(self.build_message())
+    }
+
+    /// Builds the update.
+    pub fn build(self) -> Update {
+        let id = self.update_id;
+        Update { id, kind: self.build_kind() }
+    }
+}
+
+fn private_chat(guild_id: i64, channel_id: i64) -> Chat {
+    Chat {
+        id: channel_id,
+        guild_id,
+        kind: ChatKind::Private(ChatPrivate {
+            type_: (),
+            username: None,
+            first_name: None,
+            last_name: None,
+        }),
+        photo: None,
+    }
+}
+
+/// A message of `chat` known only by its identifier.
+fn stub_message(message_id: i64, chat: &Chat, date: i64) -> Message {
+    match chat.kind {
+        ChatKind::Public(_) => Message::new_public_without_kind(
+            message_id,
+            chat.id,
+            chat.guild_id,
+            date,
+        ),
+        ChatKind::Private(_) => Message::new_private_without_kind(
+            message_id,
+            chat.id,
+            chat.guild_id,
+            date,
+        ),
+    }
+}
+
+fn with_extras(
+    media_kind: MediaKind,
+    caption: Option<(String, Vec<MessageEntity>)>,
+    media_group_id: Option<String>,
+) -> MediaKind {
+    let (caption, caption_entities) = match caption {
+        Some((caption, entities)) => (Some(caption), entities),
+        None => (None, vec![]),
+    };
+
+    match media_kind {
+        MediaKind::Animation(media) => MediaKind::Animation(MediaAnimation {
+            caption,
+            caption_entities,
+            ..media
+        }),
+        MediaKind::Audio(media) => {
+            MediaKind::Audio(MediaAudio { caption, caption_entities, ..media })
+        }
+        MediaKind::Document(media) => MediaKind::Document(MediaDocument {
+            caption,
+            caption_entities,
+            ..media
+        }),
+        MediaKind::Photo(media) => MediaKind::Photo(MediaPhoto {
+            caption,
+            caption_entities,
+            media_group_id,
+            ..media
+        }),
+        MediaKind::Video(media) => MediaKind::Video(MediaVideo {
+            caption,
+            caption_entities,
+            media_group_id,
+            ..media
+        }),
+        MediaKind::Voice(media) => {
+            MediaKind::Voice(MediaVoice { caption, caption_entities, ..media })
+        }
+        other => other,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn user() -> User {
+        User { id: 1, username: Some("ann".to_owned()), ..User::default() }
+    }
+
+    #[test]
+    fn text_channel_post() {
+        let update = UpdateBuilder::channel_post()
+            .id(7)
+            .message_id(42)
+            .date(1000)
+            .from(user())
+            .in_channel(100, 200)
+            .reply_to_id(41)
+            .text("hello")
+            .build();
+
+        assert_eq!(update.id, 7);
+        let message = match update.kind {
+            UpdateKind::ChannelPost(message) => message,
+            other => panic!("Expected a channel post, got {:?}", other),
+        };
+        assert_eq!(message.id, 42);
+        assert_eq!(message.chat.guild_id, 100);
+        assert_eq!(message.chat.id, 200);
+        assert!(message.chat.is_channel());
+        assert_eq!(message.text(), Some("hello"));
+        assert_eq!(message.from(), Some(&user()));
+        assert_eq!(message.reply_to_message().map(|m| m.id), Some(41));
+    }
+
+    #[test]
+    fn caption_is_applied_to_media() {
+        let message = UpdateBuilder::message()
+            .caption("A voice")
+            .voice(Voice {
+                file_id: "id".to_owned(),
+                file_unique_id: "id".to_owned(),
+                duration: 3,
+                mime_type: None,
+                file_size: None,
+            })
+            .build_message();
+
+        assert_eq!(message.caption(), Some("A voice"));
+    }
+
+    #[test]
+    fn service_messages() {
+        let message = UpdateBuilder::channel_post()
+            .in_group(1, 2)
+            .from(user())
+            .reaction(10, "add", "👍")
+            .build_message();
+
+        match message.kind {
+            MessageKind::Reaction(Reaction { from, reaction }) => {
+                assert_eq!(from, Some(user()));
+                assert_eq!(reaction.reaction_to_message.id, 10);
+                assert_eq!(reaction.reaction_to_message.chat.id, 2);
+                assert_eq!(reaction.emoji, "👍");
+            }
+            other => panic!("Expected a reaction, got {:?}", other),
+        }
+
+        let message = UpdateBuilder::message()
+            .new_chat_members(vec![user()])
+            .build_message();
+        assert_eq!(message.new_chat_members(), Some(&[user()][..]));
+    }
+}
@@ -0,0 +1,680 @@
+//! Typed command arguments.
+//!
+//! [`BotCommand::parse`] splits arguments on whitespace and leaves them as
+//! strings. [`Args`] instead reads them one by one, honouring quotes, and
+//! converts them into types implementing [`FromArgs`]:
+//!
+//! ```
+//! use std::time::Duration;
+//! use teloxide::utils::command::{Args, Rest};
+//!
+//! let (id, duration, Rest(reason)): (i64, Duration, Rest) =
+//!     Args::new(r#"42 1h30m "spam links" again"#).extract().unwrap();
+//!
+//! assert_eq!(id, 42);
+//! assert_eq!(duration, Duration::from_secs(90 * 60));
+//! assert_eq!(reason, r#""spam links" again"#);
+//! ```
+//!
+//! A failure is reported as a [`ParseError`], which can be shown to the user
+//! along with the descriptions of the commands:
+//!
+//! ```
+//! use teloxide::utils::command::{Args, BotCommand};
+//!
+//! #[derive(BotCommand)]
+//! #[command(rename = "lowercase")]
+//! enum Command {
+//!     #[command(description = "ban a user.")]
+//!     Ban,
+//! }
+//!
+//! let error = Args::new("soon").extract::<i64>().unwrap_err();
+//! assert_eq!(
+//!     error.with_usage::<Command>(),
+//!     "Invalid argument 1 `soon`: expected an integer.\n\n/ban - ban a \
+//!      user.\n"
+//! );
+//! ```
+//!
+//! [`BotCommand::parse`]: crate::utils::command::BotCommand::parse
+
+use std::{str::FromStr, time::Duration};
+
+use thiserror::Error;
+
+use crate::{
+    types::{Message, MessageEntity, MessageEntityKind, User},
+    utils::command::{BotCommand, CommandSyntax},
+};
+
+/// An error produced while parsing command arguments.
+///
+/// Argument positions are 1-based.
+#[derive(Clone, Debug, Eq, Hash, PartialEq, Error)]
+pub enum ParseError {
+    /// An argument is missing.
+    #[error("Missing argument {position}: expected {expected}.")]
+    MissingArgument { position: usize, expected: String },
+
+    /// An argument has a wrong format.
+    #[error("Invalid argument {position} `{value}`: expected {expected}.")]
+    InvalidArgument { position: usize, value: String, expected: String },
+
+    /// There are more arguments than a command takes.
+    #[error("Unexpected argument {position} `{value}`.")]
+    UnexpectedArgument { position: usize, value: String },
+
+    /// A quoted argument has no closing quote.
+    #[error("Argument {position} has no closing quote.")]
+    UnterminatedQuote { position: usize },
+}
+
+impl ParseError {
+    /// Renders this error followed by [`BotCommand::descriptions`] of `C`,
+    /// ready to be sent back to the user.
+    ///
+    /// [`BotCommand::descriptions`]: crate::utils::command::BotCommand::descriptions
+    pub fn with_usage<C>(&self) -> String
+    where
+        C: BotCommand,
+    {
+        format!("{}\n\n{}", self, C::descriptions())
+    }
+}
+
+/// Arguments of a command, read one by one.
+///
+/// Arguments are separated by whitespace. An argument starting with `"` or
+/// `'` lasts until the same closing quote and may contain whitespace; inside
+/// it, `\` escapes the quote and itself.
+#[derive(Clone, Debug)]
+pub struct Args<'a> {
+    text: &'a str,
+    entities: &'a [MessageEntity],
+    pos: usize,
+    position: usize,
+}
+
+impl<'a> Args<'a> {
+    /// Arguments written in `text`.
+    pub fn new(text: &'a str) -> Self {
+        Self { text, entities: &[], pos: 0, position: 0 }
+    }
+
+    /// Finds the first command of `C` written in `syntax` in the text (or
+    /// the caption) of `message`, like [`BotCommand::parse_with`], and
+    /// returns it along with its arguments.
+    ///
+    /// Unlike [`Args::new`], this makes entities of the message, such as
+    /// text mentions, available to [`FromArgs`] implementations.
+    ///
+    /// The arguments found by `syntax` must be slices of the text, as with
+    /// the syntaxes of this module. Otherwise, `None` is returned.
+    ///
+    /// [`BotCommand::parse_with`]: crate::utils::command::BotCommand::parse_with
+    /// [`Args::new`]: crate::utils::command::Args::new
+    /// [`FromArgs`]: crate::utils::command::FromArgs
+    pub fn from_message<C, N, S>(
+        message: &'a Message,
+        bot_name: N,
+        syntax: &S,
+    ) -> Option<(C, Self)>
+    where
+        C: BotCommand,
+        N: AsRef<str>,
+        S: CommandSyntax + ?Sized,
+    {
+        let (text, entities) = match message.text() {
+            Some(text) => (text, message.entities()),
+            None => (
+                message.caption().unwrap_or_default(),
+                message.caption_entities(),
+            ),
+        };
+        let (command, words) = C::parse_with(text, bot_name, syntax)?;
+
+        // The arguments span from the first word to the last one, so quoted
+        // arguments keep their whitespace.
+        let offset = |word: &str| {
+            let start =
+                (word.as_ptr() as usize).checked_sub(text.as_ptr() as usize)?;
+            Some(start).filter(|start| start + word.len() <= text.len())
+        };
+        let (start, end) = match (words.first(), words.last()) {
+            (Some(first), Some(last)) => {
+                (offset(first)?, offset(last)? + last.len())
+            }
+            _ => (text.len(), text.len()),
+        };
+
+        let mut args = Self::new(&text[..end]);
+        args.entities = entities.unwrap_or_default();
+        args.pos = start;
+        Some((command, args))
+    }
+
+    /// Reads all the arguments as `T`.
+    ///
+    /// Fails with [`ParseError::UnexpectedArgument`] if `T` doesn't consume
+    /// all of them.
+    ///
+    /// [`ParseError::UnexpectedArgument`]: crate::utils::command::ParseError::UnexpectedArgument
+    pub fn extract<T>(mut self) -> Result<T, ParseError>
+    where
+        T: FromArgs,
+    {
+        let value = T::from_args(&mut self)?;
+        match self.next_arg()? {
+            Some(arg) => Err(ParseError::UnexpectedArgument {
+                position: arg.position,
+                value: arg.value,
+            }),
+            None => Ok(value),
+        }
+    }
+
+    /// Returns `true` if there are no more arguments.
+    pub fn is_empty(&self) -> bool {
+        self.text[self.pos..].trim().is_empty()
+    }
+
+    /// Reads the next argument, failing with
+    /// [`ParseError::MissingArgument`] if there is none.
+    ///
+    /// `expected` describes the argument in the error, e.g. `"an integer"`.
+    ///
+    /// [`ParseError::MissingArgument`]: crate::utils::command::ParseError::MissingArgument
+    pub fn next(&mut self, expected: &str) -> Result<Arg<'a>, ParseError> {
+        self.next_arg()?.ok_or_else(|| ParseError::MissingArgument {
+            position: self.position + 1,
+            expected: expected.to_owned(),
+        })
+    }
+
+    /// Returns all the remaining text as is, without splitting it into
+    /// arguments.
+    pub fn rest(&mut self) -> &'a str {
+        let rest = self.text[self.pos..].trim();
+        self.pos = self.text.len();
+        if !rest.is_empty() {
+            self.position += 1;
+        }
+        rest
+    }
+
+    fn next_arg(&mut self) -> Result<Option<Arg<'a>>, ParseError> {
+        self.skip_whitespace();
+        let start = self.pos;
+        let mut chars = self.text[start..].char_indices().peekable();
+        let quote = match chars.peek() {
+            None => return Ok(None),
+            Some(&(_, c)) if c == '"' || c == '\'' => {
+                chars.next();
+                Some(c)
+            }
+            Some(_) => None,
+        };
+        self.position += 1;
+
+        let mut value = String::new();
+        let mut end = None;
+        while let Some((i, c)) = chars.next() {
+            match quote {
+                Some(q) if c == q => {
+                    end = Some(start + i + c.len_utf8());
+                    break;
+                }
+                Some(q) if c == '\\' => match chars.peek() {
+                    Some(&(_, next)) if next == q || next == '\\' => {
+                        value.push(next);
+                        chars.next();
+                    }
+                    _ => value.push(c),
+                },
+                None if c.is_whitespace() => {
+                    end = Some(start + i);
+                    break;
+                }
+                _ => value.push(c),
+            }
+        }
+
+        let end = match end {
+            Some(end) => end,
+            None if quote.is_some() => {
+                return Err(ParseError::UnterminatedQuote {
+                    position: self.position,
+                })
+            }
+            None => self.text.len(),
+        };
+        self.pos = end;
+
+        Ok(Some(Arg {
+            value,
+            position: self.position,
+            entity: self.entity_at(start, end),
+        }))
+    }
+
+    fn skip_whitespace(&mut self) {
+        let rest = &self.text[self.pos..];
+        self.pos += rest.len() - rest.trim_start().len();
+    }
+
+    /// Finds an entity spanning exactly the bytes `start..end` of the text.
+    fn entity_at(&self, start: usize, end: usize) -> Option<&'a MessageEntity> {
+        if self.entities.is_empty() {
+            return None;
+        }
+
+        let offset = self.text[..start].encode_utf16().count();
+        let length = self.text[start..end].encode_utf16().count();
+        self.entities.iter().find(|e| e.offset == offset && e.length == length)
+    }
+}
+
+/// A single command argument.
+#[derive(Clone, Debug)]
+pub struct Arg<'a> {
+    value: String,
+    position: usize,
+    entity: Option<&'a MessageEntity>,
+}
+
+impl<'a> Arg<'a> {
+    /// The argument, without quotes.
+    pub fn value(&self) -> &str {
+        &self.value
+    }
+
+    /// The 1-based position of the argument.
+    pub fn position(&self) -> usize {
+        self.position
+    }
+
+    /// An entity of the message covering exactly this argument.
+    pub fn entity(&self) -> Option<&'a MessageEntity> {
+        self.entity
+    }
+
+    /// Parses the argument using [`FromStr`].
+    ///
+    /// [`FromStr`]: std::str::FromStr
+    pub fn parse<T>(&self, expected: &str) -> Result<T, ParseError>
+    where
+        T: FromStr,
+    {
+        self.value.parse().map_err(|_| self.invalid(expected))
+    }
+
+    /// Returns the value paired with the argument (compared
+    /// case-insensitively).
+    ///
+    /// This is handy for enums:
+    /// ```
+    /// use teloxide::utils::command::{Args, FromArgs, ParseError};
+    ///
+    /// #[derive(Copy, Clone, Debug, PartialEq)]
+    /// enum Scope {
+    ///     Chat,
+    ///     Guild,
+    /// }
+    ///
+    /// impl FromArgs for Scope {
+    ///     fn from_args(args: &mut Args<'_>) -> Result<Self, ParseError> {
+    ///         args.next("a scope")?
+    ///             .choice(&[("chat", Scope::Chat), ("guild", Scope::Guild)])
+    ///     }
+    /// }
+    ///
+    /// assert_eq!(Args::new("Guild").extract(), Ok(Scope::Guild));
+    /// assert_eq!(
+    ///     Args::new("world").extract::<Scope>().unwrap_err().to_string(),
+    ///     "Invalid argument 1 `world`: expected one of chat, guild."
+    /// );
+    /// ```
+    pub fn choice<T>(&self, choices: &[(&str, T)]) -> Result<T, ParseError>
+    where
+        T: Clone,
+    {
+        choices
+            .iter()
+            .find(|(name, _)| name.eq_ignore_ascii_case(&self.value))
+            .map(|(_, value)| value.clone())
+            .ok_or_else(|| {
+                let names: Vec<_> =
+                    choices.iter().map(|(name, _)| *name).collect();
+                self.invalid(&format!("one of {}", names.join(", ")))
+            })
+    }
+
+    /// Creates [`ParseError::InvalidArgument`] for this argument.
+    ///
+    /// [`ParseError::InvalidArgument`]: crate::utils::command::ParseError::InvalidArgument
+    pub fn invalid(&self, expected: &str) -> ParseError {
+        ParseError::InvalidArgument {
+            position: self.position,
+            value: self.value.clone(),
+            expected: expected.to_owned(),
+        }
+    }
+}
+
+/// A type which can be read from command arguments.
+///
+/// Tuples of such types read their elements one after another, [`Option`]
+/// reads a value if there are arguments left and [`Vec`] reads all the
+/// remaining arguments.
+///
+/// [`Option`]: std::option::Option
+/// [`Vec`]: std::vec::Vec
+pub trait FromArgs: Sized {
+    fn from_args(args: &mut Args<'_>) -> Result<Self, ParseError>;
+}
+
+/// All the remaining text of a command, not split into arguments.
+#[derive(Clone, Debug, Default, Eq, Hash, PartialEq)]
+pub struct Rest(pub String);
+
+impl FromArgs for Rest {
+    fn from_args(args: &mut Args<'_>) -> Result<Self, ParseError> {
+        Ok(Rest(args.rest().to_owned()))
+    }
+}
+
+impl FromArgs for String {
+    fn from_args(args: &mut Args<'_>) -> Result<Self, ParseError> {
+        Ok(args.next("text")?.value)
+    }
+}
+
+macro_rules! impl_from_args_via_from_str {
+    ($expected:literal => $($ty:ty),*) => {
+        $(
+            impl FromArgs for $ty {
+                fn from_args(
+                    args: &mut Args<'_>,
+                ) -> Result<Self, ParseError> {
+                    args.next($expected)?.parse($expected)
+                }
+            }
+        )*
+    };
+}
+
+impl_from_args_via_from_str!(
+    "an integer" => i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128,
+    usize
+);
+impl_from_args_via_from_str!("a number" => f32, f64);
+
+impl FromArgs for bool {
+    fn from_args(args: &mut Args<'_>) -> Result<Self, ParseError> {
+        args.next("yes or no")?.choice(&[
+            ("yes", true),
+            ("no", false),
+            ("true", true),
+            ("false", false),
+            ("on", true),
+            ("off", false),
+        ])
+    }
+}
+
+/// Reads a user from a [text mention] of them.
+///
+/// [text mention]: crate::types::MessageEntityKind::TextMention
+impl FromArgs for User {
+    fn from_args(args: &mut Args<'_>) -> Result<Self, ParseError> {
+        const EXPECTED: &str = "a user mention";
+
+        let arg = args.next(EXPECTED)?;
+        match arg.entity() {
+            Some(MessageEntity {
+                kind: MessageEntityKind::TextMention { user },
+                ..
+            }) => Ok(user.clone()),
+            _ => Err(arg.invalid(EXPECTED)),
+        }
+    }
+}
+
+/// Reads a duration such as `90`, `45s`, `10m`, `1h30m`, `2d` or `1w`. A
+/// number without a unit is a number of seconds.
+impl FromArgs for Duration {
+    fn from_args(args: &mut Args<'_>) -> Result<Self, ParseError> {
+        const EXPECTED: &str = "a duration, e.g. 45s, 10m, 1h30m or 2d";
+
+        let arg = args.next(EXPECTED)?;
+        parse_duration(arg.value()).ok_or_else(|| arg.invalid(EXPECTED))
+    }
+}
+
+fn parse_duration(s: &str) -> Option<Duration> {
+    if s.is_empty() {
+        return None;
+    }
+    if let Ok(secs) = s.parse() {
+        return Some(Duration::from_secs(secs));
+    }
+
+    let mut secs = 0u64;
+    let mut rest = s;
+    while !rest.is_empty() {
+        let digits = rest.find(|c: char| !c.is_ascii_digit())?;
+        let number: u64 = rest[..digits].parse().ok()?;
+        let mut unit_chars = rest[digits..].chars();
+        let unit = match unit_chars.next()?.to_ascii_lowercase() {
+            's' => 1,
+            'm' => 60,
+            'h' => 60 * 60,
+            'd' => 24 * 60 * 60,
+            'w' => 7 * 24 * 60 * 60,
+            _ => return None,
+        };
+        secs = secs.checked_add(number.checked_mul(unit)?)?;
+        rest = unit_chars.as_str();
+    }
+    Some(Duration::from_secs(secs))
+}
+
+impl<T> FromArgs for Option<T>
+where
+    T: FromArgs,
+{
+    fn from_args(args: &mut Args<'_>) -> Result<Self, ParseError> {
+        if args.is_empty() {
+            Ok(None)
+        } else {
+            T::from_args(args).map(Some)
+        }
+    }
+}
+
+impl<T> FromArgs for Vec<T>
+where
+    T: FromArgs,
+{
+    fn from_args(args: &mut Args<'_>) -> Result<Self, ParseError> {
+        let mut values = Vec::new();
+        while !args.is_empty() {
+            values.push(T::from_args(args)?);
+        }
+        Ok(values)
+    }
+}
+
+impl FromArgs for () {
+    fn from_args(_: &mut Args<'_>) -> Result<Self, ParseError> {
+        Ok(())
+    }
+}
+
+macro_rules! impl_from_args_for_tuple {
+    ($($ty:ident),+) => {
+        impl<$($ty),+> FromArgs for ($($ty,)+)
+        where
+            $($ty: FromArgs,)+
+        {
+            fn from_args(args: &mut Args<'_>) -> Result<Self, ParseError> {
+                Ok(($($ty::from_args(args)?,)+))
+            }
+        }
+    };
+}
+
+impl_from_args_for_tuple!(A);
+impl_from_args_for_tuple!(A, B);
+impl_from_args_for_tuple!(A, B, C);
+impl_from_args_for_tuple!(A, B, C, D);
+impl_from_args_for_tuple!(A, B, C, D, E);
+impl_from_args_for_tuple!(A, B, C, D, E, F);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        types::UpdateBuilder,
+        utils::command::{InlineSyntax, LeadingSyntax},
+    };
+
+    #[derive(BotCommand, Debug, PartialEq)]
+    #[command(rename = "lowercase")]
+    enum Command {
+        Ban,
+        Kick,
+    }
+
+    #[test]
+    fn quotes() {
+        let args: Vec<String> =
+            Args::new(r#"plain "two words" 'single' "esc\"aped\\" don't"#)
+                .extract()
+                .unwrap();
+
+        assert_eq!(
+            args,
+            vec!["plain", "two words", "single", r#"esc"aped\"#, "don't"]
+        );
+    }
+
+    #[test]
+    fn unterminated_quote() {
+        assert_eq!(
+            Args::new(r#"1 "open"#).extract::<(i64, String)>(),
+            Err(ParseError::UnterminatedQuote { position: 2 })
+        );
+    }
+
+    #[test]
+    fn errors() {
+        assert_eq!(
+            Args::new("1").extract::<(i64, String)>(),
+            Err(ParseError::MissingArgument {
+                position: 2,
+                expected: "text".to_owned()
+            })
+        );
+        assert_eq!(
+            Args::new("1 x").extract::<(i64, i64)>(),
+            Err(ParseError::InvalidArgument {
+                position: 2,
+                value: "x".to_owned(),
+                expected: "an integer".to_owned()
+            })
+        );
+        assert_eq!(
+            Args::new("1 2").extract::<i64>(),
+            Err(ParseError::UnexpectedArgument {
+                position: 2,
+                value: "2".to_owned()
+            })
+        );
+    }
+
+    #[test]
+    fn optional_and_rest() {
+        assert_eq!(
+            Args::new("5").extract::<(i64, Option<String>)>(),
+            Ok((5, None))
+        );
+        assert_eq!(
+            Args::new("5 on  the  rest ").extract::<(i64, bool, Rest)>(),
+            Ok((5, true, Rest("the  rest".to_owned())))
+        );
+        assert_eq!(Args::new("").extract::<Rest>(), Ok(Rest::default()));
+    }
+
+    #[test]
+    fn durations() {
+        assert_eq!(parse_duration("90"), Some(Duration::from_secs(90)));
+        assert_eq!(parse_duration("45s"), Some(Duration::from_secs(45)));
+        assert_eq!(parse_duration("1h30m"), Some(Duration::from_secs(5400)));
+        assert_eq!(parse_duration("2D"), Some(Duration::from_secs(172_800)));
+        assert_eq!(parse_duration("1w"), Some(Duration::from_secs(604_800)));
+        assert_eq!(parse_duration("5"), Some(Duration::from_secs(5)));
+        assert_eq!(parse_duration(""), None);
+        assert_eq!(parse_duration("h"), None);
+        assert_eq!(parse_duration("5x"), None);
+        assert_eq!(parse_duration("1h30"), None);
+    }
+
+    #[test]
+    fn user_from_text_mention() {
+        let user =
+            User {
+                id: 7, first_name: "Анна".to_owned(), ..User::default()
+            };
+        let text = "/ban Анна 1h";
+        let message = UpdateBuilder::message()
+            .text_with_entities(
+                text,
+                vec![MessageEntity {
+                    kind: MessageEntityKind::TextMention { user: user.clone() },
+                    offset: 5,
+                    length: 4,
+                }],
+            )
+            .build_message();
+
+        let (command, args) =
+            Args::from_message::<Command, _, _>(&message, "", &LeadingSyntax)
+                .unwrap();
+        assert_eq!(command, Command::Ban);
+        assert_eq!(
+            args.extract::<(User, Duration)>(),
+            Ok((user, Duration::from_secs(3600)))
+        );
+        assert_eq!(
+            Args::new("Анна").extract::<User>(),
+            Err(ParseError::InvalidArgument {
+                position: 1,
+                value: "Анна".to_owned(),
+                expected: "a user mention".to_owned()
+            })
+        );
+    }
+
+    #[test]
+    fn args_of_inline_command() {
+        let text = r#"Please ${/kick "too loud" 1h} now, ${/ban 2}"#;
+        let message = UpdateBuilder::message().text(text).build_message();
+
+        let (command, args) =
+            Args::from_message::<Command, _, _>(&message, "", &InlineSyntax)
+                .unwrap();
+        assert_eq!(command, Command::Kick);
+        assert_eq!(
+            args.extract::<(String, Duration)>(),
+            Ok(("too loud".to_owned(), Duration::from_secs(3600)))
+        );
+
+        let message = UpdateBuilder::message().text("${/ban}").build_message();
+        let (_, args) =
+            Args::from_message::<Command, _, _>(&message, "", &InlineSyntax)
+                .unwrap();
+        assert!(args.is_empty());
+    }
+}
@@ -0,0 +1,725 @@
+//! Command parsers.
+//!
+//! You can either create an `enum`, containing commands of your bot, or use
+//! functions, which split input text into a string command with its arguments.
+//!
+//! ## Examples
+//! Using `enum`:
+//! ```
+//! use teloxide::utils::command::BotCommand;
+//!
+//! #[derive(BotCommand, PartialEq, Debug)]
+//! #[command(rename = "lowercase")]
+//! enum AdminCommand {
+//!     Kick,
+//!     Ban,
+//! }
+//!
+//! let (command, args) =
+//!     AdminCommand::parse("/ban 3 hours", "MyBotName").unwrap();
+//! assert_eq!(command, AdminCommand::Ban);
+//! assert_eq!(args, vec!["3", "hours"]);
+//! ```
+//!
+//! Using [`parse_command`]:
+//! ```
+//! use teloxide::utils::command::parse_command;
+//!
+//! let (command, args) =
+//!     parse_command("/ban@MyBotName 3 hours", "MyBotName").unwrap();
+//! assert_eq!(command, "ban");
+//! assert_eq!(args, vec!["3", "hours"]);
+//! ```
+//!
+//! Using [`parse_command_with_prefix`]:
+//! ```
+//! use teloxide::utils::command::parse_command_with_prefix;
+//!
+//! let text = "!ban 3 hours";
+//! let (command, args) = parse_command_with_prefix("!", text, "").unwrap();
+//! assert_eq!(command, "ban");
+//! assert_eq!(args, vec!["3", "hours"]);
+//! ```
+//!
+//! If the name of a bot does not match, it will return `None`:
+//! ```
+//! use teloxide::utils::command::parse_command;
+//!
+//! let result = parse_command("/ban@MyNameBot1 3 hours", "MyNameBot2");
+//! assert!(result.is_none());
+//! ```
+//!
+//! Arguments can be converted into typed values, honouring quotes, with
+//! [`Args`].
+//!
+//! See [examples/admin_bot] as a more complicated examples.
+//!
+//! [`Args`]: crate::utils::command::Args
+//! [`parse_command`]: crate::utils::command::parse_command
+//! [`parse_command_with_prefix`]:
+//! crate::utils::command::parse_command_with_prefix
+//! [examples/admin_bot]: https://github.com/teloxide/teloxide/blob/master/examples/miltiple_handlers_bot/
+
+use std::{borrow::Cow, collections::HashMap, iter, mem};
+
+pub use args::{Arg, Args, FromArgs, ParseError, Rest};
+pub use teloxide_macros::BotCommand;
+
+mod args;
+
+/// An enumeration of bot's commands.
+///
+/// ## Example
+/// ```
+/// use teloxide::utils::command::BotCommand;
+///
+/// #[derive(BotCommand, PartialEq, Debug)]
+/// #[command(rename = "lowercase")]
+/// enum AdminCommand {
+///     Mute,
+///     Ban,
+/// }
+///
+/// let (command, args) = AdminCommand::parse("/ban 5 h", "bot_name").unwrap();
+/// assert_eq!(command, AdminCommand::Ban);
+/// assert_eq!(args, vec!["5", "h"]);
+/// ```
+///
+/// ## Enum attributes
+///  1. `#[command(rename = "rule")]`
+/// Rename all commands by rule. Allowed rules are `lowercase`. If you will not
+/// use this attribute, commands will be parsed by their original names.
+///
+///  2. `#[command(prefix = "prefix")]`
+/// Change a prefix for all commands (the default is `/`).
+///
+///  3. `#[command(description = "description")]`
+/// Add a sumary description of commands before all commands.
+///
+/// ## Variant attributes
+///  1. `#[command(rename = "rule")]`
+/// Rename one command by a rule. Allowed rules are `lowercase`, `%some_name%`,
+/// where `%some_name%` is any string, a new name.
+///
+///  2. `#[command(prefix = "prefix")]`
+/// Change a prefix for one command (the default is `/`).
+///
+///  3. `#[command(description = "description")]`
+/// Add a description of one command.
+///
+/// All variant attributes overlap the `enum` attributes.
+///
+/// ## Command syntaxes
+/// [`parse`] is generated by the derive macro and only recognises a command
+/// at the start of a text, like `/ban 5 h`. [`parse_with`] is the supported
+/// way to parse commands: it accepts any [`CommandSyntax`], e.g. the
+/// `${/ban 5 h}` tokens which guild clients insert anywhere in a message, and
+/// [`default_syntaxes`] covers everything that clients send:
+/// ```
+/// use teloxide::utils::command::{default_syntaxes, BotCommand};
+///
+/// #[derive(BotCommand, PartialEq, Debug)]
+/// #[command(rename = "lowercase")]
+/// enum AdminCommand {
+///     Mute,
+///     Ban,
+/// }
+///
+/// let (command, args) = AdminCommand::parse_with(
+///     "Please ${/ban 5 h} him",
+///     "bot_name",
+///     &default_syntaxes(),
+/// )
+/// .unwrap();
+/// assert_eq!(command, AdminCommand::Ban);
+/// assert_eq!(args, vec!["5", "h"]);
+/// ```
+///
+/// [`parse`]: BotCommand::parse
+/// [`parse_with`]: BotCommand::parse_with
+/// [`CommandSyntax`]: crate::utils::command::CommandSyntax
+/// [`default_syntaxes`]: crate::utils::command::default_syntaxes
+pub trait BotCommand: Sized {
+    fn try_from(s: &str) -> Option<Self>;
+    fn descriptions() -> String;
+
+    /// Parses a command at the start of `s`, like `/ban 5 h`.
+    ///
+    /// This method is implemented by the derive macro, which doesn't know
+    /// about [`CommandSyntax`], so it can't recognise commands in other
+    /// syntaxes. Use [`parse_with`] with [`default_syntaxes`] instead.
+    ///
+    /// [`CommandSyntax`]: crate::utils::command::CommandSyntax
+    /// [`parse_with`]: BotCommand::parse_with
+    /// [`default_syntaxes`]: crate::utils::command::default_syntaxes
+    fn parse<N>(s: &str, bot_name: N) -> Option<(Self, Vec<&str>)>
+        where
+            N: Into<String>;
+
+    /// Parses the first command written in `s` in `syntax`.
+    ///
+    /// Commands which are written in the syntax, but aren't commands of this
+    /// enum, are skipped.
+    fn parse_with<'a, N, S>(
+        s: &'a str,
+        bot_name: N,
+        syntax: &S,
+    ) -> Option<(Self, Vec<&'a str>)>
+    where
+        N: AsRef<str>,
+        S: CommandSyntax + ?Sized,
+    {
+        syntax.find(s, bot_name.as_ref()).into_iter().find_map(
+            |(command, args)| Self::try_from(&command).map(|c| (c, args)),
+        )
+    }
+
+    /// Returns the commands with the `/` prefix along with their
+    /// descriptions, as expected by [`Bot::set_my_commands`].
+    ///
+    /// Commands with other prefixes can't be shown in the command menu, and
+    /// Telegram rejects commands without a description of 3-256 characters,
+    /// so such commands are skipped.
+    ///
+    /// ## Example
+    /// ```
+    /// use teloxide::{types, utils::command::BotCommand};
+    ///
+    /// #[derive(BotCommand)]
+    /// #[command(rename = "lowercase", description = "Commands:")]
+    /// enum Command {
+    ///     #[command(description = "ban a user - for a while.")]
+    ///     Ban,
+    ///     Help,
+    ///     #[command(prefix = "!", description = "not in the menu.")]
+    ///     Secret,
+    /// }
+    ///
+    /// assert_eq!(
+    ///     Command::bot_commands(),
+    ///     vec![types::BotCommand::new("ban", "ban a user - for a while.")]
+    /// );
+    /// ```
+    ///
+    /// [`Bot::set_my_commands`]: crate::Bot::set_my_commands
+    fn bot_commands() -> Vec<crate::types::BotCommand> {
+        // The derive macro exposes the commands only through
+        // `descriptions()`: the global description, if any, followed by a line
+        // `<prefix><name>` or `<prefix><name> - <description>` per variant.
+        // Descriptions may span several lines, which may start with commands
+        // too, so the entry of a command is the last line starting with it,
+        // and the lines up to the next entry continue its description.
+        let descriptions = Self::descriptions();
+        let lines: Vec<&str> = descriptions.lines().collect();
+
+        let mut entries = HashMap::new();
+        for (i, line) in lines.iter().enumerate() {
+            let command = line.split(' ').next().unwrap_or_default();
+            let rest = &line[command.len()..];
+            if !rest.is_empty() && !rest.starts_with(" - ") {
+                continue;
+            }
+            if let Some(command) = Self::try_from(command) {
+                entries.insert(mem::discriminant(&command), i);
+            }
+        }
+        let mut starts: Vec<_> = entries.values().copied().collect();
+        starts.sort_unstable();
+
+        starts
+            .iter()
+            .enumerate()
+            .filter_map(|(n, &start)| {
+                let end = starts.get(n + 1).copied().unwrap_or(lines.len());
+                let line = lines[start];
+                let command = line.split(' ').next()?;
+                let name = command.strip_prefix('/')?;
+                if !is_menu_command(name) {
+                    return None;
+                }
+                let first_line = line[command.len()..].strip_prefix(" - ")?;
+                let description = iter::once(first_line)
+                    .chain(lines[start + 1..end].iter().copied())
+                    .collect::<Vec<_>>()
+                    .join("\n");
+                if !(3..=256).contains(&description.chars().count()) {
+                    return None;
+                }
+                Some(crate::types::BotCommand::new(name, description))
+            })
+            .collect()
+    }
+}
+
+/// Whether `name` can be shown in the command menu: 1-32 lowercase Latin
+/// letters, digits and underscores.
+fn is_menu_command(name: &str) -> bool {
+    (1..=32).contains(&name.len())
+        && name.bytes().all(|b| {
+            b.is_ascii_lowercase() || b.is_ascii_digit() || b == b'_'
+        })
+}
+
+/// A way of writing commands in a message.
+///
+/// A syntax only locates commands and their arguments, while a
+/// [`BotCommand`] decides which of them are its commands. Several syntaxes
+/// can be combined into a `Vec<Box<dyn CommandSyntax>>`, which finds the
+/// commands of all of them.
+///
+/// [`BotCommand`]: crate::utils::command::BotCommand
+pub trait CommandSyntax: Send + Sync {
+    /// Returns all the commands written in `text`, with their prefixes
+    /// (`/ban`), followed by their arguments.
+    fn find<'a>(
+        &self,
+        text: &'a str,
+        bot_name: &str,
+    ) -> Vec<(Cow<'a, str>, Vec<&'a str>)>;
+}
+
+impl CommandSyntax for Vec<Box<dyn CommandSyntax>> {
+    fn find<'a>(
+        &self,
+        text: &'a str,
+        bot_name: &str,
+    ) -> Vec<(Cow<'a, str>, Vec<&'a str>)> {
+        self.iter().flat_map(|syntax| syntax.find(text, bot_name)).collect()
+    }
+}
+
+/// A command at the start of a message: `/ban@bot_name 5 h`.
+///
+/// The `@bot_name` part is optional, but if present, must match the name of
+/// the bot.
+#[derive(Copy, Clone, Debug, Default)]
+pub struct LeadingSyntax;
+
+impl CommandSyntax for LeadingSyntax {
+    fn find<'a>(
+        &self,
+        text: &'a str,
+        bot_name: &str,
+    ) -> Vec<(Cow<'a, str>, Vec<&'a str>)> {
+        parse_command_with_prefix("", text, bot_name)
+            .map(|(command, args)| (Cow::Borrowed(command), args))
+            .into_iter()
+            .collect()
+    }
+}
+
+/// Commands written as `${/ban 5 h}` tokens anywhere in a message, the way
+/// guild clients render them.
+#[derive(Copy, Clone, Debug, Default)]
+pub struct InlineSyntax;
+
+impl CommandSyntax for InlineSyntax {
+    fn find<'a>(
+        &self,
+        text: &'a str,
+        bot_name: &str,
+    ) -> Vec<(Cow<'a, str>, Vec<&'a str>)> {
+        let mut commands = Vec::new();
+        let mut rest = text;
+
+        while let Some(start) = rest.find("${/") {
+            let token = &rest[start + 2..];
+            let end = match token.find('}') {
+                Some(end) => end,
+                None => break,
+            };
+
+            if let Some((command, args)) =
+                parse_command_with_prefix("", &token[..end], bot_name)
+            {
+                commands.push((Cow::Borrowed(command), args));
+            }
+            rest = &token[end + 1..];
+        }
+
+        commands
+    }
+}
+
+/// A command following a mention of the bot at the start of a message:
+/// `@bot_name ban 5 h` or `@bot_name /ban 5 h`.
+///
+/// If the command is written without a prefix, the `/` prefix is assumed.
+#[derive(Clone, Debug, Default)]
+pub struct MentionSyntax {
+    mention: Option<String>,
+}
+
+impl MentionSyntax {
+    /// Recognises `@bot_name`, where `bot_name` is the name passed to
+    /// [`BotCommand::parse_with`].
+    ///
+    /// [`BotCommand::parse_with`]: crate::utils::command::BotCommand::parse_with
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Recognises `mention` instead of `@bot_name`, e.g. the `${@!123}` token
+    /// guild clients use to mention the user (or bot) with the identifier
+    /// `123`.
+    pub fn with_mention<S>(mention: S) -> Self
+    where
+        S: Into<String>,
+    {
+        Self { mention: Some(mention.into()) }
+    }
+}
+
+impl CommandSyntax for MentionSyntax {
+    fn find<'a>(
+        &self,
+        text: &'a str,
+        bot_name: &str,
+    ) -> Vec<(Cow<'a, str>, Vec<&'a str>)> {
+        let mention = match &self.mention {
+            Some(mention) => Cow::Borrowed(mention.as_str()),
+            None => Cow::Owned(format!("@{}", bot_name)),
+        };
+
+        let text = text.trim_start();
+        if !text.starts_with(mention.as_ref()) {
+            return vec![];
+        }
+        let rest = &text[mention.len()..];
+        // `@bot_name2` is not a mention of `@bot_name`.
+        if !rest.starts_with(char::is_whitespace) {
+            return vec![];
+        }
+
+        let mut words = rest.split_whitespace();
+        let command = match words.next() {
+            Some(command) if command.starts_with('/') => Cow::Borrowed(command),
+            Some(command) => Cow::Owned(format!("/{}", command)),
+            None => return vec![],
+        };
+        vec![(command, words.collect())]
+    }
+}
+
+/// Returns the syntaxes used by [`DispatcherHandlerRxExt::commands`]: a
+/// command at the start of a message ([`LeadingSyntax`]) and `${/...}`
+/// tokens ([`InlineSyntax`]).
+///
+/// [`DispatcherHandlerRxExt::commands`]: crate::dispatching::DispatcherHandlerRxExt::commands
+/// [`LeadingSyntax`]: crate::utils::command::LeadingSyntax
+/// [`InlineSyntax`]: crate::utils::command::InlineSyntax
+pub fn default_syntaxes() -> Vec<Box<dyn CommandSyntax>> {
+    vec![Box::new(LeadingSyntax), Box::new(InlineSyntax)]
+}
+
+/// Parses a string into a command with args.
+///
+/// It calls [`parse_command_with_prefix`] with the default prefix `/`.
+///
+/// ## Example
+/// ```
+/// use teloxide::utils::command::parse_command;
+///
+/// let text = "/mute@my_admin_bot 5 hours";
+/// let (command, args) = parse_command(text, "my_admin_bot").unwrap();
+/// assert_eq!(command, "mute");
+/// assert_eq!(args, vec!["5", "hours"]);
+/// ```
+///
+/// [`parse_command_with_prefix`]:
+/// crate::utils::command::parse_command_with_prefix
+pub fn parse_command<N>(text: &str, bot_name: N) -> Option<(&str, Vec<&str>)>
+    where
+        N: AsRef<str>,
+{
+    parse_command_with_prefix("/", text, bot_name)
+}
+
+/// Parses a string into a command with args (custom prefix).
+///
+/// `prefix`: symbols, which denote start of a command.
+///
+/// ## Example
+/// ```
+/// use teloxide::utils::command::parse_command_with_prefix;
+///
+/// let text = "!mute 5 hours";
+/// let (command, args) = parse_command_with_prefix("!", text, "").unwrap();
+/// assert_eq!(command, "mute");
+/// assert_eq!(args, vec!["5", "hours"]);
+/// ```
+pub fn parse_command_with_prefix<'a, N>(
+    prefix: &str,
+    text: &'a str,
+    bot_name: N,
+) -> Option<(&'a str, Vec<&'a str>)>
+    where
+        N: AsRef<str>,
+{
+    if !text.starts_with(prefix) {
+        return None;
+    }
+    let mut words = text.split_whitespace();
+    let mut splited = words.next()?[prefix.len()..].split('@');
+    let command = splited.next()?;
+    let bot = splited.next();
+    match bot {
+        Some(name) if name == bot_name.as_ref() => {}
+        None => {}
+        _ => return None,
+    }
+    Some((command, words.collect()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_command_with_args_() {
+        let data = "/command arg1 arg2";
+        let expected = Some(("command", vec!["arg1", "arg2"]));
+        let actual = parse_command(data, "");
+        assert_eq!(actual, expected)
+    }
+
+    #[test]
+    fn parse_command_with_args_without_args() {
+        let data = "/command";
+        let expected = Some(("command", vec![]));
+        let actual = parse_command(data, "");
+        assert_eq!(actual, expected)
+    }
+
+    #[test]
+    fn parse_command_with_args() {
+        #[command(rename = "lowercase")]
+        #[derive(BotCommand, Debug, PartialEq)]
+        enum DefaultCommands {
+            Start,
+            Help,
+        }
+
+        let data = "/start arg1 arg2";
+        let expected = Some((DefaultCommands::Start, vec!["arg1", "arg2"]));
+        let actual = DefaultCommands::parse(data, "");
+        assert_eq!(actual, expected)
+    }
+
+    #[test]
+    fn attribute_prefix() {
+        #[command(rename = "lowercase")]
+        #[derive(BotCommand, Debug, PartialEq)]
+        enum DefaultCommands {
+            #[command(prefix = "!")]
+            Start,
+            Help,
+        }
+
+        let data = "!start arg1 arg2";
+        let expected = Some((DefaultCommands::Start, vec!["arg1", "arg2"]));
+        let actual = DefaultCommands::parse(data, "");
+        assert_eq!(actual, expected)
+    }
+
+    #[test]
+    fn many_attributes() {
+        #[command(rename = "lowercase")]
+        #[derive(BotCommand, Debug, PartialEq)]
+        enum DefaultCommands {
+            #[command(prefix = "!", description = "desc")]
+            Start,
+            Help,
+        }
+
+        assert_eq!(
+            DefaultCommands::Start,
+            DefaultCommands::parse("!start", "").unwrap().0
+        );
+        assert_eq!(DefaultCommands::descriptions(), "!start - desc\n/help\n");
+    }
+
+    #[test]
+    fn global_attributes() {
+        #[command(
+        prefix = "!",
+        rename = "lowercase",
+        description = "Bot commands"
+        )]
+        #[derive(BotCommand, Debug, PartialEq)]
+        enum DefaultCommands {
+            #[command(prefix = "/")]
+            Start,
+            Help,
+        }
+
+        assert_eq!(
+            DefaultCommands::Start,
+            DefaultCommands::parse("/start", "MyNameBot").unwrap().0
+        );
+        assert_eq!(
+            DefaultCommands::Help,
+            DefaultCommands::parse("!help", "MyNameBot").unwrap().0
+        );
+        assert_eq!(
+            DefaultCommands::descriptions(),
+            "Bot commands\n/start\n!help\n"
+        );
+    }
+
+    #[test]
+    fn parse_command_with_bot_name() {
+        #[command(rename = "lowercase")]
+        #[derive(BotCommand, Debug, PartialEq)]
+        enum DefaultCommands {
+            #[command(prefix = "/")]
+            Start,
+            Help,
+        }
+
+        assert_eq!(
+            DefaultCommands::Start,
+            DefaultCommands::parse("/start@MyNameBot", "MyNameBot").unwrap().0
+        );
+    }
+
+    #[derive(BotCommand, Debug, PartialEq)]
+    #[command(rename = "lowercase")]
+    enum SyntaxCommands {
+        Start,
+        Ban,
+    }
+
+    #[test]
+    fn leading_syntax() {
+        assert_eq!(
+            SyntaxCommands::parse_with("/ban@bot 5 h", "bot", &LeadingSyntax),
+            Some((SyntaxCommands::Ban, vec!["5", "h"]))
+        );
+        assert_eq!(
+            SyntaxCommands::parse_with("/ban@other 5 h", "bot", &LeadingSyntax),
+            None
+        );
+        assert_eq!(
+            SyntaxCommands::parse_with("please /ban", "bot", &LeadingSyntax),
+            None
+        );
+    }
+
+    #[test]
+    fn inline_syntax() {
+        assert_eq!(
+            InlineSyntax.find("a ${/ban 5 h} b ${/start} ${/open", ""),
+            vec![
+                (Cow::Borrowed("/ban"), vec!["5", "h"]),
+                (Cow::Borrowed("/start"), vec![])
+            ]
+        );
+        assert_eq!(
+            SyntaxCommands::parse_with(
+                "${/unknown} then ${/start now}",
+                "bot",
+                &InlineSyntax
+            ),
+            Some((SyntaxCommands::Start, vec!["now"]))
+        );
+        assert_eq!(
+            SyntaxCommands::parse_with("/start", "bot", &InlineSyntax),
+            None
+        );
+    }
+
+    #[test]
+    fn mention_syntax() {
+        let syntax = MentionSyntax::new();
+        assert_eq!(
+            SyntaxCommands::parse_with("@bot ban 5 h", "bot", &syntax),
+            Some((SyntaxCommands::Ban, vec!["5", "h"]))
+        );
+        assert_eq!(
+            SyntaxCommands::parse_with("  @bot /start", "bot", &syntax),
+            Some((SyntaxCommands::Start, vec![]))
+        );
+        assert_eq!(
+            SyntaxCommands::parse_with("@bot2 ban", "bot", &syntax),
+            None
+        );
+        assert_eq!(SyntaxCommands::parse_with("ban", "bot", &syntax), None);
+
+        let syntax = MentionSyntax::with_mention("${@!123}");
+        assert_eq!(
+            SyntaxCommands::parse_with("${@!123} ban 1", "bot", &syntax),
+            Some((SyntaxCommands::Ban, vec!["1"]))
+        );
+    }
+
+    #[test]
+    fn combined_syntaxes() {
+        let syntaxes = default_syntaxes();
+        assert_eq!(
+            SyntaxCommands::parse_with("/start", "bot", &syntaxes),
+            Some((SyntaxCommands::Start, vec![]))
+        );
+        assert_eq!(
+            SyntaxCommands::parse_with("/unknown ${/ban x}", "bot", &syntaxes),
+            Some((SyntaxCommands::Ban, vec!["x"]))
+        );
+        assert_eq!(
+            SyntaxCommands::parse_with("@bot ban", "bot", &syntaxes),
+            None
+        );
+    }
+
+    #[test]
+    fn bot_commands() {
+        #[derive(BotCommand, Debug, PartialEq)]
+        #[command(rename = "lowercase", description = "All commands:")]
+        enum DefaultCommands {
+            #[command(description = "start the bot.")]
+            Start,
+            Help,
+            #[command(description = "ok")]
+            Short,
+            #[command(description = "kick - then ban.")]
+            Kick,
+            #[command(prefix = "!", description = "not in the menu.")]
+            Hidden,
+        }
+
+        assert_eq!(
+            DefaultCommands::bot_commands(),
+            vec![
+                crate::types::BotCommand::new("start", "start the bot."),
+                crate::types::BotCommand::new("kick", "kick - then ban."),
+            ]
+        );
+    }
+
+    #[test]
+    fn bot_commands_with_multiline_descriptions() {
+        #[derive(BotCommand, Debug, PartialEq)]
+        #[command(
+            rename = "lowercase",
+            description = "/start - see below.\n/help"
+        )]
+        enum MultilineCommands {
+            #[command(
+                description = "start the bot.\n/help - explained below."
+            )]
+            Start,
+            #[command(description = "show help.")]
+            Help,
+        }
+
+        assert_eq!(
+            MultilineCommands::bot_commands(),
+            vec![
+                crate::types::BotCommand::new(
+                    "start",
+                    "start the bot.\n/help - explained below."
+                ),
+                crate::types::BotCommand::new("help", "show help."),
+            ]
+        );
+    }
+}
@@ -0,0 +1,672 @@
+//! Utils for working with the Fanbook message style.
+//!
+//! The style is used for messages sent with [`ParseMode::Fanbook`]:
+//! ````text
+//! **bold text**
+//! _italic text_
+//! [inline URL](https://www.example.com/)
+//! ${@!123456789}    (a mention of the user with the identifier 123456789)
+//! ${#123456789}     (a link to the channel with the identifier 123456789)
+//! ${@&123456789}    (a mention of the role with the identifier 123456789)
+//! ${@&<guild id>}   (a mention of all the members of the guild)
+//! `inline fixed-width code`
+//! ```rust
+//! pre-formatted fixed-width code block written in Rust
+//! ```
+//! ````
+//!
+//! Characters `\`, `*`, `_`, `` ` ``, `[`, `]` and `$` are escaped with a
+//! preceding `\`. Inside code, only `` ` `` and `\` are escaped, and inside
+//! the URL of a link, only `)` and `\`.
+//!
+//! Besides building markup, this module [`parse`]s it into [`Node`]s, which
+//! [`render`] back into the markup, and extracts [`mentions`] from it.
+//!
+//! [`ParseMode::Fanbook`]: crate::types::ParseMode::Fanbook
+//! [`parse`]: crate::utils::fanbook::parse
+//! [`Node`]: crate::utils::fanbook::Node
+//! [`render`]: crate::utils::fanbook::render
+//! [`mentions`]: crate::utils::fanbook::mentions
+use crate::types::{GuildRole, User};
+use std::string::String;
+
+/// Applies the bold font style to the string.
+///
+/// Passed string will not be automatically escaped because it can contain
+/// nested markup.
+pub fn bold(s: &str) -> String {
+    format!("**{}**", s)
+}
+
+/// Applies the italic font style to the string.
+///
+/// Passed string will not be automatically escaped because it can contain
+/// nested markup.
+pub fn italic(s: &str) -> String {
+    format!("_{}_", s)
+}
+
+/// Builds an inline link with an anchor.
+///
+/// Escapes the passed URL and the link text.
+pub fn link(url: &str, text: &str) -> String {
+    format!("[{}]({})", escape(text), escape_link_url(url))
+}
+
+/// Builds a mention of the user with the identifier `user_id`.
+///
+/// Clients show the current name of the user instead of the mention.
+pub fn user_mention(user_id: i64) -> String {
+    format!("${{@!{}}}", user_id)
+}
+
+/// Builds a link to the channel with the identifier `channel_id`.
+pub fn channel_mention(channel_id: i64) -> String {
+    format!("${{#{}}}", channel_id)
+}
+
+/// Builds a mention of the guild role with the identifier `role_id`.
+pub fn role_mention(role_id: i64) -> String {
+    format!("${{@&{}}}", role_id)
+}
+
+/// Builds a mention of `role`.
+pub fn guild_role_mention(role: &GuildRole) -> String {
+    role_mention(role.id)
+}
+
+/// Builds an `@everyone` mention, which notifies all the members of the guild
+/// with the identifier `guild_id`.
+///
+/// In Fanbook, every member of a guild has the `@everyone` role, whose
+/// identifier is the identifier of the guild, so the broadcast is a mention
+/// of this role.
+pub fn everyone_mention(guild_id: i64) -> String {
+    role_mention(guild_id)
+}
+
+/// Formats the code block.
+///
+/// Escapes ``` and `\` characters inside the block.
+pub fn code_block(code: &str) -> String {
+    format!("```\n{}\n```", escape_code(code))
+}
+
+/// Formats the code block with a specific language syntax.
+///
+/// Escapes ``` and `\` characters inside the block.
+pub fn code_block_with_lang(code: &str, lang: &str) -> String {
+    format!("```{}\n{}\n```", escape(lang), escape_code(code))
+}
+
+/// Formats the string as an inline code.
+///
+/// Escapes ``` and `\` characters inside the block.
+pub fn code_inline(s: &str) -> String {
+    format!("`{}`", escape_code(s))
+}
+
+/// Escapes the string to be shown "as is" within the Fanbook message style.
+pub fn escape(s: &str) -> String {
+    escape_chars(s, &['\\', '*', '_', '`', '[', ']', '$'])
+}
+
+/// Escapes all the special characters specific for the inline link URL (`)`
+/// and `\`).
+pub fn escape_link_url(s: &str) -> String {
+    escape_chars(s, &['\\', ')'])
+}
+
+/// Escapes all the special characters specific for the code block (``` and
+/// `\`).
+pub fn escape_code(s: &str) -> String {
+    escape_chars(s, &['\\', '`'])
+}
+
+fn escape_chars(s: &str, special: &[char]) -> String {
+    let mut escaped = String::with_capacity(s.len());
+    for c in s.chars() {
+        if special.contains(&c) {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+/// Mentions the user by the identifier, which clients show as the nickname
+/// of the user in the guild.
+///
+/// If the identifier is unknown (`0`), e.g. for a user built from a
+/// [`Mention::Username`], falls back to [`user_mention_by_name`].
+///
+/// [`Mention::Username`]: crate::utils::fanbook::Mention::Username
+/// [`user_mention_by_name`]: crate::utils::fanbook::user_mention_by_name
+pub fn user_mention_or_link(user: &User) -> String {
+    match user.id {
+        0 => user_mention_by_name(user),
+        id => user_mention(id),
+    }
+}
+
+/// Mentions the user as plain text: `@username`, or `@nickname` if the user
+/// has no username.
+///
+/// The nickname of a guild member is [`User::full_name`].
+///
+/// [`User::full_name`]: crate::types::User::full_name
+pub fn user_mention_by_name(user: &User) -> String {
+    match &user.username {
+        Some(username) => escape(&format!("@{}", username)),
+        None => escape(&format!("@{}", user.full_name())),
+    }
+}
+
+/// Something mentioned in a message.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub enum Mention {
+    /// The user with the identifier.
+    User(i64),
+
+    /// The user with the username, without `@`.
+    ///
+    /// Such mentions come from [`MessageEntityKind::Mention`] entities.
+    ///
+    /// [`MessageEntityKind::Mention`]: crate::types::MessageEntityKind::Mention
+    Username(String),
+
+    /// The guild role with the identifier.
+    Role(i64),
+
+    /// The channel with the identifier.
+    Channel(i64),
+}
+
+impl Mention {
+    /// Renders the mention into the markup.
+    ///
+    /// A [`Mention::Username`] is rendered as plain text.
+    ///
+    /// [`Mention::Username`]: crate::utils::fanbook::Mention::Username
+    pub fn render(&self) -> String {
+        match self {
+            Mention::User(id) => user_mention(*id),
+            Mention::Username(username) => escape(&format!("@{}", username)),
+            Mention::Role(id) => role_mention(*id),
+            Mention::Channel(id) => channel_mention(*id),
+        }
+    }
+
+    /// Returns `true` if it's an `@everyone` mention of the guild with the
+    /// identifier `guild_id`.
+    ///
+    /// See [`everyone_mention`].
+    ///
+    /// [`everyone_mention`]: crate::utils::fanbook::everyone_mention
+    pub fn is_everyone(&self, guild_id: i64) -> bool {
+        *self == Mention::Role(guild_id)
+    }
+}
+
+/// Extracts the mentions of users, roles and channels from the markup, in the
+/// order they appear.
+///
+/// Mentions inside code are ignored.
+///
+/// ## Example
+/// ```
+/// use teloxide::utils::fanbook::{mentions, Mention};
+///
+/// assert_eq!(
+///     mentions("**${@!42}**, see ${#7}, not `${@&1}`"),
+///     vec![Mention::User(42), Mention::Channel(7)]
+/// );
+/// ```
+pub fn mentions(s: &str) -> Vec<Mention> {
+    mentions_with_offsets(s).into_iter().map(|(_, mention)| mention).collect()
+}
+
+/// Like [`mentions`], but also returns the byte offsets of the mentions in
+/// `s`.
+pub(crate) fn mentions_with_offsets(s: &str) -> Vec<(usize, Mention)> {
+    let mut parser = Parser { s, pos: 0, mentions: Vec::new() };
+    parser.nodes(None);
+    parser.mentions
+}
+
+/// A piece of a message in the Fanbook message style.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub enum Node {
+    /// Plain text.
+    Text(String),
+    Bold(Vec<Node>),
+    Italic(Vec<Node>),
+    Link {
+        text: String,
+        url: String,
+    },
+    UserMention(i64),
+    ChannelMention(i64),
+    RoleMention(i64),
+    Code(String),
+    CodeBlock {
+        language: Option<String>,
+        code: String,
+    },
+}
+
+impl Node {
+    /// Renders the node into the markup.
+    pub fn render(&self) -> String {
+        match self {
+            Node::Text(text) => escape(text),
+            Node::Bold(nodes) => bold(&render(nodes)),
+            Node::Italic(nodes) => italic(&render(nodes)),
+            Node::Link { text, url } => link(url, text),
+            Node::UserMention(id) => user_mention(*id),
+            Node::ChannelMention(id) => channel_mention(*id),
+            Node::RoleMention(id) => role_mention(*id),
+            Node::Code(code) => code_inline(code),
+            Node::CodeBlock { language: Some(language), code } => {
+                code_block_with_lang(code, language)
+            }
+            Node::CodeBlock { language: None, code } => code_block(code),
+        }
+    }
+}
+
+/// Renders `nodes` into the markup.
+///
+/// For any nodes returned by [`parse`], `parse(&render(&nodes)) == nodes`.
+///
+/// [`parse`]: crate::utils::fanbook::parse
+pub fn render(nodes: &[Node]) -> String {
+    nodes.iter().map(Node::render).collect()
+}
+
+/// Parses the markup into nodes.
+///
+/// Parsing never fails: markup which isn't closed, such as `**bold`, is
+/// treated as plain text.
+///
+/// ## Example
+/// ```
+/// use teloxide::utils::fanbook::{parse, Node};
+///
+/// assert_eq!(
+///     parse("Hi, **${@!42}**!"),
+///     vec![
+///         Node::Text("Hi, ".to_owned()),
+///         Node::Bold(vec![Node::UserMention(42)]),
+///         Node::Text("!".to_owned()),
+///     ]
+/// );
+/// ```
+pub fn parse(s: &str) -> Vec<Node> {
+    Parser { s, pos: 0, mentions: Vec::new() }.nodes(None).unwrap_or_default()
+}
+
+struct Parser<'a> {
+    s: &'a str,
+    pos: usize,
+    /// The mentions parsed so far, with their byte offsets.
+    mentions: Vec<(usize, Mention)>,
+}
+
+impl<'a> Parser<'a> {
+    fn rest(&self) -> &'a str {
+        &self.s[self.pos..]
+    }
+
+    /// Parses nodes up to `end`, which is required if specified.
+    fn nodes(&mut self, end: Option<&str>) -> Option<Vec<Node>> {
+        let mut nodes = Vec::new();
+        let mut text = String::new();
+
+        loop {
+            let rest = self.rest();
+            if let Some(end) = end {
+                if rest.starts_with(end) {
+                    self.pos += end.len();
+                    break;
+                }
+            }
+            let c = match rest.chars().next() {
+                Some(c) => c,
+                None if end.is_none() => break,
+                None => return None,
+            };
+
+            let start = self.pos;
+            let mentions = self.mentions.len();
+            let node = match c {
+                '\\' => {
+                    self.pos += 1;
+                    text.push(self.escaped_char());
+                    continue;
+                }
+                '`' if rest.starts_with("```") => self.code_block(),
+                '`' => self.code(),
+                '*' if rest.starts_with("**") => self.styled("**", Node::Bold),
+                '_' => self.styled("_", Node::Italic),
+                '[' => self.link(),
+                '$' if rest.starts_with("${") => self.mention(),
+                _ => None,
+            };
+
+            match node {
+                Some(node) => {
+                    if !text.is_empty() {
+                        nodes.push(Node::Text(std::mem::take(&mut text)));
+                    }
+                    nodes.push(node);
+                }
+                None => {
+                    // Not markup: take the character (or both `*` of `**`)
+                    // as text.
+                    self.pos = start;
+                    self.mentions.truncate(mentions);
+                    let len =
+                        if rest.starts_with("**") { 2 } else { c.len_utf8() };
+                    text.push_str(&rest[..len]);
+                    self.pos += len;
+                }
+            }
+        }
+
+        if !text.is_empty() {
+            nodes.push(Node::Text(text));
+        }
+        Some(nodes)
+    }
+
+    /// Takes the character after `\`, or `\` itself at the end.
+    fn escaped_char(&mut self) -> char {
+        match self.rest().chars().next() {
+            Some(c) => {
+                self.pos += c.len_utf8();
+                c
+            }
+            None => '\\',
+        }
+    }
+
+    /// Takes unescaped text up to `end`.
+    fn raw_until(&mut self, end: &str) -> Option<String> {
+        let mut text = String::new();
+        loop {
+            let rest = self.rest();
+            if rest.starts_with(end) {
+                self.pos += end.len();
+                return Some(text);
+            }
+            let c = rest.chars().next()?;
+            self.pos += c.len_utf8();
+            if c == '\\' {
+                text.push(self.escaped_char());
+            } else {
+                text.push(c);
+            }
+        }
+    }
+
+    fn styled<F>(&mut self, delimiter: &str, node: F) -> Option<Node>
+    where
+        F: FnOnce(Vec<Node>) -> Node,
+    {
+        self.pos += delimiter.len();
+        match self.nodes(Some(delimiter)) {
+            Some(nodes) if !nodes.is_empty() => Some(node(nodes)),
+            _ => None,
+        }
+    }
+
+    fn code(&mut self) -> Option<Node> {
+        self.pos += 1;
+        self.raw_until("`").map(Node::Code)
+    }
+
+    fn code_block(&mut self) -> Option<Node> {
+        self.pos += 3;
+        let header_len = self.rest().find('\n')?;
+        let header = &self.rest()[..header_len];
+        if header.contains('`') {
+            return None;
+        }
+        let language = match header {
+            "" => None,
+            language => Some(unescape(language)),
+        };
+        self.pos += header_len + 1;
+
+        let mut code = self.raw_until("```")?;
+        if code.ends_with('\n') {
+            code.pop();
+        }
+        Some(Node::CodeBlock { language, code })
+    }
+
+    fn link(&mut self) -> Option<Node> {
+        self.pos += 1;
+        let text = self.raw_until("](")?;
+        let url = self.raw_until(")")?;
+        Some(Node::Link { text, url })
+    }
+
+    fn mention(&mut self) -> Option<Node> {
+        let rest = &self.rest()[2..];
+        let end = rest.find('}')?;
+        let token = &rest[..end];
+
+        type Kind = (fn(i64) -> Node, fn(i64) -> Mention);
+        let ((node, mention), id): (Kind, _) =
+            if let Some(id) = token.strip_prefix("@!") {
+                ((Node::UserMention, Mention::User), id)
+            } else if let Some(id) = token.strip_prefix("@&") {
+                ((Node::RoleMention, Mention::Role), id)
+            } else if let Some(id) = token.strip_prefix('#') {
+                ((Node::ChannelMention, Mention::Channel), id)
+            } else {
+                return None;
+            };
+        if id.is_empty() || !id.bytes().all(|b| b.is_ascii_digit()) {
+            return None;
+        }
+
+        let id = id.parse().ok()?;
+        self.mentions.push((self.pos, mention(id)));
+        self.pos += 2 + end + 1;
+        Some(node(id))
+    }
+}
+
+/// Removes the `\` escaping characters.
+fn unescape(s: &str) -> String {
+    let mut unescaped = String::with_capacity(s.len());
+    let mut chars = s.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => unescaped.push(chars.next().unwrap_or('\\')),
+            c => unescaped.push(c),
+        }
+    }
+    unescaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn text(s: &str) -> Node {
+        Node::Text(s.to_owned())
+    }
+
+    #[test]
+    fn test_bold() {
+        assert_eq!(bold(" foobar "), "** foobar **");
+        assert_eq!(bold(" _foobar_ "), "** _foobar_ **");
+    }
+
+    #[test]
+    fn test_italic() {
+        assert_eq!(italic(" foobar "), "_ foobar _");
+        assert_eq!(italic(" **foobar** "), "_ **foobar** _");
+    }
+
+    #[test]
+    fn test_link() {
+        assert_eq!(
+            link("https://www.google.com/(search)", "[google]"),
+            r"[\[google\]](https://www.google.com/(search\))",
+        );
+    }
+
+    #[test]
+    fn test_mentions() {
+        assert_eq!(user_mention(123), "${@!123}");
+        assert_eq!(channel_mention(456), "${#456}");
+        assert_eq!(role_mention(789), "${@&789}");
+        assert_eq!(everyone_mention(100), "${@&100}");
+        assert_eq!(
+            guild_role_mention(&GuildRole::new(
+                5,
+                "admins".to_owned(),
+                1,
+                0,
+                0
+            )),
+            "${@&5}"
+        );
+    }
+
+    #[test]
+    fn extract_mentions() {
+        let markup = "${@&100} _${@!1} [${@!2}](x)_ ${#3} \\${@!4}\n\
+                      ```\n${@!5}\n``` ${@!1}";
+
+        let mentions = mentions(markup);
+        assert_eq!(
+            mentions,
+            [
+                Mention::Role(100),
+                Mention::User(1),
+                Mention::Channel(3),
+                Mention::User(1),
+            ]
+        );
+        assert_eq!(Mention::Username("a_b".to_owned()).render(), r"@a\_b");
+        assert_eq!(Mention::Channel(3).render(), "${#3}");
+        assert!(mentions[0].is_everyone(100));
+        assert!(!mentions[0].is_everyone(1));
+        assert!(!mentions[1].is_everyone(1));
+    }
+
+    #[test]
+    fn test_code() {
+        assert_eq!(code_inline(r"a`b\c"), r"`a\`b\\c`");
+        assert_eq!(code_block("fn main() {}"), "```\nfn main() {}\n```");
+        assert_eq!(
+            code_block_with_lang("let s = `x`;", "rust"),
+            "```rust\nlet s = \\`x\\`;\n```"
+        );
+    }
+
+    #[test]
+    fn test_escape() {
+        assert_eq!(
+            escape(r"**snake_case** [link] ${@!1} `code` \ (ok)"),
+            r"\*\*snake\_case\*\* \[link\] \${@!1} \`code\` \\ (ok)"
+        );
+    }
+
+    #[test]
+    fn parse_nested() {
+        assert_eq!(
+            parse(
+                "Hi **${@!1} _and_ ${@&2}**, see ${#3} or [docs](https://x.y/(z\\))"
+            ),
+            vec![
+                text("Hi "),
+                Node::Bold(vec![
+                    Node::UserMention(1),
+                    text(" "),
+                    Node::Italic(vec![text("and")]),
+                    text(" "),
+                    Node::RoleMention(2),
+                ]),
+                text(", see "),
+                Node::ChannelMention(3),
+                text(" or "),
+                Node::Link {
+                    text: "docs".to_owned(),
+                    url: "https://x.y/(z)".to_owned()
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn parse_code() {
+        assert_eq!(
+            parse("run `**a**` and\n```rust\nlet x = 1;\n```"),
+            vec![
+                text("run "),
+                Node::Code("**a**".to_owned()),
+                text(" and\n"),
+                Node::CodeBlock {
+                    language: Some("rust".to_owned()),
+                    code: "let x = 1;".to_owned()
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn parse_unclosed_markup_as_text() {
+        assert_eq!(
+            parse("**bold _it ${/ban} ${@!x} [a](b"),
+            vec![text("**bold _it ${/ban} ${@!x} [a](b")]
+        );
+        assert_eq!(
+            parse(r"\*\*not bold\*\* a\_b \\"),
+            vec![text(r"**not bold** a_b \")]
+        );
+    }
+
+    #[test]
+    fn round_trip() {
+        let nodes = vec![
+            text("1 * 2 = $2 [_] \\ "),
+            Node::Bold(vec![
+                text("bold "),
+                Node::Italic(vec![text("both"), Node::UserMention(7)]),
+            ]),
+            Node::Link { text: "a ] b".to_owned(), url: "c)d".to_owned() },
+            Node::ChannelMention(8),
+            Node::RoleMention(9),
+            Node::Code("`x`".to_owned()),
+            text("\n"),
+            Node::CodeBlock { language: None, code: "```\n\\".to_owned() },
+            text("\n"),
+            Node::CodeBlock {
+                language: Some(r"c\+_".to_owned()),
+                code: "x".to_owned(),
+            },
+        ];
+
+        let markup = render(&nodes);
+        assert_eq!(parse(&markup), nodes);
+        assert_eq!(render(&parse(&markup)), markup);
+    }
+
+    #[test]
+    fn user_mention_by_id_or_name() {
+        let user =
+            User { id: 42, first_name: "Ann_".to_owned(), ..User::default() };
+        assert_eq!(user_mention_or_link(&user), "${@!42}");
+        assert_eq!(user_mention_by_name(&user), r"@Ann\_");
+
+        let user = User { username: Some("ann".to_owned()), ..User::default() };
+        assert_eq!(user_mention_or_link(&user), "@ann");
+    }
+}
@@ -1,7 +1,7 @@
 //! Utils for working with the [HTML message style][spec].
 //!
 //! [spec]: https://core.telegram.org/bots/api#html-style
-use crate::types::User;
+use crate::types::{GuildRole, User};
 use std::string::String;
 
 /// Applies the bold font style to the string.
@@ -91,6 +91,33 @@ pub fn user_mention_or_link(user: &User) -> String {
     }
 }
 
+/// Mentions the user as plain text: `@username`, or `@nickname` if the user
+/// has no username.
+///
+/// Unlike [`user_mention_or_link`], this doesn't need `tg://user?id=` links,
+/// which guild clients don't follow. The nickname of a guild member is
+/// [`User::full_name`].
+///
+/// [`user_mention_or_link`]: crate::utils::html::user_mention_or_link
+/// [`User::full_name`]: crate::types::User::full_name
+pub fn user_mention_by_name(user: &User) -> String {
+    match &user.username {
+        Some(username) => escape(&format!("@{}", username)),
+        None => escape(&format!("@{}", user.full_name())),
+    }
+}
+
+/// Mentions `role` as plain text: `@name`.
+///
+/// To mention a role, so that its members are notified, use
+/// [`fanbook::guild_role_mention`] with [`ParseMode::Fanbook`].
+///
+/// [`fanbook::guild_role_mention`]: crate::utils::fanbook::guild_role_mention
+/// [`ParseMode::Fanbook`]: crate::types::ParseMode::Fanbook
+pub fn guild_role_mention(role: &GuildRole) -> String {
+    escape(&format!("@{}", role.name))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -210,4 +237,22 @@ mod tests {
             r#"<a href="tg://user/?id=123456789">Name</a>"#
         )
     }
+
+    #[test]
+    fn mention_by_name() {
+        let user = User {
+            first_name: "<Ann>".to_owned(),
+            ..Default::default()
+        };
+        assert_eq!(user_mention_by_name(&user), "@&lt;Ann&gt;");
+
+        let user = User {
+            username: Some("ann".to_owned()),
+            ..Default::default()
+        };
+        assert_eq!(user_mention_by_name(&user), "@ann");
+
+        let role = GuildRole::new(5, "a&b".to_owned(), 1, 0, 0);
+        assert_eq!(guild_role_mention(&role), "@a&amp;b");
+    }
 }
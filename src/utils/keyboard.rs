@@ -0,0 +1,472 @@
+//! Laying out keyboards.
+//!
+//! [`KeyboardBuilder`] arranges buttons into columns, splits long lists into
+//! pages with a navigation row (`◀ 1/5 ▶`) and checks the buttons before
+//! producing an [`InlineKeyboardMarkup`] or a [`ReplyKeyboardMarkup`]:
+//!
+//! ```
+//! use teloxide::{
+//!     types::InlineKeyboardButton, utils::keyboard::KeyboardBuilder,
+//! };
+//!
+//! let keyboard = KeyboardBuilder::new()
+//!     .columns(2)
+//!     .buttons((1..=10).map(|i| {
+//!         InlineKeyboardButton::callback(i.to_string(), format!("item:{}", i))
+//!     }))
+//!     .paginate(4, 0, "page:")
+//!     .build()
+//!     .unwrap();
+//!
+//! // Two rows of items and the navigation row.
+//! assert_eq!(keyboard.inline_keyboard.len(), 3);
+//! ```
+//!
+//! [`KeyboardBuilder`]: crate::utils::keyboard::KeyboardBuilder
+//! [`InlineKeyboardMarkup`]: crate::types::InlineKeyboardMarkup
+//! [`ReplyKeyboardMarkup`]: crate::types::ReplyKeyboardMarkup
+
+use thiserror::Error;
+
+use crate::types::{
+    InlineKeyboardButton, InlineKeyboardButtonKind, InlineKeyboardMarkup,
+    KeyboardButton, ReplyKeyboardMarkup,
+};
+
+/// The maximum length of callback data in bytes.
+pub const MAX_CALLBACK_DATA_LEN: usize = 64;
+
+/// A button of a keyboard which [`KeyboardBuilder`] can lay out.
+///
+/// [`KeyboardBuilder`]: crate::utils::keyboard::KeyboardBuilder
+pub trait Button: Sized {
+    /// A keyboard made of such buttons.
+    type Markup;
+
+    /// Creates a navigation button, leading to the page `page` of a
+    /// paginated keyboard.
+    fn navigation(text: String, callback_prefix: &str, page: usize) -> Self;
+
+    /// Checks that the button can be sent.
+    fn validate(&self) -> Result<(), KeyboardError>;
+
+    /// Makes a keyboard out of `rows`.
+    fn markup(rows: Vec<Vec<Self>>) -> Self::Markup;
+}
+
+impl Button for InlineKeyboardButton {
+    type Markup = InlineKeyboardMarkup;
+
+    fn navigation(text: String, callback_prefix: &str, page: usize) -> Self {
+        Self::callback(text, format!("{}{}", callback_prefix, page))
+    }
+
+    fn validate(&self) -> Result<(), KeyboardError> {
+        match &self.kind {
+            InlineKeyboardButtonKind::CallbackData(data) if data.is_empty() => {
+                Err(KeyboardError::EmptyCallbackData {
+                    text: self.text.clone(),
+                })
+            }
+            InlineKeyboardButtonKind::CallbackData(data)
+                if data.len() > MAX_CALLBACK_DATA_LEN =>
+            {
+                Err(KeyboardError::CallbackDataTooLong {
+                    text: self.text.clone(),
+                    len: data.len(),
+                })
+            }
+            _ => Ok(()),
+        }
+    }
+
+    fn markup(rows: Vec<Vec<Self>>) -> Self::Markup {
+        InlineKeyboardMarkup { inline_keyboard: rows }
+    }
+}
+
+impl Button for KeyboardButton {
+    type Markup = ReplyKeyboardMarkup;
+
+    /// Reply keyboards can't carry callback data, so the navigation buttons
+    /// are plain text ones and pressing them sends their text.
+    fn navigation(text: String, _callback_prefix: &str, _page: usize) -> Self {
+        Self::new(text)
+    }
+
+    fn validate(&self) -> Result<(), KeyboardError> {
+        Ok(())
+    }
+
+    fn markup(rows: Vec<Vec<Self>>) -> Self::Markup {
+        ReplyKeyboardMarkup { keyboard: rows, ..ReplyKeyboardMarkup::default() }
+    }
+}
+
+/// An error returned by [`KeyboardBuilder::build`].
+///
+/// [`KeyboardBuilder::build`]: crate::utils::keyboard::KeyboardBuilder::build
+#[derive(Debug, Error, Clone, PartialEq, Eq)]
+pub enum KeyboardError {
+    #[error(
+        "Callback data of the button {text:?} is {len} bytes long, but at \
+         most 64 bytes are allowed"
+    )]
+    CallbackDataTooLong { text: String, len: usize },
+
+    #[error("Callback data of the button {text:?} is empty")]
+    EmptyCallbackData { text: String },
+
+    #[error("Page #{page} is requested, but there are only {pages} pages")]
+    PageOutOfRange { page: usize, pages: usize },
+}
+
+/// Builds keyboards out of [`InlineKeyboardButton`]s or [`KeyboardButton`]s.
+///
+/// Buttons added by [`button`] and [`buttons`] are laid out in
+/// [`columns`], while [`row`] adds a row as is, e.g. a header or a "Cancel"
+/// button. Only the former are paginated, the rows are shown on every page.
+///
+/// See [the module-level documentation](crate::utils::keyboard) for an
+/// example.
+///
+/// [`InlineKeyboardButton`]: crate::types::InlineKeyboardButton
+/// [`KeyboardButton`]: crate::types::KeyboardButton
+/// [`button`]: KeyboardBuilder::button
+/// [`buttons`]: KeyboardBuilder::buttons
+/// [`columns`]: KeyboardBuilder::columns
+/// [`row`]: KeyboardBuilder::row
+#[derive(Clone, Debug)]
+pub struct KeyboardBuilder<B> {
+    entries: Vec<Entry<B>>,
+    columns: usize,
+    pagination: Option<Pagination>,
+}
+
+#[derive(Clone, Debug)]
+enum Entry<B> {
+    Button(B),
+    Row(Vec<B>),
+}
+
+#[derive(Clone, Debug)]
+struct Pagination {
+    per_page: usize,
+    page: usize,
+    callback_prefix: String,
+}
+
+impl<B> Default for KeyboardBuilder<B> {
+    fn default() -> Self {
+        Self { entries: vec![], columns: 1, pagination: None }
+    }
+}
+
+impl<B> KeyboardBuilder<B>
+where
+    B: Button,
+{
+    /// Creates an empty keyboard with one column.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the number of columns.
+    ///
+    /// # Panics
+    /// If `val` is `0`.
+    pub fn columns(mut self, val: usize) -> Self {
+        assert!(val > 0, "A keyboard must have at least one column");
+        self.columns = val;
+        self
+    }
+
+    /// Adds a button, laid out in columns.
+    pub fn button(mut self, val: B) -> Self {
+        self.entries.push(Entry::Button(val));
+        self
+    }
+
+    /// Adds several buttons, laid out in columns.
+    pub fn buttons<I>(mut self, val: I) -> Self
+    where
+        I: IntoIterator<Item = B>,
+    {
+        self.entries.extend(val.into_iter().map(Entry::Button));
+        self
+    }
+
+    /// Adds a row of buttons as is, unless it's empty.
+    pub fn row(mut self, val: Vec<B>) -> Self {
+        if !val.is_empty() {
+            self.entries.push(Entry::Row(val));
+        }
+        self
+    }
+
+    /// Shows only `per_page` buttons of the page `page` (counting from `0`),
+    /// followed by a navigation row: `◀ 2/5 ▶`.
+    ///
+    /// For inline keyboards, the navigation buttons carry
+    /// `callback_prefix` followed by the number of the page they lead to,
+    /// which can be parsed back by [`parse_page`]. There is no navigation row
+    /// if all the buttons fit into one page.
+    ///
+    /// # Panics
+    /// If `per_page` is `0`.
+    ///
+    /// [`parse_page`]: crate::utils::keyboard::parse_page
+    pub fn paginate<S>(
+        mut self,
+        per_page: usize,
+        page: usize,
+        callback_prefix: S,
+    ) -> Self
+    where
+        S: Into<String>,
+    {
+        assert!(per_page > 0, "A page must have at least one button");
+        self.pagination = Some(Pagination {
+            per_page,
+            page,
+            callback_prefix: callback_prefix.into(),
+        });
+        self
+    }
+
+    /// Lays out the buttons and checks them.
+    pub fn build(self) -> Result<B::Markup, KeyboardError> {
+        let total = self
+            .entries
+            .iter()
+            .filter(|entry| matches!(entry, Entry::Button(_)))
+            .count();
+
+        let (visible, navigation) = match &self.pagination {
+            Some(pagination) => {
+                let per_page = pagination.per_page;
+                let pages =
+                    total / per_page + usize::from(total % per_page != 0);
+                if pagination.page >= pages.max(1) {
+                    return Err(KeyboardError::PageOutOfRange {
+                        page: pagination.page,
+                        pages,
+                    });
+                }
+
+                let start = pagination.page * pagination.per_page;
+                let end = (start + pagination.per_page).min(total);
+                let navigation = if pages > 1 {
+                    Some(navigation_row(pagination, pages))
+                } else {
+                    None
+                };
+                (start..end, navigation)
+            }
+            None => (0..total, None),
+        };
+
+        let mut rows = Vec::new();
+        let mut pending = Vec::new();
+        let mut index = 0;
+        for entry in self.entries {
+            match entry {
+                Entry::Button(button) => {
+                    if visible.contains(&index) {
+                        pending.push(button);
+                    }
+                    index += 1;
+                }
+                Entry::Row(row) => {
+                    flush(&mut rows, &mut pending, self.columns);
+                    rows.push(row);
+                }
+            }
+        }
+        flush(&mut rows, &mut pending, self.columns);
+        rows.extend(navigation);
+
+        for button in rows.iter().flatten() {
+            button.validate()?;
+        }
+
+        Ok(B::markup(rows))
+    }
+}
+
+fn flush<B>(rows: &mut Vec<Vec<B>>, pending: &mut Vec<B>, columns: usize) {
+    let mut buttons = pending.drain(..).peekable();
+    while buttons.peek().is_some() {
+        rows.push(buttons.by_ref().take(columns).collect());
+    }
+}
+
+fn navigation_row<B>(pagination: &Pagination, pages: usize) -> Vec<B>
+where
+    B: Button,
+{
+    let Pagination { page, callback_prefix, .. } = pagination;
+    let mut row = Vec::with_capacity(3);
+
+    if *page > 0 {
+        row.push(B::navigation("◀".to_owned(), callback_prefix, page - 1));
+    }
+    row.push(B::navigation(
+        format!("{}/{}", page + 1, pages),
+        callback_prefix,
+        *page,
+    ));
+    if page + 1 < pages {
+        row.push(B::navigation("▶".to_owned(), callback_prefix, page + 1));
+    }
+
+    row
+}
+
+/// Parses the page number out of callback data of a navigation button,
+/// created by [`KeyboardBuilder::paginate`] with the same `callback_prefix`.
+///
+/// [`KeyboardBuilder::paginate`]: crate::utils::keyboard::KeyboardBuilder::paginate
+pub fn parse_page(data: &str, callback_prefix: &str) -> Option<usize> {
+    if !data.starts_with(callback_prefix) {
+        return None;
+    }
+    data[callback_prefix.len()..].parse().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn callback(i: usize) -> InlineKeyboardButton {
+        InlineKeyboardButton::callback(i.to_string(), i.to_string())
+    }
+
+    fn texts(markup: &InlineKeyboardMarkup) -> Vec<Vec<&str>> {
+        markup
+            .inline_keyboard
+            .iter()
+            .map(|row| row.iter().map(|button| button.text.as_str()).collect())
+            .collect()
+    }
+
+    #[test]
+    fn columns() {
+        let markup = KeyboardBuilder::new()
+            .columns(2)
+            .buttons((1..=5).map(callback))
+            .row(vec![])
+            .row(vec![callback(0)])
+            .build()
+            .unwrap();
+
+        assert_eq!(
+            texts(&markup),
+            vec![vec!["1", "2"], vec!["3", "4"], vec!["5"], vec!["0"]]
+        );
+    }
+
+    #[test]
+    fn pagination() {
+        let builder = KeyboardBuilder::new()
+            .columns(2)
+            .buttons((1..=10).map(callback))
+            .row(vec![callback(0)]);
+
+        let first = builder.clone().paginate(4, 0, "p:").build().unwrap();
+        assert_eq!(
+            texts(&first),
+            vec![vec!["1", "2"], vec!["3", "4"], vec!["0"], vec!["1/3", "▶"]]
+        );
+
+        let middle = builder.clone().paginate(4, 1, "p:").build().unwrap();
+        assert_eq!(
+            texts(&middle),
+            vec![
+                vec!["5", "6"],
+                vec!["7", "8"],
+                vec!["0"],
+                vec!["◀", "2/3", "▶"]
+            ]
+        );
+        let navigation = middle.inline_keyboard.last().unwrap();
+        assert_eq!(
+            navigation[0].kind,
+            InlineKeyboardButtonKind::CallbackData("p:0".to_owned())
+        );
+        assert_eq!(
+            navigation[2].kind,
+            InlineKeyboardButtonKind::CallbackData("p:2".to_owned())
+        );
+
+        let last = builder.clone().paginate(4, 2, "p:").build().unwrap();
+        assert_eq!(
+            texts(&last),
+            vec![vec!["9", "10"], vec!["0"], vec!["◀", "3/3"]]
+        );
+
+        assert_eq!(
+            builder.paginate(4, 3, "p:").build(),
+            Err(KeyboardError::PageOutOfRange { page: 3, pages: 3 })
+        );
+    }
+
+    #[test]
+    fn single_page_has_no_navigation() {
+        let markup = KeyboardBuilder::new()
+            .buttons((1..=3).map(callback))
+            .paginate(5, 0, "p:")
+            .build()
+            .unwrap();
+
+        assert_eq!(texts(&markup), vec![vec!["1"], vec!["2"], vec!["3"]]);
+    }
+
+    #[test]
+    fn callback_data_limit() {
+        let too_long =
+            InlineKeyboardButton::callback("a".to_owned(), "x".repeat(65));
+        assert_eq!(
+            KeyboardBuilder::new().button(too_long).build(),
+            Err(KeyboardError::CallbackDataTooLong {
+                text: "a".to_owned(),
+                len: 65
+            })
+        );
+
+        let empty =
+            InlineKeyboardButton::callback("a".to_owned(), "".to_owned());
+        assert_eq!(
+            KeyboardBuilder::new().button(empty).build(),
+            Err(KeyboardError::EmptyCallbackData { text: "a".to_owned() })
+        );
+
+        let longest =
+            InlineKeyboardButton::callback("a".to_owned(), "x".repeat(64));
+        assert!(KeyboardBuilder::new().button(longest).build().is_ok());
+    }
+
+    #[test]
+    fn reply_keyboard() {
+        let markup = KeyboardBuilder::new()
+            .columns(3)
+            .buttons((1..=4).map(|i| KeyboardButton::new(i.to_string())))
+            .paginate(3, 1, "")
+            .build()
+            .unwrap();
+
+        let texts: Vec<Vec<&str>> = markup
+            .keyboard
+            .iter()
+            .map(|row| row.iter().map(|button| button.text.as_str()).collect())
+            .collect();
+        assert_eq!(texts, vec![vec!["4"], vec!["◀", "2/2"]]);
+    }
+
+    #[test]
+    fn parse_page_works() {
+        assert_eq!(parse_page("p:3", "p:"), Some(3));
+        assert_eq!(parse_page("q:3", "p:"), None);
+        assert_eq!(parse_page("p:x", "p:"), None);
+    }
+}
@@ -0,0 +1,466 @@
+//! Building formatted messages without markup.
+//!
+//! [`MessageText`] collects plain text along with [`MessageEntity`]s, whose
+//! offsets it counts in UTF-16 code units, as the API expects. The result can
+//! be sent as is, with [`SendMessage::message_text`], or rendered into markup
+//! of any [`ParseMode`], taking care of escaping:
+//!
+//! ```
+//! use teloxide::{types::ParseMode, utils::message_text::MessageText};
+//!
+//! let text = MessageText::new()
+//!     .bold("Warning")
+//!     .plain(": 2 < 3, see ")
+//!     .link("the docs", "https://example.com/");
+//!
+//! assert_eq!(text.as_str(), "Warning: 2 < 3, see the docs");
+//! assert_eq!(
+//!     text.render(ParseMode::HTML),
+//!     "<b>Warning</b>: 2 &lt; 3, see <a href=\"https://example.com/\">the \
+//!      docs</a>"
+//! );
+//! ```
+//!
+//! [`MessageText`]: crate::utils::message_text::MessageText
+//! [`MessageEntity`]: crate::types::MessageEntity
+//! [`SendMessage::message_text`]: crate::requests::SendMessage::message_text
+//! [`ParseMode`]: crate::types::ParseMode
+
+use crate::{
+    types::{MessageEntity, MessageEntityKind, ParseMode, User},
+    utils::{fanbook, html, markdown},
+};
+
+/// Plain text of a message with its entities.
+///
+/// See [the module-level documentation](crate::utils::message_text).
+#[derive(Clone, Debug, Default, Eq, Hash, PartialEq)]
+pub struct MessageText {
+    text: String,
+    entities: Vec<MessageEntity>,
+    /// The length of `text` in UTF-16 code units.
+    len: usize,
+}
+
+impl MessageText {
+    /// Creates an empty text.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Appends unformatted `text`.
+    #[must_use]
+    pub fn plain(mut self, text: &str) -> Self {
+        self.push(text);
+        self
+    }
+
+    /// Appends `text` in the bold font style.
+    #[must_use]
+    pub fn bold(self, text: &str) -> Self {
+        self.entity(MessageEntityKind::Bold, text)
+    }
+
+    /// Appends `text` in the italic font style.
+    #[must_use]
+    pub fn italic(self, text: &str) -> Self {
+        self.entity(MessageEntityKind::Italic, text)
+    }
+
+    /// Appends underlined `text`.
+    #[must_use]
+    pub fn underline(self, text: &str) -> Self {
+        self.entity(MessageEntityKind::Underline, text)
+    }
+
+    /// Appends strikethrough `text`.
+    #[must_use]
+    pub fn strike(self, text: &str) -> Self {
+        self.entity(MessageEntityKind::Strikethrough, text)
+    }
+
+    /// Appends `code` as an inline code.
+    #[must_use]
+    pub fn code(self, code: &str) -> Self {
+        self.entity(MessageEntityKind::Code, code)
+    }
+
+    /// Appends a code block.
+    #[must_use]
+    pub fn code_block(self, code: &str) -> Self {
+        self.entity(MessageEntityKind::Pre { language: None }, code)
+    }
+
+    /// Appends a code block with a specific language syntax.
+    #[must_use]
+    pub fn code_block_with_lang(self, code: &str, lang: &str) -> Self {
+        let kind = MessageEntityKind::Pre { language: Some(lang.to_owned()) };
+        self.entity(kind, code)
+    }
+
+    /// Appends `text` linking to `url`.
+    #[must_use]
+    pub fn link(self, text: &str, url: &str) -> Self {
+        self.entity(MessageEntityKind::TextLink { url: url.to_owned() }, text)
+    }
+
+    /// Appends `text` mentioning `user`.
+    #[must_use]
+    pub fn mention(self, text: &str, user: User) -> Self {
+        self.entity(MessageEntityKind::TextMention { user }, text)
+    }
+
+    /// Appends `text` as an entity of the `kind`.
+    #[must_use]
+    pub fn entity(self, kind: MessageEntityKind, text: &str) -> Self {
+        self.nested(kind, MessageText::new().plain(text))
+    }
+
+    /// Appends `inner` as an entity of the `kind`, keeping the entities of
+    /// `inner`, e.g. to make a bold link.
+    #[must_use]
+    pub fn nested(
+        mut self,
+        kind: MessageEntityKind,
+        inner: MessageText,
+    ) -> Self {
+        let offset = self.len;
+        self.entities.push(MessageEntity { kind, offset, length: inner.len });
+        self.append(inner)
+    }
+
+    /// Appends `other` with its entities.
+    #[must_use]
+    pub fn append(mut self, other: MessageText) -> Self {
+        let offset = self.len;
+        self.entities.extend(other.entities.into_iter().map(|entity| {
+            MessageEntity { offset: entity.offset + offset, ..entity }
+        }));
+        self.push(&other.text);
+        self
+    }
+
+    fn push(&mut self, text: &str) {
+        self.text.push_str(text);
+        self.len += text.encode_utf16().count();
+    }
+
+    /// The plain text.
+    #[must_use]
+    pub fn as_str(&self) -> &str {
+        &self.text
+    }
+
+    /// The entities of the text, with offsets in UTF-16 code units.
+    #[must_use]
+    pub fn entities(&self) -> &[MessageEntity] {
+        &self.entities
+    }
+
+    /// Returns the plain text and its entities.
+    #[must_use]
+    pub fn into_parts(self) -> (String, Vec<MessageEntity>) {
+        (self.text, self.entities)
+    }
+
+    /// Renders the text into markup of `parse_mode`.
+    ///
+    /// Entities which `parse_mode` can't express, such as underline in
+    /// [`ParseMode::Fanbook`] or code containing `` ` `` in the legacy
+    /// Markdown style, are rendered as plain text.
+    ///
+    /// [`ParseMode::Fanbook`]: crate::types::ParseMode::Fanbook
+    #[must_use]
+    pub fn render(&self, parse_mode: ParseMode) -> String {
+        let mut spans: Vec<_> = self
+            .entities
+            .iter()
+            .filter_map(|entity| {
+                let range = entity.range_in(&self.text)?;
+                Some(Span {
+                    start: range.start,
+                    end: range.end,
+                    kind: &entity.kind,
+                })
+            })
+            .collect();
+        // Outer entities go before the entities nested into them.
+        spans.sort_by(|a, b| a.start.cmp(&b.start).then(b.end.cmp(&a.end)));
+
+        render_spans(&self.text, 0, self.text.len(), &spans, parse_mode)
+    }
+}
+
+#[derive(Clone)]
+struct Span<'a> {
+    start: usize,
+    end: usize,
+    kind: &'a MessageEntityKind,
+}
+
+fn render_spans(
+    text: &str,
+    start: usize,
+    end: usize,
+    spans: &[Span<'_>],
+    parse_mode: ParseMode,
+) -> String {
+    let mut rendered = String::new();
+    let mut pos = start;
+    let mut i = 0;
+
+    while i < spans.len() {
+        let span = &spans[i];
+        // An entity crossing the end of the previous one is cut.
+        let span_start = span.start.max(pos);
+        let mut nested_end = i + 1;
+        while nested_end < spans.len() && spans[nested_end].start < span.end {
+            nested_end += 1;
+        }
+        let nested: Vec<_> = spans[i + 1..nested_end]
+            .iter()
+            .map(|nested| Span { end: nested.end.min(span.end), ..*nested })
+            .collect();
+
+        rendered.push_str(&escape(&text[pos..span_start], parse_mode));
+        let raw = &text[span_start..span.end];
+        let inner =
+            render_spans(text, span_start, span.end, &nested, parse_mode);
+        rendered.push_str(&wrap(span.kind, raw, &inner, parse_mode));
+
+        pos = span.end;
+        i = nested_end;
+    }
+
+    rendered.push_str(&escape(&text[pos..end], parse_mode));
+    rendered
+}
+
+#[allow(deprecated)]
+fn escape(text: &str, parse_mode: ParseMode) -> String {
+    match parse_mode {
+        ParseMode::HTML => html::escape(text),
+        ParseMode::MarkdownV2 => markdown::escape(text),
+        ParseMode::Markdown => text
+            .replace('_', r"\_")
+            .replace('*', r"\*")
+            .replace('`', r"\`")
+            .replace('[', r"\["),
+        ParseMode::Fanbook => fanbook::escape(text),
+    }
+}
+
+/// Wraps `inner`, the rendered `raw` text of an entity, into its markup.
+#[allow(deprecated)]
+fn wrap(
+    kind: &MessageEntityKind,
+    raw: &str,
+    inner: &str,
+    parse_mode: ParseMode,
+) -> String {
+    use MessageEntityKind::*;
+
+    match (parse_mode, kind) {
+        (ParseMode::HTML, Bold) => html::bold(inner),
+        (ParseMode::HTML, Italic) => html::italic(inner),
+        (ParseMode::HTML, Underline) => html::underline(inner),
+        (ParseMode::HTML, Strikethrough) => html::strike(inner),
+        (ParseMode::HTML, Code) => html::code_inline(raw),
+        (ParseMode::HTML, Pre { language: None }) => html::code_block(raw),
+        (ParseMode::HTML, Pre { language: Some(lang) }) => {
+            html::code_block_with_lang(raw, lang)
+        }
+        (ParseMode::HTML, TextLink { url }) => {
+            format!("<a href=\"{}\">{}</a>", html::escape(url), inner)
+        }
+        (ParseMode::HTML, TextMention { user }) => {
+            format!("<a href=\"tg://user?id={}\">{}</a>", user.id, inner)
+        }
+
+        (ParseMode::MarkdownV2, Bold) => markdown::bold(inner),
+        (ParseMode::MarkdownV2, Italic) => markdown::italic(inner),
+        (ParseMode::MarkdownV2, Underline) => markdown::underline(inner),
+        (ParseMode::MarkdownV2, Strikethrough) => markdown::strike(inner),
+        (ParseMode::MarkdownV2, Code) => markdown::code_inline(raw),
+        (ParseMode::MarkdownV2, Pre { language: None }) => {
+            markdown::code_block(raw)
+        }
+        (ParseMode::MarkdownV2, Pre { language: Some(lang) }) => {
+            markdown::code_block_with_lang(raw, lang)
+        }
+        (ParseMode::MarkdownV2, TextLink { url }) => markdown::link(url, inner),
+        (ParseMode::MarkdownV2, TextMention { user }) => {
+            markdown::user_mention(user.id, inner)
+        }
+
+        // The legacy style can't escape anything inside of entities, so the
+        // entities containing their delimiters are rendered as plain text.
+        (ParseMode::Markdown, _) if !fits_legacy_markdown(kind, raw) => {
+            inner.to_owned()
+        }
+        (ParseMode::Markdown, Bold) => format!("*{}*", raw),
+        (ParseMode::Markdown, Italic) => format!("_{}_", raw),
+        (ParseMode::Markdown, Code) => format!("`{}`", raw),
+        (ParseMode::Markdown, Pre { language }) => format!(
+            "```{}\n{}```",
+            language.as_deref().unwrap_or_default(),
+            raw
+        ),
+        (ParseMode::Markdown, TextLink { url }) => {
+            format!("[{}]({})", raw, url)
+        }
+        (ParseMode::Markdown, TextMention { user }) => {
+            format!("[{}](tg://user?id={})", raw, user.id)
+        }
+
+        (ParseMode::Fanbook, Bold) => fanbook::bold(inner),
+        (ParseMode::Fanbook, Italic) => fanbook::italic(inner),
+        (ParseMode::Fanbook, Code) => fanbook::code_inline(raw),
+        (ParseMode::Fanbook, Pre { language: None }) => {
+            fanbook::code_block(raw)
+        }
+        (ParseMode::Fanbook, Pre { language: Some(lang) }) => {
+            fanbook::code_block_with_lang(raw, lang)
+        }
+        (ParseMode::Fanbook, TextLink { url }) => {
+            format!("[{}]({})", inner, fanbook::escape_link_url(url))
+        }
+        (ParseMode::Fanbook, TextMention { user }) => {
+            fanbook::user_mention(user.id)
+        }
+
+        _ => inner.to_owned(),
+    }
+}
+
+/// Whether an entity with the text `raw` can be written in the legacy
+/// Markdown style, which can't escape its delimiters inside of entities.
+fn fits_legacy_markdown(kind: &MessageEntityKind, raw: &str) -> bool {
+    use MessageEntityKind::*;
+
+    match kind {
+        Bold => !raw.contains('*'),
+        Italic => !raw.contains('_'),
+        Code | Pre { .. } => !raw.contains('`'),
+        TextLink { url } => !raw.contains(']') && !url.contains(')'),
+        TextMention { .. } => !raw.contains(']'),
+        _ => true,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn user() -> User {
+        User { id: 42, first_name: "Ann".to_owned(), ..User::default() }
+    }
+
+    fn entity(
+        kind: MessageEntityKind,
+        offset: usize,
+        length: usize,
+    ) -> MessageEntity {
+        MessageEntity { kind, offset, length }
+    }
+
+    #[test]
+    fn utf16_offsets() {
+        let text = MessageText::new()
+            .plain("Привет 👋 ")
+            .bold("мир")
+            .plain(" ")
+            .mention("Ann", user());
+
+        assert_eq!(text.as_str(), "Привет 👋 мир Ann");
+        assert_eq!(
+            text.entities(),
+            &[
+                entity(MessageEntityKind::Bold, 10, 3),
+                entity(MessageEntityKind::TextMention { user: user() }, 14, 3),
+            ]
+        );
+    }
+
+    #[test]
+    fn nested_entities() {
+        let text = MessageText::new().plain("👋 ").nested(
+            MessageEntityKind::Bold,
+            MessageText::new().plain("see ").link("docs", "https://x.y/"),
+        );
+
+        assert_eq!(
+            text.entities(),
+            &[
+                entity(MessageEntityKind::Bold, 3, 8),
+                entity(
+                    MessageEntityKind::TextLink {
+                        url: "https://x.y/".to_owned()
+                    },
+                    7,
+                    4
+                ),
+            ]
+        );
+        assert_eq!(
+            text.render(ParseMode::HTML),
+            "👋 <b>see <a href=\"https://x.y/\">docs</a></b>"
+        );
+        assert_eq!(
+            text.render(ParseMode::MarkdownV2),
+            r"👋 *see [docs](https://x.y/)*"
+        );
+        assert_eq!(
+            text.render(ParseMode::Fanbook),
+            r"👋 **see [docs](https://x.y/)**"
+        );
+    }
+
+    #[test]
+    #[allow(deprecated)]
+    fn render_escapes() {
+        let text = MessageText::new()
+            .plain("a_b <c> ")
+            .italic("1*2")
+            .plain(" ")
+            .code("x<`y`>")
+            .plain(" ")
+            .mention("Ann", user());
+
+        assert_eq!(
+            text.render(ParseMode::HTML),
+            "a_b &lt;c&gt; <i>1*2</i> <code>x&lt;`y`&gt;</code> <a \
+             href=\"tg://user?id=42\">Ann</a>"
+        );
+        assert_eq!(
+            text.render(ParseMode::MarkdownV2),
+            r"a\_b <c\> _1\*2_ `x<\`y\`>` [Ann](tg://user?id=42)"
+        );
+        assert_eq!(
+            text.render(ParseMode::Markdown),
+            r"a\_b <c> _1*2_ x<\`y\`> [Ann](tg://user?id=42)"
+        );
+        assert_eq!(
+            text.render(ParseMode::Fanbook),
+            r"a\_b <c> _1\*2_ `x<\`y\`>` ${@!42}"
+        );
+    }
+
+    #[test]
+    fn code_blocks_and_unsupported_entities() {
+        let text = MessageText::new()
+            .underline("u")
+            .code_block_with_lang("fn main() {}", "rust");
+
+        assert_eq!(
+            text.render(ParseMode::Fanbook),
+            "u```rust\nfn main() {}\n```"
+        );
+        assert_eq!(
+            text.render(ParseMode::HTML),
+            "<u>u</u><pre><code class=\"language-rust\">fn main() \
+             {}</code></pre>"
+        );
+    }
+}
@@ -2,5 +2,8 @@
 
 pub mod client_from_env;
 pub mod command;
+pub mod fanbook;
 pub mod html;
+pub mod keyboard;
 pub mod markdown;
+pub mod message_text;